@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use mc_mod_meta::error::Error as MetaError;
 use thiserror::Error;
 
@@ -25,8 +27,31 @@ pub enum Error {
     #[error("The id or slug provided is not valid")]
     NotValidModrinthId,
 
-    #[error("Failed to parse cache file:  {}", err)]
-    FailedToParseEntryCache { err: serde_json::Error },
+    #[error(
+        "The downloaded file does not match the expected hash (expected {expected}, found {found})"
+    )]
+    ChecksumMismatch { expected: String, found: String },
+
+    #[error("Modrinth did not provide a hash to verify the downloaded file against")]
+    MissingChecksum,
+
+    #[error("The file for this entry could not be found in the mods folder")]
+    ModFileNotFound,
+
+    #[error("{0}")]
+    InvalidSetting(String),
+
+    #[error("{} was damaged and has been restored from its backup, recent changes may be lost", path.display())]
+    RestoredFromBackup { path: PathBuf },
+
+    #[error("{} was damaged and there was no backup to restore, it has been moved to {}", path.display(), damaged_path.display())]
+    DamagedFile {
+        path: PathBuf,
+        damaged_path: PathBuf,
+    },
+
+    #[error("{} was damaged and there was no backup to restore, it could not be moved aside and will be replaced", path.display())]
+    DamagedFileNotMoved { path: PathBuf },
 
     // Shared errors
     #[error("Encountered an I/O error while handling the file: {}", .0)]
@@ -38,6 +63,9 @@ pub enum Error {
     #[error("Unable to fetch {item}")]
     ReqwestError { inner: reqwest::Error, item: String },
 
+    #[error("Working offline and there is no cached copy of {item}")]
+    Offline { item: String },
+
     // Manifest metadata errors
     #[error("There was an error parsing the Forge manifest")]
     MetadataTomlError,
@@ -55,7 +83,7 @@ pub enum Error {
     FerinthNotSHA1Error,
     #[error("Could not parse url")]
     FerinthURLParseError,
-    #[error("Could not parse url")]
+    #[error("The rate limit was exceeded, try again in {0} seconds")]
     FerinthRatelimitExceeded(usize),
 
     // Daedalus errors
@@ -0,0 +1,443 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RETRY_AFTER},
+    Method, RequestBuilder, StatusCode, Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{instrument, warn};
+
+use crate::{
+    error::{self, LibResult},
+    persistence::http_cache::CachedResponse,
+    settings::CONF,
+};
+
+lazy_static! {
+    /// The client every request to an external service goes through
+    pub(crate) static ref HTTP: HttpClient = HttpClient::default();
+}
+
+/// How long connecting to a server can take, the whole request is limited by the settings
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The longest wait for a rate limit to reset that is honoured, a server asking for more fails the request instead
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5 * 60);
+
+/// The longest delay before retrying a request, however many attempts were made
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+const RATELIMIT_REMAINING: &str = "x-ratelimit-remaining";
+const RATELIMIT_RESET: &str = "x-ratelimit-reset";
+
+/// A wrapper around [reqwest::Client] that limits how many requests are in flight, waits out rate limits and retries requests that failed for transient reasons
+#[derive(Debug)]
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    /// The semaphore capping the concurrent requests, along with the amount of permits it was created with
+    limiter: Mutex<(usize, Arc<Semaphore>)>,
+    /// Set when a server reports its rate limit as exhausted, no request is sent before this point
+    blocked_until: Mutex<Option<Instant>>,
+}
+
+/// A value parsed from a response that may have come from the on-disk cache
+#[derive(Debug)]
+pub(crate) struct Cached<T> {
+    pub value: T,
+    /// Whether the value is older than it should be, because it could not be revalidated
+    pub stale: bool,
+}
+
+/// A successful response, which keeps its slot in the concurrency limit until it is dropped
+pub(crate) struct Response {
+    inner: reqwest::Response,
+    url: String,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        let permits = CONF.lock().max_concurrent_requests.max(1);
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .expect("The client is built from a fixed configuration");
+
+        Self {
+            client,
+            limiter: Mutex::new((permits, Arc::new(Semaphore::new(permits)))),
+            blocked_until: Mutex::new(None),
+        }
+    }
+}
+
+impl HttpClient {
+    pub(crate) fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    /// Fetch and parse the JSON document at `url`, going through the on-disk cache
+    ///
+    /// A cached copy younger than `ttl` is used without asking the server, an older one is revalidated using its ETag.
+    /// If the server cannot be reached or the app is offline, the cached copy is used regardless of its age and marked as stale.
+    #[instrument(skip(self, query))]
+    pub(crate) async fn get_json_cached<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+        ttl: Duration,
+    ) -> LibResult<Cached<T>> {
+        let url =
+            Url::parse_with_params(url, query).map_err(|_| error::Error::FerinthURLParseError)?;
+        let key = url.as_str();
+
+        let mut cached = CachedResponse::load(key);
+        let parse_cached = |cached: &CachedResponse, stale| -> LibResult<Cached<T>> {
+            Ok(Cached {
+                value: serde_json::from_str(&cached.body)?,
+                stale,
+            })
+        };
+
+        if let Some(cached) = &cached {
+            let expired = cached.age() >= ttl;
+
+            if !expired || CONF.lock().offline_mode {
+                return parse_cached(cached, expired);
+            }
+        }
+
+        let mut request = self.client.get(url.clone());
+
+        if let Some(etag) = cached.as_ref().and_then(|cached| cached.etag.as_ref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = match self.send(request, true).await {
+            Ok(response) => response,
+            Err(error) => {
+                return match &cached {
+                    Some(cached) if is_unavailable(&error) => {
+                        warn!(%error, "Using a stale cached response");
+                        parse_cached(cached, true)
+                    }
+                    _ => Err(error),
+                };
+            }
+        };
+
+        if let Some(cached) = cached.as_mut().filter(|_| response.is_not_modified()) {
+            cached.touch();
+            if let Err(error) = cached.save(key) {
+                warn!(%error, "Could not update the cached response");
+            }
+            return parse_cached(cached, false);
+        }
+
+        let etag = response.etag();
+        let bytes = response.bytes().await?;
+        let value = serde_json::from_slice(&bytes)?;
+
+        // Only text responses are cached, which every JSON document is
+        if let Ok(body) = String::from_utf8(bytes.to_vec()) {
+            if let Err(error) = CachedResponse::new(etag, body).save(key) {
+                warn!(%error, "Could not cache the response");
+            }
+        }
+
+        Ok(Cached {
+            value,
+            stale: false,
+        })
+    }
+
+    /// Send `body` as JSON to `url` and parse the response
+    ///
+    /// `idempotent` must only be set if sending the request more than once has no additional effects (Ex. a lookup)
+    pub(crate) async fn post_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &impl Serialize,
+        idempotent: bool,
+    ) -> LibResult<T> {
+        let request = self
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(body)?);
+
+        let bytes = self.send(request, idempotent).await?.bytes().await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Send a request, retrying it with an exponential backoff if it failed for reasons that may go away on their own
+    ///
+    /// Failures to connect, timeouts, server errors and exceeded rate limits are retried, anything else fails right away.
+    /// `GET` and `HEAD` requests are always considered idempotent
+    #[instrument(skip(self, request))]
+    pub(crate) async fn send(
+        &self,
+        request: RequestBuilder,
+        idempotent: bool,
+    ) -> LibResult<Response> {
+        let (max_retries, base_delay, timeout, respect_rate_limits) = {
+            let conf = CONF.lock();
+            (
+                conf.max_request_retries,
+                Duration::from_millis(conf.retry_base_delay_ms),
+                Duration::from_secs(conf.request_timeout_secs),
+                conf.respect_rate_limits,
+            )
+        };
+
+        let mut request = request
+            .build()
+            .map_err(|inner| error::Error::ReqwestError {
+                inner,
+                item: "Invalid request".to_string(),
+            })?;
+
+        let url = request.url().to_string();
+
+        // A stalled server would otherwise keep the request, and its slot, waiting forever
+        request.timeout_mut().get_or_insert(timeout);
+
+        if CONF.lock().offline_mode {
+            return Err(error::Error::Offline { item: url });
+        }
+
+        let to_error = |inner| error::Error::ReqwestError {
+            inner,
+            item: url.clone(),
+        };
+
+        let idempotent =
+            idempotent || request.method() == Method::GET || request.method() == Method::HEAD;
+        // Requests with a streamed body cannot be sent again
+        let max_retries = match request.try_clone() {
+            Some(_) if idempotent => max_retries,
+            _ => 0,
+        };
+
+        let mut request = Some(request);
+        let mut attempt = 0;
+
+        loop {
+            if respect_rate_limits {
+                self.wait_for_rate_limit().await;
+            }
+
+            let current = if attempt < max_retries {
+                request.as_ref().and_then(reqwest::Request::try_clone)
+            } else {
+                request.take()
+            }
+            .expect("The request is only consumed on its last attempt");
+
+            let permit = self.acquire().await;
+
+            let delay = match self.client.execute(current).await {
+                Ok(response) => {
+                    let reset = rate_limit_reset(response.headers());
+
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                        let wait = reset.unwrap_or_else(|| backoff(base_delay, attempt));
+
+                        if respect_rate_limits {
+                            self.block_for(wait)?;
+                        }
+
+                        if !respect_rate_limits || attempt >= max_retries {
+                            return Err(error::Error::FerinthRatelimitExceeded(
+                                wait.as_secs() as usize
+                            ));
+                        }
+
+                        wait
+                    } else {
+                        if respect_rate_limits && rate_limit_exhausted(response.headers()) {
+                            if let Some(reset) = reset {
+                                self.block_for(reset)?;
+                            }
+                        }
+
+                        if response.status().is_server_error() && attempt < max_retries {
+                            backoff(base_delay, attempt)
+                        } else {
+                            return match response.error_for_status() {
+                                Ok(inner) => Ok(Response {
+                                    inner,
+                                    url: url.clone(),
+                                    _permit: permit,
+                                }),
+                                Err(error) => Err(to_error(error)),
+                            };
+                        }
+                    }
+                }
+                Err(error) if attempt < max_retries && is_transient(&error) => {
+                    backoff(base_delay, attempt)
+                }
+                Err(error) => return Err(to_error(error)),
+            };
+
+            // Do not hold a slot while waiting
+            drop(permit);
+
+            attempt += 1;
+            warn!(%url, attempt, ?delay, "Retrying request");
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Wait for a free slot, picking up changes to the configured limit
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        let semaphore = {
+            let permits = CONF.lock().max_concurrent_requests.max(1);
+            let mut limiter = self.limiter.lock();
+
+            // Requests holding permits of the old semaphore finish normally, only new ones use the new limit
+            if limiter.0 != permits {
+                *limiter = (permits, Arc::new(Semaphore::new(permits)));
+            }
+
+            limiter.1.clone()
+        };
+
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("The semaphore is never closed")
+    }
+
+    async fn wait_for_rate_limit(&self) {
+        let blocked_until = *self.blocked_until.lock();
+
+        if let Some(until) = blocked_until {
+            let now = Instant::now();
+
+            if until > now {
+                tokio::time::sleep(until - now).await;
+            }
+        }
+    }
+
+    /// Hold off every request for `duration`, without shortening a block that is already in place
+    ///
+    /// Waits longer than [MAX_RATE_LIMIT_WAIT] are refused, as blocking every request for that long would look like a hang
+    fn block_for(&self, duration: Duration) -> LibResult<()> {
+        let until = Some(duration)
+            .filter(|duration| *duration <= MAX_RATE_LIMIT_WAIT)
+            .and_then(|duration| Instant::now().checked_add(duration))
+            .ok_or(error::Error::FerinthRatelimitExceeded(
+                duration.as_secs() as usize
+            ))?;
+        let mut blocked_until = self.blocked_until.lock();
+
+        if blocked_until.map_or(true, |current| current < until) {
+            *blocked_until = Some(until);
+        }
+
+        Ok(())
+    }
+}
+
+impl Response {
+    pub(crate) fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn is_not_modified(&self) -> bool {
+        self.inner.status() == StatusCode::NOT_MODIFIED
+    }
+
+    fn etag(&self) -> Option<String> {
+        let etag = self.inner.headers().get(ETAG)?;
+        etag.to_str().ok().map(ToString::to_string)
+    }
+
+    /// Read the next chunk of the body
+    pub(crate) async fn chunk(&mut self) -> LibResult<Option<Bytes>> {
+        let url = &self.url;
+
+        self.inner
+            .chunk()
+            .await
+            .map_err(|inner| error::Error::ReqwestError {
+                inner,
+                item: url.clone(),
+            })
+    }
+
+    /// Read the whole body
+    pub(crate) async fn bytes(self) -> LibResult<Bytes> {
+        let url = self.url;
+
+        self.inner
+            .bytes()
+            .await
+            .map_err(|inner| error::Error::ReqwestError { inner, item: url })
+    }
+}
+
+/// Errors that mean the server could not be used at the moment, as opposed to it rejecting the request
+fn is_unavailable(error: &error::Error) -> bool {
+    match error {
+        error::Error::ReqwestError { inner, .. } => inner
+            .status()
+            .map_or(true, |status| status.is_server_error()),
+        error::Error::FerinthRatelimitExceeded(_) | error::Error::Offline { .. } => true,
+        _ => false,
+    }
+}
+
+/// Errors that happen before a response is received, which are worth trying again
+///
+/// Other errors come from the request itself, which would fail the same way every time.
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect()
+}
+
+/// The time to wait until the rate limit resets, as reported by the server
+fn rate_limit_reset(headers: &HeaderMap) -> Option<Duration> {
+    [RETRY_AFTER.as_str(), RATELIMIT_RESET]
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok()?.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+fn rate_limit_exhausted(headers: &HeaderMap) -> bool {
+    headers
+        .get(RATELIMIT_REMAINING)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        == Some(0)
+}
+
+/// Exponential backoff with up to one base delay of random jitter, so that requests failing together do not retry together
+///
+/// The delay is capped at [MAX_BACKOFF], however large the base delay or the amount of attempts.
+fn backoff(base_delay: Duration, attempt: u32) -> Duration {
+    let base_delay = base_delay.min(MAX_BACKOFF);
+    let jitter_range = u64::try_from(base_delay.as_millis())
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
+    let jitter = RandomState::new().build_hasher().finish() % jitter_range;
+
+    base_delay
+        .checked_mul(2u32.saturating_pow(attempt.min(16)))
+        .unwrap_or(MAX_BACKOFF)
+        .saturating_add(Duration::from_millis(jitter))
+        .min(MAX_BACKOFF)
+}
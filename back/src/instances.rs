@@ -0,0 +1,615 @@
+//! Discovery of the game folders set up by the official launcher and by third party launchers
+
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+use tracing::{debug, instrument};
+
+use crate::{
+    mod_file::{ModFile, ModLoader},
+    paths,
+};
+
+/// A game folder found on the disk, along with what is known about the game it runs
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+    pub name: String,
+    pub launcher: Launcher,
+    /// The folder the game runs in, which holds the mods folder
+    pub game_dir: PathBuf,
+    pub game_version: Option<String>,
+    pub modloader: Option<ModLoader>,
+    pub loader_version: Option<String>,
+}
+
+impl Instance {
+    #[must_use]
+    pub fn mods_dir(&self) -> PathBuf {
+        self.game_dir.join("mods")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Launcher {
+    Official,
+    Prism,
+    PolyMc,
+    MultiMc,
+    AtLauncher,
+    GdLauncher,
+}
+
+impl fmt::Display for Launcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Launcher::Official => "Minecraft Launcher",
+            Launcher::Prism => "Prism Launcher",
+            Launcher::PolyMc => "PolyMC",
+            Launcher::MultiMc => "MultiMC",
+            Launcher::AtLauncher => "ATLauncher",
+            Launcher::GdLauncher => "GDLauncher",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The folders launchers keep their data in
+#[derive(Clone, Debug)]
+pub struct SearchRoots {
+    /// The folder of the official launcher (Ex. `~/.minecraft`)
+    pub minecraft_dir: PathBuf,
+    /// The folder applications store their data in (Ex. `~/.local/share`)
+    pub data_dir: PathBuf,
+    pub home_dir: PathBuf,
+}
+
+impl SearchRoots {
+    /// The folders of the current user
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            minecraft_dir: paths::default_minecraft_dir(),
+            data_dir: paths::data_dir(),
+            home_dir: paths::home_dir(),
+        }
+    }
+}
+
+/// Find every instance of the launchers that are installed for the current user
+#[must_use]
+pub fn detect_instances() -> Vec<Instance> {
+    detect_instances_in(&SearchRoots::current())
+}
+
+/// Find every instance under `roots`, each game folder is only reported once
+#[instrument]
+#[must_use]
+pub fn detect_instances_in(roots: &SearchRoots) -> Vec<Instance> {
+    let mut instances = official_instances(&roots.minecraft_dir);
+
+    let data_dir = &roots.data_dir;
+    let multimc_roots = [
+        (Launcher::Prism, data_dir.join("PrismLauncher")),
+        (
+            Launcher::Prism,
+            roots
+                .home_dir
+                .join(".var/app/org.prismlauncher.PrismLauncher/data/PrismLauncher"),
+        ),
+        (Launcher::PolyMc, data_dir.join("PolyMC")),
+        (Launcher::MultiMc, data_dir.join("multimc")),
+        (Launcher::MultiMc, data_dir.join("MultiMC")),
+    ];
+
+    for (launcher, root) in multimc_roots {
+        instances.extend(multimc_instances(launcher, &root.join("instances")));
+    }
+
+    for root in [
+        data_dir.join("ATLauncher"),
+        roots.home_dir.join("ATLauncher"),
+    ] {
+        instances.extend(atlauncher_instances(&root.join("instances")));
+    }
+
+    instances.extend(gdlauncher_instances(
+        &data_dir.join("gdlauncher_next").join("instances"),
+    ));
+
+    // Some launchers can be found through more than one path (Ex. on case insensitive file systems)
+    let mut seen = HashSet::new();
+    instances.retain(|instance| {
+        let game_dir = instance
+            .game_dir
+            .canonicalize()
+            .unwrap_or_else(|_| instance.game_dir.clone());
+        seen.insert(game_dir)
+    });
+
+    debug!(amount = instances.len(), "Detected instances");
+
+    instances
+}
+
+/// The game a mods folder is loaded by, as far as it can be told
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InferredGame {
+    pub game_version: Option<String>,
+    pub modloader: Option<ModLoader>,
+    /// What the versions were read from, to show the user
+    pub source: Option<String>,
+}
+
+/// Tell which game loads `mods_dir`, from the instance around it or failing that from what the mods in it require
+///
+/// `releases` are the ids of the game releases, newest first, that the mods' requirements are checked against.
+#[instrument(skip(mod_list, releases))]
+#[must_use]
+pub fn infer_game(
+    mods_dir: &Path,
+    roots: &SearchRoots,
+    mod_list: &[ModFile],
+    releases: &[String],
+) -> InferredGame {
+    let mut inferred = match instance_of(mods_dir, roots) {
+        Some(instance) => InferredGame {
+            game_version: instance.game_version,
+            modloader: instance.modloader,
+            source: Some(format!(
+                "the {} instance {}",
+                instance.launcher, instance.name
+            )),
+        },
+        None => InferredGame::default(),
+    };
+
+    if inferred.modloader.is_none() {
+        inferred.modloader = modloader_of_mods(mod_list);
+    }
+
+    if inferred.game_version.is_none() {
+        inferred.game_version = game_version_of_mods(mod_list, releases);
+
+        if inferred.game_version.is_some() {
+            inferred.source = Some("the game versions the installed mods require".to_string());
+        }
+    }
+
+    debug!(?inferred, "Inferred the game of the mods folder");
+
+    inferred
+}
+
+/// Find the instance a mods folder belongs to
+fn instance_of(mods_dir: &Path, roots: &SearchRoots) -> Option<Instance> {
+    let game_dir = mods_dir.parent()?;
+
+    if let Some(instance_dir) = game_dir.parent() {
+        if instance_dir.join("mmc-pack.json").is_file() {
+            return read_multimc_instance(multimc_launcher(instance_dir), instance_dir);
+        }
+    }
+
+    if let Some(instance) =
+        read_atlauncher_instance(game_dir).or_else(|| read_gdlauncher_instance(game_dir))
+    {
+        return Some(instance);
+    }
+
+    let same_dir = |a: &Path, b: &Path| {
+        a == b || matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
+    };
+
+    if let Some(instance) = official_instances(&roots.minecraft_dir)
+        .into_iter()
+        .find(|instance| same_dir(&instance.game_dir, game_dir) && instance.game_version.is_some())
+    {
+        return Some(instance);
+    }
+
+    // Without a profile, the version that was installed last is the most likely to be played
+    let versions_dir = game_dir.join("versions");
+    let mut versions: Vec<(std::time::SystemTime, String)> = fs::read_dir(&versions_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let modified = entry
+                .path()
+                .join(format!("{}.json", id))
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()?;
+
+            Some((modified, id))
+        })
+        .collect();
+    versions.sort();
+
+    let (_, id) = versions.pop()?;
+    let (game_version, modloader, loader_version) = resolve_version(&versions_dir, &id)?;
+
+    Some(Instance {
+        name: id,
+        launcher: Launcher::Official,
+        game_dir: game_dir.to_path_buf(),
+        game_version,
+        modloader,
+        loader_version,
+    })
+}
+
+/// Tell which fork of MultiMC an instance belongs to from the folder it is in
+fn multimc_launcher(instance_dir: &Path) -> Launcher {
+    let path = instance_dir.to_string_lossy();
+
+    if path.contains("PrismLauncher") {
+        Launcher::Prism
+    } else if path.contains("PolyMC") {
+        Launcher::PolyMc
+    } else {
+        Launcher::MultiMc
+    }
+}
+
+/// The loader most files only run on, if there is one
+fn modloader_of_mods(mod_list: &[ModFile]) -> Option<ModLoader> {
+    let count = |modloader| {
+        mod_list
+            .iter()
+            .filter(|mod_file| mod_file.data.loaders == [modloader])
+            .count()
+    };
+
+    let fabric = count(ModLoader::Fabric);
+    let forge = count(ModLoader::Forge);
+
+    match fabric.cmp(&forge) {
+        std::cmp::Ordering::Greater => Some(ModLoader::Fabric),
+        std::cmp::Ordering::Less => Some(ModLoader::Forge),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// The newest release that the most mods declare to work on
+fn game_version_of_mods(mod_list: &[ModFile], releases: &[String]) -> Option<String> {
+    let requirements: Vec<_> = mod_list
+        .iter()
+        .flat_map(|mod_file| &mod_file.entries)
+        .filter_map(|entry| entry.game_versions.as_ref())
+        .collect();
+
+    let mut best: Option<(usize, &String)> = None;
+
+    for release in releases {
+        let matching = requirements
+            .iter()
+            .filter(|requirement| requirement.matches(release) == Some(true))
+            .count();
+
+        if matching > best.map_or(0, |(best_matching, _)| best_matching) {
+            best = Some((matching, release));
+        }
+    }
+
+    best.map(|(_, release)| release.clone())
+}
+
+/// The profiles of `launcher_profiles.json`, profiles sharing a game folder are reported once using the most recently played one
+fn official_instances(minecraft_dir: &Path) -> Vec<Instance> {
+    let launcher_profiles = match read_json(&minecraft_dir.join("launcher_profiles.json")) {
+        Some(launcher_profiles) => launcher_profiles,
+        None => return Vec::new(),
+    };
+
+    let mut profiles: Vec<&Value> = match launcher_profiles["profiles"].as_object() {
+        Some(profiles) => profiles.values().collect(),
+        None => return Vec::new(),
+    };
+
+    // The timestamps are in ISO 8601, so they sort as text
+    profiles.sort_by(|a, b| b["lastUsed"].as_str().cmp(&a["lastUsed"].as_str()));
+
+    profiles
+        .into_iter()
+        .map(|profile| {
+            let version_id = profile["lastVersionId"].as_str().unwrap_or_default();
+            let (game_version, modloader, loader_version) =
+                resolve_version(&minecraft_dir.join("versions"), version_id)
+                    .unwrap_or_else(|| parse_version_id(version_id));
+
+            let name = match profile["name"].as_str() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                // The default profiles have no name
+                _ => match profile["type"].as_str() {
+                    Some("latest-snapshot") => "Latest snapshot".to_string(),
+                    _ => "Latest release".to_string(),
+                },
+            };
+
+            Instance {
+                name,
+                launcher: Launcher::Official,
+                game_dir: profile["gameDir"]
+                    .as_str()
+                    .map_or_else(|| minecraft_dir.to_path_buf(), PathBuf::from),
+                game_version,
+                modloader,
+                loader_version,
+            }
+        })
+        .collect()
+}
+
+/// Read the versions of an installed version of the official launcher, following the versions it inherits from
+///
+/// Loaders install their own version, which inherits from the game version it runs on and lists the loader in its libraries.
+fn resolve_version(
+    versions_dir: &Path,
+    version_id: &str,
+) -> Option<(Option<String>, Option<ModLoader>, Option<String>)> {
+    let mut version = read_json(
+        &versions_dir
+            .join(version_id)
+            .join(format!("{}.json", version_id)),
+    )?;
+    let mut modloader = None;
+    let mut loader_version = None;
+
+    // A broken installation could inherit from itself, so the chain is cut short at some point
+    for _ in 0..8 {
+        if modloader.is_none() {
+            if let Some((found_modloader, found_version)) = loader_library(&version) {
+                modloader = Some(found_modloader);
+                loader_version = Some(found_version);
+            }
+        }
+
+        let parent = match version["inheritsFrom"].as_str() {
+            Some(parent) => parent.to_string(),
+            None => break,
+        };
+
+        match read_json(&versions_dir.join(&parent).join(format!("{}.json", parent))) {
+            Some(parent_version) => version = parent_version,
+            // The parent is named after the game version, even if it was not downloaded yet
+            None => return Some((Some(parent), modloader, loader_version)),
+        }
+    }
+
+    let game_version = version["id"].as_str().map(ToString::to_string);
+
+    Some((game_version, modloader, loader_version))
+}
+
+/// Find the loader in the libraries of a version (Ex. `net.fabricmc:fabric-loader:0.14.9`)
+fn loader_library(version: &Value) -> Option<(ModLoader, String)> {
+    version["libraries"]
+        .as_array()?
+        .iter()
+        .filter_map(|library| library["name"].as_str())
+        .find_map(|name| {
+            let mut parts = name.split(':');
+            let artifact = (parts.next()?, parts.next()?);
+            let library_version = parts.next()?;
+
+            match artifact {
+                ("net.fabricmc", "fabric-loader") | ("org.quiltmc", "quilt-loader") => {
+                    Some((ModLoader::Fabric, library_version.to_string()))
+                }
+                // Forge versions start with the game version (Ex. `1.19.2-43.1.1`)
+                ("net.minecraftforge", "forge" | "fmlloader") => Some((
+                    ModLoader::Forge,
+                    library_version
+                        .split_once('-')
+                        .map_or(library_version, |(_, version)| version)
+                        .to_string(),
+                )),
+                _ => None,
+            }
+        })
+}
+
+/// Get what can be known from the id of a version of the official launcher (Ex. `fabric-loader-0.14.9-1.19.2` or `1.19.2-forge-43.1.1`)
+///
+/// The ids of the latest release and snapshot profiles say nothing about the version they point to.
+/// Quilt is reported as Fabric, as it runs Fabric mods.
+fn parse_version_id(version_id: &str) -> (Option<String>, Option<ModLoader>, Option<String>) {
+    if version_id.is_empty() || version_id.starts_with("latest-") {
+        return (None, None, None);
+    }
+
+    for prefix in ["fabric-loader-", "quilt-loader-"] {
+        if let Some(rest) = version_id.strip_prefix(prefix) {
+            if let Some((loader_version, game_version)) = rest.split_once('-') {
+                return (
+                    Some(game_version.to_string()),
+                    Some(ModLoader::Fabric),
+                    Some(loader_version.to_string()),
+                );
+            }
+        }
+    }
+
+    if let Some((game_version, loader_version)) = version_id.split_once("-forge") {
+        // Older versions repeat the game version (Ex. `1.12.2-forge1.12.2-14.23.5.2859`)
+        let loader_version = loader_version.trim_start_matches('-');
+        let loader_version = loader_version
+            .strip_prefix(&format!("{}-", game_version))
+            .unwrap_or(loader_version);
+
+        return (
+            Some(game_version.to_string()),
+            Some(ModLoader::Forge),
+            Some(loader_version.to_string()),
+        );
+    }
+
+    (Some(version_id.to_string()), None, None)
+}
+
+/// Instances of MultiMC and its forks, which describe them with `instance.cfg` and `mmc-pack.json`
+fn multimc_instances(launcher: Launcher, instances_dir: &Path) -> Vec<Instance> {
+    instance_dirs(instances_dir)
+        .into_iter()
+        .filter_map(|dir| read_multimc_instance(launcher, &dir))
+        .collect()
+}
+
+fn read_multimc_instance(launcher: Launcher, dir: &Path) -> Option<Instance> {
+    let config = fs::read_to_string(dir.join("instance.cfg")).ok()?;
+    let name = ini_value(&config, "name").unwrap_or_else(|| dir_name(dir));
+
+    let mut instance = Instance {
+        name,
+        launcher,
+        game_dir: multimc_game_dir(dir),
+        game_version: None,
+        modloader: None,
+        loader_version: None,
+    };
+
+    if let Some(pack) = read_json(&dir.join("mmc-pack.json")) {
+        apply_mmc_pack(&mut instance, &pack);
+    }
+
+    Some(instance)
+}
+
+/// Fill in the versions listed in the components of a `mmc-pack.json`
+fn apply_mmc_pack(instance: &mut Instance, pack: &Value) {
+    let components = match pack["components"].as_array() {
+        Some(components) => components,
+        None => return,
+    };
+
+    for component in components {
+        let version = component["version"].as_str().map(ToString::to_string);
+
+        match component["uid"].as_str().unwrap_or_default() {
+            "net.minecraft" => instance.game_version = version,
+            "net.fabricmc.fabric-loader" | "org.quiltmc.quilt-loader" => {
+                instance.modloader = Some(ModLoader::Fabric);
+                instance.loader_version = version;
+            }
+            "net.minecraftforge" => {
+                instance.modloader = Some(ModLoader::Forge);
+                instance.loader_version = version;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Instances can use either `.minecraft` or `minecraft`, depending on the version of the launcher that created them
+fn multimc_game_dir(instance_dir: &Path) -> PathBuf {
+    let hidden = instance_dir.join(".minecraft");
+    let visible = instance_dir.join("minecraft");
+
+    if !hidden.exists() && visible.exists() {
+        visible
+    } else {
+        hidden
+    }
+}
+
+/// Instances of ATLauncher, described by `instance.json`
+fn atlauncher_instances(instances_dir: &Path) -> Vec<Instance> {
+    instance_dirs(instances_dir)
+        .into_iter()
+        .filter_map(|dir| read_atlauncher_instance(&dir))
+        .collect()
+}
+
+fn read_atlauncher_instance(dir: &Path) -> Option<Instance> {
+    let config = read_json(&dir.join("instance.json"))?;
+    let launcher = &config["launcher"];
+
+    let modloader = match launcher["loaderVersion"]["type"].as_str() {
+        Some("Fabric" | "Quilt") => Some(ModLoader::Fabric),
+        Some("Forge") => Some(ModLoader::Forge),
+        _ => None,
+    };
+
+    Some(Instance {
+        name: launcher["name"]
+            .as_str()
+            .map_or_else(|| dir_name(dir), ToString::to_string),
+        launcher: Launcher::AtLauncher,
+        game_dir: dir.to_path_buf(),
+        game_version: config["id"].as_str().map(ToString::to_string),
+        modloader,
+        loader_version: launcher["loaderVersion"]["version"]
+            .as_str()
+            .map(ToString::to_string),
+    })
+}
+
+/// Instances of GDLauncher, described by `config.json`
+fn gdlauncher_instances(instances_dir: &Path) -> Vec<Instance> {
+    instance_dirs(instances_dir)
+        .into_iter()
+        .filter_map(|dir| read_gdlauncher_instance(&dir))
+        .collect()
+}
+
+fn read_gdlauncher_instance(dir: &Path) -> Option<Instance> {
+    let config = read_json(&dir.join("config.json"))?;
+    let loader = config.get("loader")?;
+
+    let modloader = match loader["loaderType"].as_str() {
+        Some("fabric") => Some(ModLoader::Fabric),
+        Some("forge") => Some(ModLoader::Forge),
+        _ => None,
+    };
+
+    Some(Instance {
+        name: dir_name(dir),
+        launcher: Launcher::GdLauncher,
+        game_dir: dir.to_path_buf(),
+        game_version: loader["mcVersion"].as_str().map(ToString::to_string),
+        modloader,
+        loader_version: loader["loaderVersion"].as_str().map(ToString::to_string),
+    })
+}
+
+fn instance_dirs(instances_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = match fs::read_dir(instances_dir) {
+        Ok(read_dir) => read_dir
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+
+    dirs.sort();
+    dirs
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let contents = fs::read(path).ok()?;
+    serde_json::from_slice(&contents).ok()
+}
+
+/// Find a key of the top level section of an INI file
+fn ini_value(contents: &str, key: &str) -> Option<String> {
+    contents
+        .lines()
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .find_map(|line| {
+            let (line_key, value) = line.split_once('=')?;
+            (line_key.trim() == key).then(|| value.trim().to_string())
+        })
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
@@ -1,55 +1,101 @@
 use std::{
+    collections::{HashMap, HashSet},
     fmt::Debug,
     fs,
-    io::Write,
-    path::Path,
+    path::{Path, PathBuf},
     process,
     sync::Arc,
+    time::Duration,
 };
 
+use crate::{
+    instances::Instance,
+    messages::BackendError,
+    settings::{Profile, SettingsBuilder, CONF},
+};
 use bytes::Bytes;
 use crossbeam_channel::{Receiver, Sender};
-use futures::future;
-use messages::{ToBackend, ToFrontend};
-use mod_file::{FileState, ModFileData, ModLoader};
-use mod_file::{Hashes, ModFile};
+use daedalus::minecraft::VersionManifest;
+use error::{Error, LibResult};
+use futures::{
+    future::{AbortHandle, Abortable},
+    stream, StreamExt,
+};
+use http::{Cached, HTTP};
+use messages::{
+    BackendRequest, BackendResponse, JobPhase, PlannedUpdate, RequestId, SearchQuery, ToBackend,
+    ToFrontend, UpdateSummary,
+};
+use mod_file::{details::FileDetails, Hashes, ModFile};
+use mod_file::{
+    duplicates::{self, DuplicateGroup},
+    install, FileState, ModFileData, ModLoader, Pin,
+};
 use modrinth::Modrinth;
-use parking_lot::{Mutex, Once};
-use persistence::cache::CacheStorage;
-use tracing::{debug, error, info, instrument};
-use crate::{messages::BackendError, settings::CONF};
+use parking_lot::Mutex;
+use persistence::{
+    cache::CacheStorage,
+    versions::{StoredVersion, VersionStorage},
+};
+use responder::Responder;
+use tokio::runtime::Runtime;
+use tracing::{debug, error, info, instrument, warn};
 
 mod error;
+mod http;
+pub mod instances;
 pub mod messages;
-mod paths;
 pub mod mod_file;
 mod modrinth;
+mod paths;
 mod persistence;
+mod progress;
+mod responder;
 
-pub use persistence::settings;
 pub use daedalus::minecraft::Version as GameVersion;
-pub use ferinth::structures::version_structs::VersionType;
-
+pub use ferinth::structures::version_structs::{
+    Version as ModrinthVersion, VersionFile, VersionType,
+};
+pub use paths::CONFIG_DIR_VAR;
+pub use persistence::settings;
+pub use persistence::versions;
 
-static LOG_CHANNEL_CLOSED: Once = Once::new();
+/// How long the cached version manifest is used without revalidating it
+const VERSION_MANIFEST_TTL: Duration = Duration::from_secs(60 * 60);
 
-lazy_static::lazy_static!(
-    static ref MODRINTH: Modrinth = Modrinth::default();
-);
+static MODRINTH: Modrinth = Modrinth;
 
+#[derive(Clone)]
 pub struct Back {
+    state: Arc<Mutex<BackState>>,
+    /// The jobs that are currently running, by the id of the request that started them
+    jobs: Arc<Mutex<HashMap<RequestId, AbortHandle>>>,
+    back_tx: Sender<BackendResponse>,
+    front_rx: Receiver<BackendRequest>,
+    egui_context: eframe::egui::Context,
+}
+
+/// The data shared between jobs, the lock is never held across an await point
+#[derive(Debug, Default)]
+struct BackState {
     mod_list: Vec<ModFile>,
     cache: CacheStorage,
-    back_tx: Sender<ToFrontend>,
-    front_rx: Receiver<ToBackend>,
-    egui_context: eframe::egui::Context,
+    versions: VersionStorage,
+    /// The ids of the game releases, newest first, once the version manifest was loaded
+    releases: Vec<String>,
+    /// The game version selected in the frontend, which the files are checked against before being sent
+    game_version: Option<String>,
+    /// The loader selected in the frontend, whose manifests are the ones checked for missing dependencies
+    modloader: Option<ModLoader>,
+    /// The mods that more than one file contains, found when scanning the folder
+    duplicates: Vec<DuplicateGroup>,
 }
 
 impl Debug for Back {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Back")
-            .field("mod_list", &self.mod_list)
-            .field("cache", &self.cache)
+            .field("state", &self.state)
+            .field("jobs", &self.jobs)
             .field("back_tx", &self.back_tx)
             .field("front_rx", &self.front_rx)
             // .field("egui_context", &self.egui_context)
@@ -60,14 +106,13 @@ impl Debug for Back {
 impl Back {
     #[instrument(skip(egui_context), level = "trace")]
     pub fn new(
-        back_tx: Sender<ToFrontend>,
-        front_rx: Receiver<ToBackend>,
+        back_tx: Sender<BackendResponse>,
+        front_rx: Receiver<BackendRequest>,
         egui_context: eframe::egui::Context,
     ) -> Self {
-
         Self {
-            mod_list: Vec::default(),
-            cache: CacheStorage::default(),
+            state: Arc::default(),
+            jobs: Arc::default(),
             back_tx,
             front_rx,
             egui_context,
@@ -75,104 +120,777 @@ impl Back {
     }
 
     #[instrument(skip(self))]
-    pub fn init(&mut self) {
+    pub fn init(&self) {
         info!("Initializing backend");
 
-        let rt = tokio::runtime::Runtime::new().unwrap();
+        let rt = Runtime::new().unwrap();
         debug!("Runtime created");
 
-        rt.block_on(async {
-            loop {
-                match self.front_rx.recv() {
-                    Ok(message) => {
-                        match message {
-                            ToBackend::Startup => {
-                                self.load_list_cache();
+        loop {
+            match self.front_rx.recv() {
+                Ok(BackendRequest { id, message }) => {
+                    let responder = Responder::new(id, &self.back_tx, &self.egui_context);
 
-                                self.scan_folder();
+                    match message {
+                        ToBackend::Shutdown => {
+                            self.shutdown(&responder);
+                        }
 
-                                self.transfer_list_data_to_current(&self.cache.get_cache().clone());
-                                
-                                self.send_list();
+                        ToBackend::Cancel { id } => {
+                            self.cancel(id);
+                        }
 
-                                self.get_version_metadata().await;
-                            }
+                        // Changes to the list are applied right away and in order, as the frontend relies on it.
+                        // This includes rescanning, rolling back and switching folders: they only touch the disk while holding the state,
+                        // so as jobs they could neither be cancelled nor let anything else through, their progress is reported all the same
+                        ToBackend::ScanFolder => {
+                            self.state.lock().refresh_list(&responder);
+                        }
 
-                            ToBackend::Shutdown => {
-                                if let Err(error) = CONF.lock().save_config() {
-                                    error!(%error, "Could not save config");
-                                }
-                                
-                                self.save_list_cache();
-                                process::exit(0);
-                            }
+                        ToBackend::UpdateBackendList { mod_list } => {
+                            self.state.lock().mod_list = mod_list;
+                        }
 
-                            ToBackend::ScanFolder => {
-                                self.scan_folder();
+                        ToBackend::RollbackMod { mod_file, sha1 } => {
+                            self.state.lock().rollback_mod(&responder, &mod_file, &sha1);
+                        }
 
-                                self.send_list();
-                            }
+                        ToBackend::PlanUpdateAll => {
+                            self.state.lock().plan_update_all(&responder);
+                        }
 
-                            ToBackend::UpdateBackendList { mod_list } => {
-                                self.mod_list = mod_list;
-                            },
+                        ToBackend::SetPin { sha1, pin } => {
+                            self.state.lock().set_pin(&responder, &sha1, pin);
+                        }
 
+                        ToBackend::CheckCompatibility {
+                            game_version,
+                            modloader,
+                        } => {
+                            self.state.lock().check_compatibility(
+                                &responder,
+                                game_version,
+                                modloader,
+                            );
+                        }
 
-                            ToBackend::CheckForUpdates { game_version } => {
-                                self.scan_folder();
+                        ToBackend::SetReleaseChannel { sha1, channel } => {
+                            self.state
+                                .lock()
+                                .set_release_channel(&responder, &sha1, channel);
+                        }
 
-                                self.check_for_updates(game_version).await;
+                        ToBackend::DeleteMod { path } => {
+                            self.state.lock().delete_mod(&responder, &path);
+                        }
 
-                                self.send_list();
-                            }
+                        ToBackend::UpdateSettings { settings } => {
+                            self.state.lock().update_settings(&responder, *settings);
+                        }
 
-                            ToBackend::GetVersionMetadata => {
-                               self.get_version_metadata().await;
-                            }
+                        ToBackend::AdoptInstance { instance } => {
+                            self.state.lock().adopt_instance(&responder, &instance);
+                        }
 
-                            ToBackend::AddMod {
-                                modrinth_id,
-                                game_version,
-                                modloader,
-                            } => {
-                                self.add_mod(modrinth_id, game_version, modloader).await;
-                            }
-                            ToBackend::UpdateMod { mod_file } => {
-                                self.update_mod(*mod_file).await;
-                            }
-
-                            ToBackend::DeleteMod { path } => {
-                                self.delete_mod(&path);
-                            },
+                        message => {
+                            self.spawn_job(&rt, responder, message);
                         }
-                        self.egui_context.request_repaint();
                     }
-                    Err(error) => {
-                        // As the only reason this will error out is if the channel is closed (sender is dropped) a one time log of the error is enough
-                       LOG_CHANNEL_CLOSED.call_once(|| {
-                           error!(%error, "There was an error when receiving a message from the frontend:");
-                       });
-                    }
-                };
+                    self.egui_context.request_repaint();
+                }
+                Err(error) => {
+                    // The only reason this will error out is if the channel is closed (sender is dropped), so there is nothing left to do
+                    error!(%error, "There was an error when receiving a message from the frontend:");
+                    break;
+                }
+            };
+        }
+    }
+
+    /// Run a message that can take a while on the runtime, so that other messages can be handled in the meantime
+    #[instrument(skip(self, rt, message))]
+    fn spawn_job(&self, rt: &Runtime, mut responder: Responder, message: ToBackend) {
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        self.jobs.lock().insert(responder.id(), abort_handle);
+        responder.allow_cancel();
+
+        let back = self.clone();
+        rt.spawn(async move {
+            let job = Abortable::new(back.run_job(&responder, message), abort_registration);
+
+            if job.await.is_err() {
+                info!(id = responder.id(), "Job cancelled");
+
+                responder.send(ToFrontend::Cancelled).unwrap();
             }
+
+            back.jobs.lock().remove(&responder.id());
         });
     }
 
+    async fn run_job(&self, responder: &Responder, message: ToBackend) {
+        match message {
+            ToBackend::Startup { icon_resize_size } => {
+                self.startup(responder, icon_resize_size).await;
+            }
+
+            ToBackend::CheckForUpdates {
+                game_version,
+                modloader,
+            } => {
+                {
+                    let mut state = self.state.lock();
+                    state.game_version = Some(game_version.clone());
+                    state.modloader = Some(modloader);
+                    state.scan_folder(responder);
+                }
+
+                self.check_for_updates(responder, game_version, modloader)
+                    .await;
+
+                self.state.lock().send_list(responder);
+            }
+
+            ToBackend::GetVersionMetadata => {
+                self.get_version_metadata(responder).await;
+            }
+
+            ToBackend::AddMod {
+                modrinth_id,
+                game_version,
+                modloader,
+            } => {
+                self.add_mod(responder, modrinth_id, game_version, modloader)
+                    .await;
+            }
+
+            ToBackend::UpdateMod { mod_file } => {
+                self.update_mod(responder, *mod_file).await;
+            }
+
+            ToBackend::ListVersions { mod_file } => {
+                self.list_versions(responder, *mod_file).await;
+            }
+
+            ToBackend::InstallVersion {
+                mod_file,
+                version_id,
+                version_file,
+            } => {
+                self.install_version(responder, *mod_file, version_id, version_file)
+                    .await;
+            }
+
+            ToBackend::GetChangelogs { mod_file } => {
+                self.get_changelogs(responder, *mod_file).await;
+            }
+
+            ToBackend::GetModDetails { mod_file } => {
+                self.get_mod_details(responder, &mod_file);
+            }
+
+            ToBackend::UpdateAll { plan } => {
+                self.update_all(responder, plan).await;
+            }
+
+            ToBackend::SearchMods { query } => {
+                self.search_mods(responder, query).await;
+            }
+
+            ToBackend::GetSearchCategories => {
+                self.get_search_categories(responder).await;
+            }
+
+            ToBackend::DetectInstances => {
+                let instances = instances::detect_instances();
+
+                responder
+                    .send(ToFrontend::InstancesDetected { instances })
+                    .unwrap();
+            }
+
+            ToBackend::Shutdown
+            | ToBackend::Cancel { .. }
+            | ToBackend::ScanFolder
+            | ToBackend::UpdateBackendList { .. }
+            | ToBackend::RollbackMod { .. }
+            | ToBackend::PlanUpdateAll
+            | ToBackend::SetPin { .. }
+            | ToBackend::CheckCompatibility { .. }
+            | ToBackend::SetReleaseChannel { .. }
+            | ToBackend::UpdateSettings { .. }
+            | ToBackend::AdoptInstance { .. }
+            | ToBackend::DeleteMod { .. } => unreachable!("Handled without starting a job"),
+        }
+    }
+
+    #[instrument(skip(self))]
+    fn cancel(&self, id: RequestId) {
+        match self.jobs.lock().remove(&id) {
+            Some(abort_handle) => {
+                info!("Cancelling job");
+                abort_handle.abort();
+            }
+            None => debug!("The job already finished"),
+        }
+    }
+
+    /// The settings are saved as they change, so only the list is left to save
+    #[instrument(skip(self))]
+    fn shutdown(&self, responder: &Responder) {
+        self.state.lock().save_list_cache(responder);
+        process::exit(0);
+    }
+
+    #[instrument(skip(self))]
+    async fn startup(&self, responder: &Responder, icon_resize_size: u32) {
+        {
+            let mut state = self.state.lock();
+
+            let current = SettingsBuilder::from_current();
+            if current.icon_resize_size != icon_resize_size {
+                state.update_settings(responder, current.icon_resize_size(icon_resize_size));
+            }
+
+            state.load_versions(responder);
+
+            state.load_folder(responder);
+
+            responder
+                .send(ToFrontend::SettingsChanged {
+                    settings: Box::new(SettingsBuilder::from_current()),
+                })
+                .unwrap();
+
+            state.send_list(responder);
+        }
+
+        self.get_version_metadata(responder).await;
+    }
+
+    #[instrument(skip(self))]
+    async fn check_for_updates(
+        &self,
+        responder: &Responder,
+        game_version: String,
+        modloader: ModLoader,
+    ) {
+        // Work on a copy so that other jobs can use the list while the checks are running
+        let mut mod_list = self.state.lock().mod_list.clone();
+
+        let progress = responder.progress();
+        progress.set_phase(JobPhase::CheckingForUpdates, mod_list.len());
+
+        let errors = MODRINTH
+            .check_all_for_updates(&mut mod_list, &game_version, modloader, progress)
+            .await;
+
+        // The projects only add to what is shown, so failing to get them is not worth reporting
+        if let Err(error) = MODRINTH.fetch_projects(&mut mod_list).await {
+            warn!(%error, "Could not fetch the Modrinth projects of the mods");
+        }
+
+        for error in errors {
+            error!(%error, "Failed to check for updates");
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new("Failed to check for updates", error),
+                })
+                .unwrap();
+        }
+
+        // Files that were removed in the meantime are not brought back
+        self.state.lock().transfer_list_data_to_current(&mod_list);
+    }
+
+    #[instrument(skip(self, mod_file))]
+    async fn update_mod(&self, responder: &Responder, mod_file: ModFile) {
+        info!(
+            path = ?mod_file.path,
+            sha1 = %mod_file.hashes.sha1,
+            "Updating mod"
+        );
+
+        if let Err(error) = self.try_update_mod(responder, &mod_file).await {
+            error!(path = ?mod_file.path, "Could not update mod");
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new(
+                        format!("Could not update: {}", mod_file.path.display()),
+                        error,
+                    ),
+                })
+                .unwrap();
+        }
+    }
+
+    async fn try_update_mod(&self, responder: &Responder, mod_file: &ModFile) -> LibResult<()> {
+        let progress = responder.progress();
+        progress.set_phase(JobPhase::Downloading, 1);
+        progress.add_bytes_total(download_size(mod_file));
+
+        let bytes = MODRINTH.update_mod(&mod_file.data, progress).await?;
+        debug!("Update downloaded");
+
+        progress.set_phase(JobPhase::Installing, 1);
+
+        let mut state = self.state.lock();
+
+        state.apply_update(responder, mod_file, &bytes)?;
+        progress.item_done();
+
+        state.refresh_list(responder);
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, plan), fields(amount = plan.len()))]
+    async fn update_all(&self, responder: &Responder, plan: Vec<PlannedUpdate>) {
+        info!("Updating mods in bulk");
+
+        let max_downloads = CONF.lock().max_concurrent_downloads.max(1);
+
+        let progress = responder.progress();
+        progress.set_phase(JobPhase::Downloading, plan.len());
+        progress.add_bytes_total(plan.iter().map(|planned| planned.size as u64).sum());
+
+        let downloads: Vec<(PlannedUpdate, LibResult<Bytes>)> = stream::iter(plan)
+            .map(|planned| async move {
+                let result = MODRINTH.update_mod(&planned.mod_file.data, progress).await;
+                progress.item_done();
+                (planned, result)
+            })
+            .buffer_unordered(max_downloads)
+            .collect()
+            .await;
+
+        debug!("Updates downloaded");
+
+        progress.set_phase(JobPhase::Installing, downloads.len());
+
+        let mut summary = UpdateSummary::default();
+        let mut state = self.state.lock();
+
+        // Apply every update before refreshing the list once
+        for (planned, result) in downloads {
+            let result =
+                result.and_then(|bytes| state.apply_update(responder, &planned.mod_file, &bytes));
+            progress.item_done();
+
+            match result {
+                Ok(()) => summary.updated.push(planned.display_name),
+                Err(error) => {
+                    error!(name = %planned.display_name, "Could not update mod");
+
+                    summary.failed.push(BackendError::new(
+                        format!("Could not update: {}", planned.display_name),
+                        error,
+                    ));
+                }
+            }
+        }
+
+        state.refresh_list(responder);
+
+        responder
+            .send(ToFrontend::UpdateSummary { summary })
+            .unwrap();
+    }
+
+    #[instrument(skip(self, mod_file))]
+    async fn list_versions(&self, responder: &Responder, mod_file: ModFile) {
+        let result = match &mod_file.data.sources.modrinth {
+            Some(modrinth_data) => MODRINTH.list_all_versions(&modrinth_data.id).await,
+            None => Err(Error::NoModrinthDataError),
+        };
+
+        match result {
+            Ok(versions) => responder
+                .send(ToFrontend::VersionList {
+                    mod_file: Box::new(mod_file),
+                    versions,
+                })
+                .unwrap(),
+            Err(error) => {
+                error!(path = ?mod_file.path, "Could not list the versions of the mod");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            format!(
+                                "Could not list the versions of: {}",
+                                mod_file.path.display()
+                            ),
+                            error,
+                        ),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn search_mods(&self, responder: &Responder, query: SearchQuery) {
+        match MODRINTH.search(&query).await {
+            Ok(page) => responder.send(ToFrontend::SearchResults { page }).unwrap(),
+            Err(error) => {
+                error!("Could not search for mods");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            format!("Could not search for: {}", query.text),
+                            error,
+                        ),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn get_search_categories(&self, responder: &Responder) {
+        match MODRINTH.list_categories().await {
+            Ok(categories) => responder
+                .send(ToFrontend::SearchCategories { categories })
+                .unwrap(),
+            Err(error) => {
+                error!("Could not get the search categories");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new("Could not get the search categories", error),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    #[instrument(skip(self, mod_file))]
+    async fn get_changelogs(&self, responder: &Responder, mod_file: ModFile) {
+        match MODRINTH
+            .get_changelogs(&mod_file.data, &mod_file.hashes)
+            .await
+        {
+            Ok(changelogs) => responder
+                .send(ToFrontend::Changelogs {
+                    mod_file: Box::new(mod_file),
+                    changelogs,
+                })
+                .unwrap(),
+            Err(error) => {
+                error!(path = ?mod_file.path, "Could not get the changelogs of the mod");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            format!(
+                                "Could not get the changelogs of: {}",
+                                mod_file.path.display()
+                            ),
+                            error,
+                        ),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    #[instrument(skip(self, mod_file))]
+    fn get_mod_details(&self, responder: &Responder, mod_file: &ModFile) {
+        match FileDetails::read(mod_file) {
+            Ok(details) => responder
+                .send(ToFrontend::ModDetails {
+                    details: Box::new(details),
+                })
+                .unwrap(),
+            Err(error) => {
+                error!(path = ?mod_file.path, "Could not read the details of the mod");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            format!("Could not read the details of: {}", mod_file.path.display()),
+                            error,
+                        ),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Replace a file with a specific version file, regardless of it being newer or older
+    #[instrument(skip(self, mod_file, version_file))]
+    async fn install_version(
+        &self,
+        responder: &Responder,
+        mut mod_file: ModFile,
+        version_id: String,
+        version_file: VersionFile,
+    ) {
+        if let Some(modrinth_data) = &mut mod_file.data.sources.modrinth {
+            modrinth_data.latest_valid_version = Some(version_file);
+            modrinth_data.latest_version_id = Some(version_id);
+            modrinth_data.latest_version_number = None;
+        }
+
+        self.update_mod(responder, mod_file).await;
+    }
+
+    #[instrument(skip(self))]
+    async fn add_mod(
+        &self,
+        responder: &Responder,
+        modrinth_id: String,
+        game_version: String,
+        modloader: ModLoader,
+    ) {
+        let progress = responder.progress();
+        progress.set_phase(JobPhase::FetchingMetadata, 1);
+
+        let result = match MODRINTH
+            .create_mod_file(modrinth_id.clone(), game_version, modloader, progress)
+            .await
+        {
+            Ok((mod_data, bytes)) => {
+                progress.set_phase(JobPhase::Installing, 1);
+                self.state
+                    .lock()
+                    .install_mod_file(responder, &mod_data, &bytes, None)
+            }
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(()) => self.state.lock().refresh_list(responder),
+            Err(error) => {
+                error!(%modrinth_id, "Could not add mod");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            format!("Could not add mod: {}", modrinth_id),
+                            error,
+                        ),
+                    })
+                    .unwrap();
+            }
+        };
+    }
+
+    #[instrument(skip(self))]
+    async fn get_version_metadata(&self, responder: &Responder) {
+        let url = CONF.lock().active_version_manifest_url();
+        let result = HTTP
+            .get_json_cached::<VersionManifest>(&url, &[], VERSION_MANIFEST_TTL)
+            .await;
+
+        match result {
+            Ok(Cached { value, stale }) => {
+                let releases = value
+                    .versions
+                    .iter()
+                    .filter(|version| version.type_ == daedalus::minecraft::VersionType::Release)
+                    .map(|version| version.id.clone())
+                    .collect();
+
+                responder
+                    .send(ToFrontend::SetVersionMetadata {
+                        manifest: value,
+                        stale,
+                    })
+                    .unwrap();
+
+                let mut state = self.state.lock();
+                state.releases = releases;
+                state.send_inferred_game(responder);
+            }
+            Err(error) => {
+                error!("There was an error getting the version metadata");
+                responder
+                    .send(ToFrontend::VersionMetadataUnavailable)
+                    .unwrap();
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            "There was an error getting the version metadata",
+                            error,
+                        ),
+                    })
+                    .unwrap();
+
+                // What is on the disk can still be read without the manifest
+                self.state.lock().send_inferred_game(responder);
+            }
+        };
+    }
+}
+
+impl BackState {
+    /// Build the list of the mods folder in the settings, using its cache
+    #[instrument(skip(self))]
+    fn load_folder(&mut self, responder: &Responder) {
+        self.load_list_cache(responder);
+
+        self.scan_folder(responder);
+
+        let cache = self.cache.get_cache().clone();
+        self.transfer_list_data_to_current(&cache);
+    }
+
+    #[instrument(skip(self, settings))]
+    fn update_settings(&mut self, responder: &Responder, settings: SettingsBuilder) {
+        let current = SettingsBuilder::from_current();
+
+        if let Err(error) = settings.validate(&current) {
+            error!(%error, "Rejected a settings change");
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new("Could not change the settings", error),
+                })
+                .unwrap();
+
+            // Let the frontend undo the change it already shows
+            responder
+                .send(ToFrontend::SettingsChanged {
+                    settings: Box::new(current),
+                })
+                .unwrap();
+
+            return;
+        }
+
+        let folder_changed = settings.mod_folder_path != current.mod_folder_path;
+
+        if folder_changed {
+            // The list still belongs to the old folder
+            self.save_list_cache(responder);
+        }
+
+        let changed = settings != current;
+        settings.clone().apply();
+
+        if !changed {
+            debug!("The settings did not change, nothing to save");
+        } else if let Err(error) = settings.save_config() {
+            error!(%error, "Could not save the settings");
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new("Could not save the settings", error),
+                })
+                .unwrap();
+        }
+
+        responder
+            .send(ToFrontend::SettingsChanged {
+                settings: Box::new(settings),
+            })
+            .unwrap();
+
+        if folder_changed {
+            info!("The mods folder changed, reloading the list");
+
+            // Nothing about the files of the old folder applies to the new one
+            self.mod_list.clear();
+            self.duplicates.clear();
+
+            self.load_folder(responder);
+            self.send_list(responder);
+            self.send_inferred_game(responder);
+        }
+    }
+
+    /// Tell the frontend which game the mods folder is most likely loaded by, so that it can be selected by default
+    #[instrument(skip(self))]
+    fn send_inferred_game(&self, responder: &Responder) {
+        let mods_dir = CONF.lock().mod_folder_path.clone();
+        let game = instances::infer_game(
+            &mods_dir,
+            &instances::SearchRoots::current(),
+            &self.mod_list,
+            &self.releases,
+        );
+
+        responder.send(ToFrontend::InferredGame { game }).unwrap();
+    }
+
+    #[instrument(skip(self))]
+    fn adopt_instance(&mut self, responder: &Responder, instance: &Instance) {
+        let mods_dir = instance.mods_dir();
+
+        // Instances that were never played with mods do not have the folder yet
+        if let Err(error) = fs::create_dir_all(&mods_dir) {
+            error!(%error, "Could not create the mods folder of the instance");
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new(
+                        format!("Could not create {}", mods_dir.display()),
+                        error,
+                    ),
+                })
+                .unwrap();
+
+            return;
+        }
+
+        let settings = SettingsBuilder::from_current()
+            .profile(Profile::from(instance))
+            .mod_folder_path(mods_dir);
+
+        self.update_settings(responder, settings);
+    }
+
     #[instrument(skip(self))]
-    fn load_list_cache(&mut self) {
+    fn load_list_cache(&mut self, responder: &Responder) {
         if let Err(error) = self.cache.load_list_cache() {
             error!(%error, "Could not load cache");
 
-            self.back_tx
+            responder
                 .send(ToFrontend::BackendError {
                     error: BackendError::new(format!("Could not load cache: {}", error), error),
                 })
                 .unwrap();
         }
+
+        Self::send_load_warnings(responder);
+    }
+
+    #[instrument(skip(self))]
+    fn load_versions(&mut self, responder: &Responder) {
+        if let Err(error) = self.versions.load_versions() {
+            error!(%error, "Could not load the version history");
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new(
+                        format!("Could not load the version history: {}", error),
+                        error,
+                    ),
+                })
+                .unwrap();
+        }
+
+        Self::send_load_warnings(responder);
+    }
+
+    /// Tell the frontend about the damaged files found since the last time, the settings included
+    fn send_load_warnings(responder: &Responder) {
+        for warning in persistence::take_load_warnings() {
+            responder
+                .send(ToFrontend::BackendWarning {
+                    warning: BackendError::new("Some saved data was damaged", warning),
+                })
+                .unwrap();
+        }
     }
 
     #[instrument(skip(self))]
-    fn save_list_cache(&mut self) {
+    fn save_list_cache(&mut self, responder: &Responder) {
         let mut mod_list_clone = self.mod_list.clone();
 
         // Transfer the data for existing entries
@@ -195,7 +913,7 @@ impl Back {
         if let Err(error) = self.cache.save_list_cache() {
             error!(%error, "Could not save cache");
 
-            self.back_tx
+            responder
                 .send(ToFrontend::BackendError {
                     error: BackendError::new(format!("Could not save cache: {}", error), error),
                 })
@@ -204,181 +922,394 @@ impl Back {
     }
 
     #[instrument(skip(self))]
-    fn send_list(&mut self) {
+    fn send_list(&mut self, responder: &Responder) {
         info!(length = self.mod_list.len(), "Sending the mods list");
 
-        self.back_tx
+        // Files may have been added or checked on Modrinth since the last time
+        let available_ids: HashSet<String> = self
+            .mod_list
+            .iter()
+            .flat_map(ModFile::available_ids)
+            .map(ToString::to_string)
+            .collect();
+        let available_ids: HashSet<&str> = available_ids.iter().map(String::as_str).collect();
+
+        for mod_file in &mut self.mod_list {
+            mod_file.apply_compatibility(
+                self.game_version.as_deref(),
+                self.modloader,
+                &available_ids,
+            );
+        }
+
+        responder
             .send(ToFrontend::UpdateModList {
                 mod_list: self.mod_list.clone(),
             })
             .unwrap();
+
+        responder
+            .send(ToFrontend::DuplicateMods {
+                groups: self.duplicates.clone(),
+            })
+            .unwrap();
+
+        responder
+            .send(ToFrontend::UpdateVersionHistory {
+                history: self.versions.get_versions().clone(),
+            })
+            .unwrap();
+    }
+
+    #[instrument(skip(self))]
+    fn check_compatibility(
+        &mut self,
+        responder: &Responder,
+        game_version: String,
+        modloader: ModLoader,
+    ) {
+        self.game_version = Some(game_version);
+        self.modloader = Some(modloader);
+
+        self.send_list(responder);
     }
 
     #[instrument(skip(self))]
-    fn scan_folder(&mut self) {
+    fn scan_folder(&mut self, responder: &Responder) {
         let mod_folder_path = CONF.lock().mod_folder_path.clone();
         info!(folder_path = %mod_folder_path.display(), "Scanning the mods folder");
 
+        let paths = match Self::list_folder(&mod_folder_path) {
+            Ok(paths) => paths,
+            Err(error) => {
+                error!(%error, "Could not read the mods folder");
+
+                responder
+                    .send(ToFrontend::BackendError {
+                        error: BackendError::new(
+                            format!("Could not read: {}", mod_folder_path.display()),
+                            error,
+                        ),
+                    })
+                    .unwrap();
+                return;
+            }
+        };
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| self.is_relevant_file(path))
+            .collect();
+
         let old_list = self.mod_list.clone();
         self.mod_list.clear();
 
-        let read_dir = fs::read_dir(&mod_folder_path).unwrap();
+        let progress = responder.progress();
+        progress.set_phase(JobPhase::Scanning, paths.len());
 
-        'file_loop: for file_entry in read_dir {
-            let path = file_entry.unwrap().path();
+        for path in paths {
+            debug!(?path, "Parsing file");
 
-            if self.is_relevant_file(&path) {
-                debug!(?path, "Parsing file");
-
-                match ModFile::from_path(path.clone()) {
-                    Ok(entry) => self.mod_list.push(entry),
-                    Err(error) => {
-                        // In the case of an error the mod list will be cleared
-                        self.mod_list.clear();
-
-                        error!(path = %path.display(), "Could not parse mod");
-
-                        self.back_tx
-                            .send(ToFrontend::BackendError {
-                                error: BackendError::new(
-                                    format!("Could not parse: {}", path.display()),
-                                    error,
-                                ),
-                            })
-                            .unwrap();
-                        break 'file_loop;
-                    }
+            match ModFile::from_path(path.clone()) {
+                Ok(entry) => {
+                    self.mod_list.push(entry);
+                    progress.item_done();
+                }
+                Err(error) => {
+                    // In the case of an error the mod list will be cleared
+                    self.mod_list.clear();
+
+                    error!(path = %path.display(), "Could not parse mod");
+
+                    responder
+                        .send(ToFrontend::BackendError {
+                            error: BackendError::new(
+                                format!("Could not parse: {}", path.display()),
+                                error,
+                            ),
+                        })
+                        .unwrap();
+                    break;
                 }
             }
         }
 
         self.transfer_list_data_to_current(&old_list);
+        modrinth::load_cached_icons(&mut self.mod_list);
+
+        self.duplicates = duplicates::find_duplicates(&self.mod_list);
+    }
+
+    /// The paths of every entry in `folder`
+    fn list_folder(folder: &Path) -> LibResult<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+
+        for file_entry in fs::read_dir(folder)? {
+            paths.push(file_entry?.path());
+        }
+
+        Ok(paths)
+    }
+
+    /// Replace the file of `mod_file` with the downloaded update
+    fn apply_update(
+        &mut self,
+        responder: &Responder,
+        mod_file: &ModFile,
+        bytes: &Bytes,
+    ) -> LibResult<()> {
+        let mut old_file = mod_file.clone();
+        old_file.path = self
+            .find_file_by_hash(&mod_file.hashes.sha1)?
+            .ok_or(Error::ModFileNotFound)?;
+
+        self.install_mod_file(responder, &mod_file.data, bytes, Some(&old_file))
     }
 
+    /// Collect every outdated file that is not pinned for the user to review
     #[instrument(skip(self))]
-    async fn check_for_updates(&mut self, game_version: String) {
-        let back_tx = &self.back_tx;
-        let mod_list_m = self
+    fn plan_update_all(&mut self, responder: &Responder) {
+        let plan: Vec<PlannedUpdate> = self
             .mod_list
-            .iter_mut()
-            .map(|file| Arc::new(Mutex::new(file)));
+            .iter()
+            .filter(|mod_file| {
+                mod_file.data.state == FileState::Outdated
+                    && mod_file.data.pin != Some(Pin::CurrentFile)
+            })
+            .filter_map(PlannedUpdate::new)
+            .collect();
 
-        let mut handles = Vec::new();
+        info!(amount = plan.len(), "Planned updates");
 
-        for file in mod_list_m {
-            let game_version = game_version.clone();
-            handles.push(async move {
-                let mut file = file.lock();
+        responder.send(ToFrontend::UpdatePlan { plan }).unwrap();
+    }
 
-                let hashes = file.hashes.clone();
+    /// Install the downloaded file for `mod_data` into the mods folder, replacing `replaces` if provided
+    #[instrument(skip(self, mod_data, bytes, replaces))]
+    fn install_mod_file(
+        &mut self,
+        responder: &Responder,
+        mod_data: &ModFileData,
+        bytes: &Bytes,
+        replaces: Option<&ModFile>,
+    ) -> LibResult<()> {
+        info!("Installing a new mod file");
+
+        let filename = &mod_data
+            .sources
+            .modrinth
+            .as_ref()
+            .ok_or(Error::NoModrinthDataError)?
+            .latest_valid_version
+            .as_ref()
+            .ok_or(Error::InvalidLatestVersionError)?
+            .filename;
+
+        let path = CONF.lock().mod_folder_path.join(filename);
+
+        let (mut new_file, backup) =
+            install::install_file(bytes, &path, replaces.map(|file| file.path.as_path()))?;
+
+        if let (Some(old_file), Some(backup)) = (replaces, backup) {
+            self.store_version(responder, old_file, &backup);
+        }
 
-                if let Err(error) = MODRINTH
-                    .check_for_updates(&mut file.data, Some(&hashes), &game_version)
-                    .await
-                {
-                    error!("Failed to check for updates");
+        // Ensure the data for the entry is kept
+        new_file.data.sources.modrinth = mod_data.sources.modrinth.clone();
+        new_file.data.sourced_from = mod_data.sourced_from;
 
-                    back_tx
-                        .send(ToFrontend::BackendError {
-                            error: BackendError::new("Failed to check for updates", error),
-                        })
-                        .unwrap();
-                };
-            });
+        self.replace_in_list(replaces, new_file);
+
+        Ok(())
+    }
+
+    /// Keep a replaced file around in the version history
+    #[instrument(skip(self, old_file))]
+    fn store_version(&mut self, responder: &Responder, old_file: &ModFile, backup: &Path) {
+        if let Err(error) = self.versions.store(old_file, backup) {
+            error!(%error, "Could not store the previous version");
+
+            install::discard(backup);
+
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new(
+                        "Could not keep the previous version of the mod",
+                        error,
+                    ),
+                })
+                .unwrap();
         }
+    }
 
-        future::join_all(handles).await;
+    /// Swap the entry for a replaced file with its new version
+    fn replace_in_list(&mut self, replaces: Option<&ModFile>, new_file: ModFile) {
+        if let Some(old_file) = replaces {
+            self.mod_list
+                .retain(|mod_file| mod_file.hashes.sha1 != old_file.hashes.sha1);
+        }
+        self.mod_list.push(new_file);
+    }
+
+    /// Re-scan the folder, keeping the data of the files in the list, and send the result
+    fn refresh_list(&mut self, responder: &Responder) {
+        self.scan_folder(responder);
+
+        self.send_list(responder);
     }
 
     #[instrument(skip(self, mod_file))]
-    async fn update_mod(&mut self, mod_file: ModFile) {
+    fn rollback_mod(&mut self, responder: &Responder, mod_file: &ModFile, sha1: &str) {
         info!(
             path = ?mod_file.path,
-            sha1 = %mod_file.hashes.sha1,
-            "Updating mod"
+            to = %sha1,
+            "Rolling back mod"
         );
 
-        if let Ok(bytes) = MODRINTH.update_mod(&mod_file.data).await {
-            debug!("Update downloaded");
-            let read_dir = fs::read_dir(&CONF.lock().mod_folder_path).unwrap();
-
-            'file_loop: for file_entry in read_dir {
-                let path = file_entry.unwrap().path();
+        if let Err(error) = self.try_rollback_mod(responder, mod_file, sha1) {
+            error!(path = ?mod_file.path, "Could not roll back mod");
 
-                if self.is_relevant_file(&path) {
-                    let mut file = fs::File::open(&path).unwrap();
-
-                    let hashes = Hashes::get_hashes_from_file(&mut file).unwrap();
+            responder
+                .send(ToFrontend::BackendError {
+                    error: BackendError::new(
+                        format!("Could not roll back: {}", mod_file.path.display()),
+                        error,
+                    ),
+                })
+                .unwrap();
+        }
+    }
 
-                    // We found the file the mod_file belongs to
-                    if mod_file.hashes.sha1 == hashes.sha1 {
-                        std::fs::remove_file(path).unwrap();
+    fn try_rollback_mod(
+        &mut self,
+        responder: &Responder,
+        mod_file: &ModFile,
+        sha1: &str,
+    ) -> LibResult<()> {
+        let mut old_file = mod_file.clone();
+        old_file.path = self
+            .find_file_by_hash(&mod_file.hashes.sha1)?
+            .ok_or(Error::ModFileNotFound)?;
+
+        // The restored file is no longer a previous version, which is saved before the folder is touched
+        let (index, stored) = self.versions.take(sha1).ok_or(Error::ModFileNotFound)?;
+
+        let progress = responder.progress();
+        progress.set_phase(JobPhase::Installing, 1);
+
+        let restored = self
+            .versions
+            .save_versions()
+            .and_then(|()| Self::restore_version(&stored, &old_file));
+
+        let (mut new_file, backup) = match restored {
+            Ok(restored) => restored,
+            Err(error) => {
+                self.versions.put_back(index, stored);
 
-                        self.create_mod_file(&mod_file.data, &bytes);
-                        break 'file_loop;
-                    }
+                if let Err(error) = self.versions.save_versions() {
+                    error!(%error, "Could not save the version history after a failed rollback");
                 }
+
+                return Err(error);
             }
         };
+        progress.item_done();
+
+        // The file it replaced is now a previous version instead
+        if let Some(backup) = backup {
+            self.store_version(responder, &old_file, &backup);
+        }
+        install::discard(&stored.path());
+
+        new_file.data.sources = stored.data.sources;
+        new_file.data.sourced_from = stored.data.sourced_from;
+        // Keep the next update check from offering the version that was just rolled back from
+        new_file.data.pin = Some(Pin::CurrentFile);
+        new_file.data.state = FileState::Pinned;
+
+        self.replace_in_list(Some(&old_file), new_file);
+
+        self.refresh_list(responder);
+
+        Ok(())
     }
 
-    #[instrument(skip(self))]
-    async fn add_mod(&mut self, modrinth_id: String, game_version: String, modloader: ModLoader) {
-        match MODRINTH
-            .create_mod_file(modrinth_id.clone(), game_version, modloader)
-            .await
-        {
-            Ok((mod_data, bytes)) => {
-                self.create_mod_file(&mod_data, &bytes);
-            }
-            Err(error) => {
-                error!(%modrinth_id, "Could not add mod");
+    /// Install the jar of a stored version in place of `old_file`
+    fn restore_version(
+        stored: &StoredVersion,
+        old_file: &ModFile,
+    ) -> LibResult<(ModFile, Option<PathBuf>)> {
+        let bytes = fs::read(stored.path())?;
+        let path = CONF.lock().mod_folder_path.join(&stored.file_name);
 
-                self.back_tx
-                    .send(ToFrontend::BackendError {
-                        error: BackendError::new(
-                            format!("Could not add mod: {}", modrinth_id),
-                            error,
-                        ),
-                    })
-                    .unwrap();
-            }
-        };
+        install::install_file(&bytes, &path, Some(&old_file.path))
     }
 
-    #[instrument(skip(self, mod_data, bytes))]
-    fn create_mod_file(&mut self, mod_data: &ModFileData, bytes: &Bytes) {
-        info!("Creating a new mod file");
-        // The data is guaranteed to exist, unwrapping here is fine
-        let path = CONF.lock().mod_folder_path.join(
-            &mod_data
-                .sources
-                .modrinth
-                .as_ref()
-                .unwrap()
-                .latest_valid_version
-                .as_ref()
-                .unwrap()
-                .filename,
-        );
+    /// Look for the file in the mods folder with the given hash
+    fn find_file_by_hash(&self, sha1: &str) -> LibResult<Option<PathBuf>> {
+        let read_dir = fs::read_dir(&CONF.lock().mod_folder_path)?;
 
-        let mut new_mod_file = fs::File::create(&path).unwrap();
+        for file_entry in read_dir {
+            let path = file_entry?.path();
 
-        new_mod_file.write_all(bytes).unwrap();
+            if self.is_relevant_file(&path) {
+                let mut file = fs::File::open(&path)?;
 
-        let mut new_file = ModFile::from_path(path).unwrap();
+                let hashes = Hashes::get_hashes_from_file(&mut file)?;
 
-        // Ensure the data for the entry is kept
-        new_file.data.sources.modrinth = mod_data.sources.modrinth.clone();
-        new_file.data.sourced_from = mod_data.sourced_from;
+                if hashes.sha1 == sha1 {
+                    return Ok(Some(path));
+                }
+            }
+        }
 
-        self.scan_folder();
+        Ok(None)
+    }
 
-        self.send_list();
+    #[instrument(skip(self))]
+    fn set_pin(&mut self, responder: &Responder, sha1: &str, pin: Option<Pin>) {
+        info!(?pin, "Changing the pin of a mod");
+
+        for mod_file in &mut self.mod_list {
+            if mod_file.hashes.sha1 == sha1 {
+                // The state will be refined on the next update check
+                mod_file.data.state = if pin.is_some() {
+                    FileState::Pinned
+                } else {
+                    FileState::Current
+                };
+                mod_file.data.pin = pin.clone();
+            }
+        }
+
+        self.save_list_cache(responder);
+
+        self.send_list(responder);
     }
 
     #[instrument(skip(self))]
-    fn delete_mod(&mut self, path: &Path) {
+    fn set_release_channel(
+        &mut self,
+        responder: &Responder,
+        sha1: &str,
+        channel: Option<VersionType>,
+    ) {
+        info!(?channel, "Changing the release channel of a mod");
+
+        // The state will be refined on the next update check
+        for mod_file in &mut self.mod_list {
+            if mod_file.hashes.sha1 == sha1 {
+                mod_file.data.release_channel = channel;
+            }
+        }
+
+        self.save_list_cache(responder);
+
+        self.send_list(responder);
+    }
+
+    #[instrument(skip(self))]
+    fn delete_mod(&mut self, responder: &Responder, path: &Path) {
         info!(
             file_path = %path.display(),
             "Deleting file"
@@ -390,38 +1321,18 @@ impl Back {
                 "Could not delete file"
             );
 
-            self.back_tx
+            responder
                 .send(ToFrontend::BackendError {
                     error: BackendError::new("Failed to delete the file", error),
                 })
                 .unwrap();
         } else {
             self.mod_list.retain(|mod_file| mod_file.path != path);
+            self.duplicates = duplicates::find_duplicates(&self.mod_list);
 
             debug!("File deleted successfully");
 
-            self.send_list();
-        };
-    }
-
-    #[instrument(skip(self))]
-    async fn get_version_metadata(&self) {
-        match daedalus::minecraft::fetch_version_manifest(None).await {
-            Ok(manifest) => self
-                .back_tx
-                .send(ToFrontend::SetVersionMetadata { manifest })
-                .unwrap(),
-            Err(error) => {
-                error!("There was an error getting the version metadata");
-                self.back_tx
-                    .send(ToFrontend::BackendError {
-                        error: BackendError::new(
-                            "There was an error getting the version metadata",
-                            error,
-                        ),
-                    })
-                    .unwrap();
-            }
+            self.send_list(responder);
         };
     }
 
@@ -457,9 +1368,13 @@ impl Back {
             if !filtered_old.is_empty() {
                 mod_file.data.sourced_from = filtered_old[0].data.sourced_from;
                 mod_file.data.sources = filtered_old[0].data.sources.clone();
+                mod_file.data.pin = filtered_old[0].data.pin.clone();
+                mod_file.data.release_channel = filtered_old[0].data.release_channel;
 
                 if keep_state {
                     mod_file.data.state = filtered_old[0].data.state;
+                    mod_file.data.state_reason = filtered_old[0].data.state_reason.clone();
+                    mod_file.data.overridden_state = filtered_old[0].data.overridden_state;
                 } else {
                     mod_file.data.state = FileState::Current;
                 }
@@ -467,3 +1382,14 @@ impl Back {
         }
     }
 }
+
+/// The size of the update available for a file, zero if there is none
+fn download_size(mod_file: &ModFile) -> u64 {
+    mod_file
+        .data
+        .sources
+        .modrinth
+        .as_ref()
+        .and_then(|modrinth_data| modrinth_data.latest_valid_version.as_ref())
+        .map_or(0, |version_file| version_file.size as u64)
+}
@@ -1,14 +1,58 @@
-use std::path::PathBuf;
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use daedalus::minecraft::VersionManifest;
+use ferinth::structures::version_structs::{Version, VersionFile, VersionType};
 
 use crate::{
     error,
-    mod_file::{ModFile, ModLoader},
+    instances::{InferredGame, Instance},
+    mod_file::{details::FileDetails, duplicates::DuplicateGroup, ModFile, ModLoader, Pin},
+    settings::SettingsBuilder,
+    versions::StoredVersion,
 };
 
+pub type RequestId = u64;
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A message for the backend, along with an id to tell apart the responses it causes
+pub struct BackendRequest {
+    pub id: RequestId,
+    pub message: ToBackend,
+}
+
+impl BackendRequest {
+    #[must_use]
+    pub fn new(message: ToBackend) -> Self {
+        Self {
+            id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+            message,
+        }
+    }
+}
+
+impl From<ToBackend> for BackendRequest {
+    fn from(message: ToBackend) -> Self {
+        Self::new(message)
+    }
+}
+
+/// A message for the frontend, tagged with the id of the request that caused it
+pub struct BackendResponse {
+    pub id: RequestId,
+    pub message: ToFrontend,
+}
+
 pub enum ToBackend {
-    Startup,
+    /// Load the mods folder and send everything the frontend shows, along with the settings in use
+    Startup {
+        /// The size the frontend shows icons at, applied before any is loaded
+        icon_resize_size: u32,
+    },
 
     Shutdown,
 
@@ -20,6 +64,8 @@ pub enum ToBackend {
 
     CheckForUpdates {
         game_version: String,
+        /// The loader the game runs on, which updates have to support
+        modloader: ModLoader,
     },
 
     GetVersionMetadata,
@@ -34,17 +80,310 @@ pub enum ToBackend {
         mod_file: Box<ModFile>,
     },
 
+    RollbackMod {
+        mod_file: Box<ModFile>,
+        /// The hash of the stored version to restore
+        sha1: String,
+    },
+
+    /// Fetch every version of a mod
+    ListVersions {
+        mod_file: Box<ModFile>,
+    },
+
+    /// Move a mod to a specific file of one of its versions
+    InstallVersion {
+        mod_file: Box<ModFile>,
+        version_id: String,
+        version_file: VersionFile,
+    },
+
+    /// Fetch the changelogs between the installed file and its update
+    GetChangelogs {
+        mod_file: Box<ModFile>,
+    },
+
+    /// Read everything the manifests of a file declare
+    GetModDetails {
+        mod_file: Box<ModFile>,
+    },
+
+    /// Gather every update that would be applied by [UpdateAll](Self::UpdateAll)
+    PlanUpdateAll,
+
+    UpdateAll {
+        plan: Vec<PlannedUpdate>,
+    },
+
+    SetPin {
+        sha1: String,
+        pin: Option<Pin>,
+    },
+
+    /// Check the game versions and dependencies the files declare against the game selected, without reaching Modrinth
+    CheckCompatibility {
+        game_version: String,
+        modloader: ModLoader,
+    },
+
+    /// Override the release channel of a file, [None] goes back to the one in the settings
+    SetReleaseChannel {
+        sha1: String,
+        channel: Option<VersionType>,
+    },
+
     DeleteMod {
         path: PathBuf,
     },
+
+    /// Validate, apply and save new settings, reloading whatever depends on the ones that changed
+    UpdateSettings {
+        settings: Box<SettingsBuilder>,
+    },
+
+    /// Search Modrinth for mods to add
+    SearchMods {
+        query: SearchQuery,
+    },
+
+    /// Fetch the categories mods can be filtered by when searching
+    GetSearchCategories,
+
+    /// Look for the game folders of the launchers installed on this computer
+    DetectInstances,
+
+    /// Add an instance to the profiles and switch to its mods folder, creating it if needed
+    AdoptInstance {
+        instance: Box<Instance>,
+    },
+
+    /// Stop a running job, any work that was not completed is discarded
+    Cancel {
+        id: RequestId,
+    },
 }
 
 pub enum ToFrontend {
-    SetVersionMetadata { manifest: VersionManifest },
+    SetVersionMetadata {
+        manifest: VersionManifest,
+        /// Whether the manifest is a cached copy that could not be refreshed
+        stale: bool,
+    },
+
+    /// The version manifest could neither be fetched nor loaded from the cache
+    VersionMetadataUnavailable,
+
+    UpdateModList {
+        mod_list: Vec<ModFile>,
+    },
 
-    UpdateModList { mod_list: Vec<ModFile> },
+    /// The mods more than one file contains, sent along with every list
+    DuplicateMods {
+        groups: Vec<DuplicateGroup>,
+    },
+
+    UpdateVersionHistory {
+        history: Vec<StoredVersion>,
+    },
+
+    Changelogs {
+        mod_file: Box<ModFile>,
+        changelogs: Vec<Changelog>,
+    },
+
+    UpdatePlan {
+        plan: Vec<PlannedUpdate>,
+    },
+
+    ModDetails {
+        details: Box<FileDetails>,
+    },
+
+    UpdateSummary {
+        summary: UpdateSummary,
+    },
+
+    VersionList {
+        mod_file: Box<ModFile>,
+        versions: Vec<Version>,
+    },
+
+    /// The settings in use, sent whenever they change or a change was rejected
+    SettingsChanged {
+        settings: Box<SettingsBuilder>,
+    },
+
+    SearchResults {
+        page: SearchPage,
+    },
+
+    SearchCategories {
+        categories: Vec<String>,
+    },
+
+    InstancesDetected {
+        instances: Vec<Instance>,
+    },
+
+    /// The game the mods folder is most likely loaded by, sent when the folder or the known game versions change
+    InferredGame {
+        game: InferredGame,
+    },
+
+    /// The state of a running job, sent whenever it changes
+    Progress {
+        progress: JobProgress,
+    },
+
+    /// The job for the request was stopped before finishing
+    Cancelled,
+
+    BackendError {
+        error: BackendError,
+    },
+
+    /// Something went wrong that did not stop the request, but the user should know about
+    BackendWarning {
+        warning: BackendError,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct JobProgress {
+    /// The request that started the job
+    pub job_id: RequestId,
+    pub phase: JobPhase,
+    /// Whether the job can be stopped with [`ToBackend::Cancel`]
+    pub cancellable: bool,
+    pub items_done: usize,
+    pub items_total: usize,
+    pub bytes_done: u64,
+    /// The amount of bytes to download, zero if the phase does not download anything
+    pub bytes_total: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobPhase {
+    Scanning,
+    FetchingMetadata,
+    CheckingForUpdates,
+    Downloading,
+    Installing,
+    /// The job is done and can be removed, no more messages will be sent for it
+    Finished,
+}
+
+impl fmt::Display for JobPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobPhase::Scanning => write!(f, "Scanning the mods folder"),
+            JobPhase::FetchingMetadata => write!(f, "Fetching mod information"),
+            JobPhase::CheckingForUpdates => write!(f, "Checking for updates"),
+            JobPhase::Downloading => write!(f, "Downloading"),
+            JobPhase::Installing => write!(f, "Installing"),
+            JobPhase::Finished => write!(f, "Finished"),
+        }
+    }
+}
+
+/// An update that is about to be applied as part of a bulk update
+#[derive(Debug, Clone)]
+pub struct PlannedUpdate {
+    pub mod_file: ModFile,
+    pub display_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    /// The size of the new file in bytes
+    pub size: usize,
+    pub changelog_url: String,
+}
+
+impl PlannedUpdate {
+    /// Create a plan entry for a file, as long as it has an update to move to
+    #[must_use]
+    pub fn new(mod_file: &ModFile) -> Option<Self> {
+        let modrinth_data = mod_file.data.sources.modrinth.as_ref()?;
+        let version_file = modrinth_data.latest_valid_version.as_ref()?;
+        let entry = mod_file.entries.first();
+
+        let changelog_url = match &modrinth_data.latest_version_id {
+            Some(version_id) => format!(
+                "https://modrinth.com/mod/{}/version/{}",
+                modrinth_data.id, version_id
+            ),
+            None => format!("https://modrinth.com/mod/{}/changelog", modrinth_data.id),
+        };
+
+        Some(Self {
+            mod_file: mod_file.clone(),
+            display_name: entry.map_or_else(
+                || mod_file.path.display().to_string(),
+                |entry| entry.display_name.clone(),
+            ),
+            old_version: entry.map(|entry| entry.version.clone()).unwrap_or_default(),
+            new_version: modrinth_data
+                .latest_version_number
+                .clone()
+                .unwrap_or_else(|| version_file.filename.clone()),
+            size: version_file.size,
+            changelog_url,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UpdateSummary {
+    /// The names of the mods that were updated
+    pub updated: Vec<String>,
+    pub failed: Vec<BackendError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Changelog {
+    pub version_number: String,
+    pub version_type: VersionType,
+    pub changelog: Option<String>,
+}
+
+/// The side of the game a mod has to work on to show up in a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModSide {
+    Client,
+    Server,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    /// Only show mods with versions for this game version
+    pub game_version: Option<String>,
+    /// Only show mods for this loader, [ModLoader::Both] shows mods for any of them
+    pub modloader: ModLoader,
+    /// Only show mods that are in every one of these categories
+    pub categories: Vec<String>,
+    pub side: Option<ModSide>,
+    /// The page to fetch, starting from zero
+    pub page: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchPage {
+    pub hits: Vec<SearchHit>,
+    pub page: usize,
+    pub total_pages: usize,
+}
 
-    BackendError { error: BackendError },
+/// A mod found by a search
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub project_id: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub downloads: u64,
+    pub categories: Vec<String>,
+    /// The icon as RGBA pixels, resized like the icons of the files in the mods folder
+    pub icon: Option<Vec<u8>>,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use tracing::instrument;
+
+use super::{CurrentSource, FileState, ModEntry, ModFile, ModLoader};
+
+impl ModEntry {
+    /// The loader the dependencies of the entry were declared for, the entry of a multi-loader jar is read from its Fabric manifest
+    fn dependency_loader(&self) -> ModLoader {
+        match self.modloader {
+            ModLoader::Both => ModLoader::Fabric,
+            modloader => modloader,
+        }
+    }
+}
+
+impl ModFile {
+    /// Every id other mods can depend on this file as, including those of the mods it bundles
+    pub fn available_ids(&self) -> impl Iterator<Item = &str> {
+        let own = self
+            .entries
+            .iter()
+            .flat_map(|entry| std::iter::once(&entry.id).chain(&entry.provides));
+
+        let bundled = self
+            .bundled
+            .iter()
+            .flat_map(|bundled| std::iter::once(&bundled.id).chain(&bundled.provides));
+
+        own.chain(bundled).map(String::as_str)
+    }
+
+    /// Check the game versions and dependencies the file declares, which works without reaching Modrinth
+    ///
+    /// Files that declare to be incompatible with `game_version`, or depend on ids missing from `available_ids`, become [FileState::Invalid], even if an update is offered.
+    /// Only the dependencies declared for `modloader` are checked, as those of the other loader are never loaded.
+    /// The state they had is kept aside and restored once they are compatible again.
+    /// Files that cannot be checked on Modrinth fall back to [FileState::Local] once a game version is known.
+    #[instrument(skip(self, available_ids), fields(path = %self.path.display()), level = "debug")]
+    pub(crate) fn apply_compatibility(
+        &mut self,
+        game_version: Option<&str>,
+        modloader: Option<ModLoader>,
+        available_ids: &HashSet<&str>,
+    ) {
+        let incompatibility = game_version.and_then(|game_version| {
+            self.entries.iter().find_map(|entry| {
+                let game_versions = entry.game_versions.as_ref()?;
+
+                (game_versions.matches(game_version) == Some(false)).then(|| {
+                    format!(
+                        "{} requires Minecraft {}, which does not include {}",
+                        entry.display_name, game_versions, game_version
+                    )
+                })
+            })
+        });
+
+        let missing_dependency = || {
+            self.entries.iter().find_map(|entry| {
+                let checked = match modloader {
+                    Some(ModLoader::Both) | None => true,
+                    Some(modloader) => entry.dependency_loader() == modloader,
+                };
+                if !checked {
+                    return None;
+                }
+
+                let missing: Vec<&str> = entry
+                    .dependencies
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|id| !available_ids.contains(id))
+                    .collect();
+
+                (!missing.is_empty()).then(|| {
+                    format!(
+                        "{} requires {}, which no mod in the folder provides",
+                        entry.display_name,
+                        missing.join(", ")
+                    )
+                })
+            })
+        };
+
+        let reason = incompatibility.or_else(missing_dependency);
+
+        let data = &mut self.data;
+        let tracked = data.sourced_from == CurrentSource::Modrinth;
+
+        match reason {
+            Some(reason) => {
+                // A state other than the override was set since the last check, and is the one to restore
+                if data.state != FileState::Invalid || data.overridden_state.is_none() {
+                    data.overridden_state = Some(data.state);
+                }
+
+                data.state = FileState::Invalid;
+                data.state_reason = Some(reason);
+            }
+            None if !tracked && game_version.is_some() => {
+                data.state = FileState::Local;
+                data.state_reason = None;
+                data.overridden_state = None;
+            }
+            None => {
+                // Only undo what an earlier check marked, Modrinth has the final say otherwise
+                if let Some(state) = data.overridden_state.take() {
+                    data.state = state;
+                }
+
+                data.state_reason = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Everything the manifests of a file declare, read again from the file when it is looked at in full
+
+use std::{collections::HashMap, fmt, fs, io::BufReader, path::PathBuf};
+
+use mc_mod_meta::{
+    fabric::{
+        Author, DependencyVersion, Entrypoint, Environment, FabricManifest, License, Mixin,
+        FABRIC_META_PATH,
+    },
+    forge::{ForgeManifest, FORGE_META_PATH},
+};
+use tracing::instrument;
+
+use crate::error::LibResult;
+
+use super::{from_file::read_archive_file, Hashes, ModFile};
+
+/// A file along with all that is known about the mods in it
+#[derive(Clone, Debug)]
+pub struct FileDetails {
+    pub path: PathBuf,
+    /// The size of the file in bytes
+    pub size: u64,
+    pub hashes: Hashes,
+    /// The page of the Modrinth project the file belongs to
+    pub modrinth_url: Option<String>,
+    pub entries: Vec<EntryDetails>,
+    /// The manifests as they are written, by their path in the file
+    pub manifests: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EntryDetails {
+    pub id: String,
+    pub display_name: String,
+    pub contributors: Vec<String>,
+    pub license: Option<String>,
+    /// Where to find out more about the mod, by what the link is for (Ex. "Issues")
+    pub contact: Vec<(String, String)>,
+    pub credits: Option<String>,
+    /// The code the loader starts the mod with, by the entrypoint it is for (Ex. "client")
+    pub entrypoints: Vec<(String, String)>,
+    pub mixins: Vec<String>,
+    pub dependencies: Vec<DeclaredDependency>,
+}
+
+/// A relation to another mod, as declared in a manifest
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeclaredDependency {
+    pub id: String,
+    pub kind: DependencyKind,
+    pub versions: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependencyKind {
+    Required,
+    Recommended,
+    Suggested,
+    Optional,
+    Breaks,
+    Conflicts,
+}
+
+impl fmt::Display for DependencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl FileDetails {
+    /// Read the manifests of `mod_file` in full
+    #[instrument(skip(mod_file), fields(path = %mod_file.path.display()), level = "debug")]
+    pub fn read(mod_file: &ModFile) -> LibResult<Self> {
+        let file = fs::File::open(&mod_file.path)?;
+        let size = file.metadata()?.len();
+        let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+
+        let mut manifests = Vec::new();
+        let mut entries = Vec::new();
+
+        for manifest_path in [FABRIC_META_PATH, FORGE_META_PATH] {
+            let text = match read_archive_file(&mut archive, manifest_path) {
+                Ok(buf) => String::from_utf8_lossy(&buf).to_string(),
+                Err(_) => continue,
+            };
+
+            if manifest_path == FABRIC_META_PATH {
+                if let Ok(manifest) = FabricManifest::from_buffer(&text) {
+                    entries.push(fabric_details(manifest));
+                }
+            } else if let Ok(manifest) = ForgeManifest::from_buffer(&text) {
+                entries.extend(forge_details(manifest));
+            }
+
+            manifests.push((manifest_path.to_string(), text));
+        }
+
+        let modrinth_url = mod_file
+            .data
+            .sources
+            .modrinth
+            .as_ref()
+            .map(|modrinth_data| format!("https://modrinth.com/mod/{}", modrinth_data.id));
+
+        Ok(Self {
+            path: mod_file.path.clone(),
+            size,
+            hashes: mod_file.hashes.clone(),
+            modrinth_url,
+            entries,
+            manifests,
+        })
+    }
+}
+
+fn fabric_details(manifest: FabricManifest) -> EntryDetails {
+    let contributors = manifest
+        .contributors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|author| match author {
+            Author::Name(name) => name,
+            Author::Object(object) => object.name,
+        })
+        .collect();
+
+    let license = manifest.license.map(|license| match license {
+        License::Single(license) => license,
+        License::Multiple(licenses) => licenses.join(", "),
+    });
+
+    let mut contact = Vec::new();
+
+    if let Some(object) = manifest.contact {
+        for (name, value) in [
+            ("Homepage", object.homepage),
+            ("Sources", object.sources),
+            ("Issues", object.issues),
+            ("Email", object.email),
+            ("IRC", object.irc),
+        ] {
+            if let Some(value) = value {
+                contact.push((name.to_string(), value));
+            }
+        }
+
+        contact.extend(
+            sorted(object.other)
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .as_str()
+                        .map(|value| (capitalized(&name), value.to_string()))
+                }),
+        );
+    }
+
+    let mut entrypoints = Vec::new();
+
+    if let Some(object) = manifest.entrypoints {
+        let other = sorted(object.other)
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, serde_json::from_value(value).ok()?)));
+
+        for (name, list) in [
+            ("main".to_string(), object.main),
+            ("client".to_string(), object.client),
+            ("server".to_string(), object.server),
+            ("preLaunch".to_string(), object.prelaunch),
+        ]
+        .into_iter()
+        .chain(other)
+        {
+            entrypoints.extend(list.into_iter().map(|entrypoint| {
+                let value = match entrypoint {
+                    Entrypoint::Java(value) => value,
+                    Entrypoint::Adapter(object) => format!("{} ({})", object.value, object.adapter),
+                };
+
+                (name.clone(), value)
+            }));
+        }
+    }
+
+    let mixins = manifest
+        .mixins
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mixin| match mixin {
+            Mixin::Path(path) => path,
+            Mixin::Object(object) => {
+                format!(
+                    "{} ({})",
+                    object.config,
+                    environment_name(&object.environment)
+                )
+            }
+        })
+        .collect();
+
+    let mut dependencies = Vec::new();
+
+    for (kind, map) in [
+        (DependencyKind::Required, manifest.depends),
+        (DependencyKind::Recommended, manifest.recommends),
+        (DependencyKind::Suggested, manifest.suggests),
+        (DependencyKind::Breaks, manifest.breaks),
+        (DependencyKind::Conflicts, manifest.conflicts),
+    ] {
+        dependencies.extend(
+            sorted(map.unwrap_or_default())
+                .into_iter()
+                .map(|(id, versions)| DeclaredDependency {
+                    id,
+                    kind,
+                    versions: match versions {
+                        DependencyVersion::Single(version) => version,
+                        DependencyVersion::Multiple(versions) => versions.join(" or "),
+                    },
+                }),
+        );
+    }
+
+    EntryDetails {
+        display_name: manifest.name.unwrap_or_else(|| manifest.id.clone()),
+        id: manifest.id,
+        contributors,
+        license,
+        contact,
+        credits: None,
+        entrypoints,
+        mixins,
+        dependencies,
+    }
+}
+
+fn forge_details(mut manifest: ForgeManifest) -> Vec<EntryDetails> {
+    manifest
+        .mods
+        .into_iter()
+        .map(|entry| {
+            let mut contact = Vec::new();
+
+            if let Some(display_url) = entry.display_url {
+                contact.push(("Homepage".to_string(), display_url));
+            }
+
+            if !manifest.issue_tracker_url.is_empty() {
+                contact.push(("Issues".to_string(), manifest.issue_tracker_url.clone()));
+            }
+
+            let dependencies = manifest
+                .dependencies
+                .remove(&entry.mod_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|dependency| DeclaredDependency {
+                    id: dependency.mod_id,
+                    kind: if dependency.mandatory {
+                        DependencyKind::Required
+                    } else {
+                        DependencyKind::Optional
+                    },
+                    versions: dependency.version_range,
+                })
+                .collect();
+
+            EntryDetails {
+                id: entry.mod_id,
+                display_name: entry.display_name,
+                license: Some(manifest.license.clone()),
+                contact,
+                credits: entry.credits,
+                dependencies,
+                ..EntryDetails::default()
+            }
+        })
+        .collect()
+}
+
+fn environment_name(environment: &Environment) -> &'static str {
+    match environment {
+        Environment::Both => "client and server",
+        Environment::Client => "client",
+        Environment::Server => "server",
+    }
+}
+
+/// Manifests are read into maps, which would otherwise be shown in a different order every time
+fn sorted<T>(map: HashMap<String, T>) -> Vec<(String, T)> {
+    let mut entries: Vec<(String, T)> = map.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    entries
+}
+
+fn capitalized(name: &str) -> String {
+    let mut chars = name.chars();
+
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
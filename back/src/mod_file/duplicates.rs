@@ -0,0 +1,124 @@
+//! Finding mods that are in the folder more than once, which the loaders refuse to start with
+
+use std::{cmp::Reverse, collections::BTreeMap, fs, path::PathBuf, time::SystemTime};
+
+use super::{version_range::Version, ModFile};
+
+/// Every file that contains the same mod, newest first
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateGroup {
+    pub id: String,
+    pub copies: Vec<DuplicateCopy>,
+}
+
+impl DuplicateGroup {
+    /// The copies that can be trashed to keep the newest standalone one
+    ///
+    /// Only files that are the mod itself are offered, as trashing the others would take the mods that provide or bundle it along.
+    #[must_use]
+    pub fn older_copies(&self) -> Vec<&DuplicateCopy> {
+        self.copies
+            .iter()
+            .filter(|copy| copy.claim != IdClaim::Bundled)
+            .skip(1)
+            .filter(|copy| copy.claim == IdClaim::Own)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateCopy {
+    pub path: PathBuf,
+    pub display_name: String,
+    pub version: String,
+    pub claim: IdClaim,
+}
+
+/// How a file comes to contain a mod
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdClaim {
+    /// The mod is the file itself
+    Own,
+    /// The file provides the id as an alias
+    Provided,
+    /// The mod is nested in the file (jar-in-jar)
+    ///
+    /// Only the jars Fabric manifests declare are read, mods nested in Forge jars are not detected.
+    Bundled,
+}
+
+/// Find the ids that more than one file contains
+///
+/// Ids that are only in one file outside of the ones bundling them are left out, as the loader picks one of the copies on its own.
+#[must_use]
+pub fn find_duplicates(mod_list: &[ModFile]) -> Vec<DuplicateGroup> {
+    let mut claims: BTreeMap<&str, Vec<DuplicateCopy>> = BTreeMap::new();
+
+    for mod_file in mod_list {
+        let display_name = mod_file.entries.first().map_or_else(
+            || mod_file.path.display().to_string(),
+            |entry| entry.display_name.clone(),
+        );
+
+        let own = mod_file.entries.iter().flat_map(|entry| {
+            std::iter::once((entry.id.as_str(), &entry.version, IdClaim::Own)).chain(
+                entry
+                    .provides
+                    .iter()
+                    .map(move |id| (id.as_str(), &entry.version, IdClaim::Provided)),
+            )
+        });
+
+        let bundled = mod_file.bundled.iter().flat_map(|bundled| {
+            std::iter::once(bundled.id.as_str())
+                .chain(bundled.provides.iter().map(String::as_str))
+                .map(move |id| (id, &bundled.version, IdClaim::Bundled))
+        });
+
+        for (id, version, claim) in own.chain(bundled) {
+            let copies = claims.entry(id).or_default();
+
+            // A file that contains a mod more than once only counts once, by its most direct claim
+            if copies.iter().any(|copy| copy.path == mod_file.path) {
+                continue;
+            }
+
+            copies.push(DuplicateCopy {
+                path: mod_file.path.clone(),
+                display_name: display_name.clone(),
+                version: version.clone(),
+                claim,
+            });
+        }
+    }
+
+    claims
+        .into_iter()
+        .filter(|(_, copies)| {
+            copies
+                .iter()
+                .filter(|copy| copy.claim != IdClaim::Bundled)
+                .count()
+                > 1
+        })
+        .map(|(id, mut copies)| {
+            copies.sort_by_cached_key(|copy| Reverse(age_key(copy)));
+
+            DuplicateGroup {
+                id: id.to_string(),
+                copies,
+            }
+        })
+        .collect()
+}
+
+/// What copies are sorted by, the newest having the largest key
+///
+/// Copies whose version cannot be compared come after the ones that can, the file that was changed last is the newest among the rest.
+fn age_key(copy: &DuplicateCopy) -> (Option<Version>, SystemTime) {
+    let modified = fs::metadata(&copy.path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    (Version::parse(&copy.version), modified)
+}
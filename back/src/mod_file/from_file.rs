@@ -1,15 +1,24 @@
 use std::{
     fs,
-    io::{BufReader, Read},
+    io::{BufReader, Cursor, Read, Seek},
     path::PathBuf,
 };
 
-use mc_mod_meta::{fabric::FabricManifest, forge::ForgeManifest};
-use tracing::instrument;
+use mc_mod_meta::{
+    fabric::{FabricManifest, FABRIC_META_PATH},
+    forge::ForgeManifest,
+};
+use tracing::{debug, instrument};
 
 use crate::{error::LibResult, settings::CONF};
 
-use super::{CurrentSource, FileState, Hashes, ModEntry, ModFile, ModFileData, ModLoader, Sources};
+use super::{
+    BundledMod, CurrentSource, FileState, Hashes, ModEntry, ModFile, ModFileData, ModLoader,
+    Sources,
+};
+
+/// Jars can be nested in jars that are nested themselves, but not endlessly
+const MAX_BUNDLE_DEPTH: usize = 4;
 
 impl ModFile {
     pub fn from_path(path: PathBuf) -> LibResult<Self> {
@@ -19,6 +28,11 @@ impl ModFile {
 
         let entries = ModEntry::from_file(&mut file)?;
 
+        let bundled = match zip::ZipArchive::new(BufReader::new(&file)) {
+            Ok(mut archive) => bundled_mods(&mut archive, 0),
+            Err(_) => Vec::new(),
+        };
+
         let mut loaders: Vec<ModLoader> = entries.iter().map(|entry| entry.modloader).collect();
         loaders.dedup();
 
@@ -27,10 +41,15 @@ impl ModFile {
             sourced_from: CurrentSource::None,
             state: FileState::Current,
             loaders,
+            pin: None,
+            release_channel: None,
+            state_reason: None,
+            overridden_state: None,
         };
 
         Ok(Self {
             entries,
+            bundled,
             data,
             hashes,
             path,
@@ -49,7 +68,8 @@ impl ModEntry {
                 let forge_meta = ForgeManifest::from_file(file)?;
                 for forge_mod_entry in forge_meta.mods {
                     let icon_path = forge_mod_entry.logo_file.clone();
-                    let mod_entry = Self::from_forge_manifest(forge_mod_entry);
+                    let dependencies = forge_meta.dependencies.get(&forge_mod_entry.mod_id);
+                    let mod_entry = Self::from_forge_manifest(forge_mod_entry, dependencies);
 
                     add_to_mod_vec(&mut mod_vec, file, mod_entry, icon_path);
                 }
@@ -80,6 +100,68 @@ impl ModEntry {
     }
 }
 
+/// Read the Fabric mods nested in an archive, along with the ones nested in them
+///
+/// Nested jars that cannot be read are skipped, as they do not keep the file itself from being listed.
+/// Forge jar-in-jar (`META-INF/jarjar/metadata.json`) is not read, so mods nested in Forge jars are not detected.
+fn bundled_mods<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, depth: usize) -> Vec<BundledMod> {
+    if depth >= MAX_BUNDLE_DEPTH {
+        return Vec::new();
+    }
+
+    let manifest = match read_archive_file(archive, FABRIC_META_PATH)
+        .ok()
+        .and_then(|buf| FabricManifest::from_buffer(&String::from_utf8_lossy(&buf)).ok())
+    {
+        Some(manifest) => manifest,
+        None => return Vec::new(),
+    };
+
+    let mut bundled = Vec::new();
+
+    for jar in manifest.jars.unwrap_or_default() {
+        let nested = read_archive_file(archive, &jar.file)
+            .ok()
+            .and_then(|buf| zip::ZipArchive::new(Cursor::new(buf)).ok());
+
+        let mut nested = match nested {
+            Some(nested) => nested,
+            None => {
+                debug!(path = %jar.file, "Could not open a bundled jar");
+                continue;
+            }
+        };
+
+        let nested_manifest = read_archive_file(&mut nested, FABRIC_META_PATH)
+            .ok()
+            .and_then(|buf| FabricManifest::from_buffer(&String::from_utf8_lossy(&buf)).ok());
+
+        if let Some(nested_manifest) = nested_manifest {
+            bundled.push(BundledMod {
+                id: nested_manifest.id,
+                version: nested_manifest.version,
+                provides: nested_manifest.provides.unwrap_or_default(),
+            });
+        }
+
+        bundled.extend(bundled_mods(&mut nested, depth + 1));
+    }
+
+    bundled
+}
+
+pub(super) fn read_archive_file<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    path: &str,
+) -> LibResult<Vec<u8>> {
+    let mut file = archive.by_name(path)?;
+
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+
+    Ok(buf)
+}
+
 fn add_to_mod_vec(
     mod_vec: &mut Vec<ModEntry>,
     file: &fs::File,
@@ -103,10 +185,16 @@ fn load_icon(zip_file: &fs::File, icon_path: &str) -> LibResult<Vec<u8>> {
     let mut buf = Vec::new();
     file.read_to_end(&mut buf).unwrap();
 
+    resize_icon(&buf)
+}
+
+/// Decode an icon into RGBA pixels of the size set in the settings
+pub(crate) fn resize_icon(raw: &[u8]) -> LibResult<Vec<u8>> {
     let icon_size = CONF.lock().icon_resize_size;
-    let image = image::load_from_memory(&buf)
-        .unwrap()
-        .resize(icon_size, icon_size, image::imageops::FilterType::Triangle)
+
+    // The frontend expects square icons, so the aspect ratio is not kept
+    let image = image::load_from_memory(raw)?
+        .resize_exact(icon_size, icon_size, image::imageops::FilterType::Triangle)
         .to_rgba8()
         .to_vec();
     Ok(image)
@@ -24,7 +24,6 @@ impl Hashes {
         Ok(get_hashes_from_vec(buf))
     }
 
-    #[allow(dead_code)]
     #[instrument(skip(bytes))]
     pub(crate) fn get_hashes_from_bytes(bytes: &Bytes) -> Self {
         get_hashes_from_vec(bytes)
@@ -0,0 +1,121 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use tracing::{debug, error, instrument};
+
+use crate::{
+    error::{Error, LibResult},
+    paths,
+};
+
+use super::ModFile;
+
+/// Write `bytes` to `target`, replacing the file at `replaces` if provided.
+///
+/// The new contents are first written to a temporary file next to the target, which has to be a mod that can be read before anything else is touched.
+/// The old file is then moved into the backup folder and the new one renamed into place.
+/// If any of the steps fail, the folder is restored to the state it was in before the call.
+///
+/// On success, the installed file is returned along with the path the replaced file was moved to, so that it can be kept or discarded by the caller.
+#[instrument(skip(bytes))]
+pub(crate) fn install_file(
+    bytes: &[u8],
+    target: &Path,
+    replaces: Option<&Path>,
+) -> LibResult<(ModFile, Option<PathBuf>)> {
+    // Never overwrite a file that is not the one being replaced
+    if target.exists() && replaces != Some(target) {
+        return Err(Error::EntryAlreadyInList);
+    }
+
+    let staged_path = staged_path(target);
+
+    let staged_file = write_staged(&staged_path, bytes).and_then(|()| {
+        debug!("Reading the staged file");
+        ModFile::from_path(staged_path.clone())
+    });
+
+    let mut new_file = match staged_file {
+        Ok(new_file) => new_file,
+        Err(error) => {
+            discard(&staged_path);
+            return Err(error);
+        }
+    };
+
+    let backup = match replaces {
+        Some(old_path) => match move_file(old_path, &backup_path(old_path)) {
+            Ok(backup_path) => Some((old_path, backup_path)),
+            Err(error) => {
+                discard(&staged_path);
+                return Err(error);
+            }
+        },
+        None => None,
+    };
+
+    if let Err(error) = fs::rename(&staged_path, target) {
+        error!(%error, "Could not move the new file into place, restoring the previous state");
+
+        discard(&staged_path);
+
+        if let Some((old_path, backup_path)) = &backup {
+            if let Err(error) = move_file(backup_path, old_path) {
+                error!(%error, backup = %backup_path.display(), "Could not restore the backup");
+            }
+        }
+
+        return Err(error.into());
+    }
+
+    debug!("File installed");
+
+    new_file.path = target.to_path_buf();
+
+    Ok((new_file, backup.map(|(_, backup_path)| backup_path)))
+}
+
+fn write_staged(path: &Path, bytes: &[u8]) -> LibResult<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Moves a file, falling back to a copy if a rename is not possible (Ex. the paths are on different filesystems)
+fn move_file(from: &Path, to: &Path) -> LibResult<PathBuf> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+
+    Ok(to.to_path_buf())
+}
+
+pub(crate) fn discard(path: &Path) {
+    if path.exists() {
+        if let Err(error) = fs::remove_file(path) {
+            error!(%error, path = %path.display(), "Could not remove leftover file");
+        }
+    }
+}
+
+fn staged_path(target: &Path) -> PathBuf {
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    target.with_file_name(format!(".{}.part", file_name))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    paths::BACKUP_DIR.join(path.file_name().unwrap_or_default())
+}
@@ -1,20 +1,32 @@
 use core::fmt;
-use std::{fmt::Debug, path::PathBuf};
+use std::{collections::BTreeMap, fmt::Debug, path::PathBuf};
 
-use ferinth::structures::version_structs::{ModLoader as FeModLoader, VersionFile};
-use mc_mod_meta::{fabric::FabricManifest, forge::ForgeModEntry, ModLoader as McModLoader};
+use ferinth::structures::version_structs::{VersionFile, VersionType};
+use mc_mod_meta::{
+    fabric::{DependencyVersion, FabricManifest},
+    forge::{Dependency, ForgeModEntry},
+    ModLoader as McModLoader,
+};
 
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
-pub use self::hash::Hashes;
+pub use self::{hash::Hashes, version_range::VersionRange};
 
+mod compatibility;
+pub mod details;
+pub mod duplicates;
 pub mod from_file;
 pub mod hash;
+pub(crate) mod install;
+pub mod version_range;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModFile {
     pub entries: Vec<ModEntry>,
+    /// The mods shipped inside the file (jar-in-jar), which are loaded along with it
+    #[serde(default)]
+    pub bundled: Vec<BundledMod>,
     pub data: ModFileData,
     pub hashes: Hashes,
     pub path: PathBuf,
@@ -26,6 +38,17 @@ pub struct ModFileData {
     pub sourced_from: CurrentSource,
     pub state: FileState,
     pub loaders: Vec<ModLoader>,
+    #[serde(default)]
+    pub pin: Option<Pin>,
+    /// The least stable release type accepted for this file, overriding the one in the settings
+    #[serde(default)]
+    pub release_channel: Option<VersionType>,
+    /// Why the file is in its state, if that needs explaining
+    #[serde(default)]
+    pub state_reason: Option<String>,
+    /// The state the compatibility check replaced, restored once the file is compatible again
+    #[serde(default)]
+    pub overridden_state: Option<FileState>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -36,10 +59,45 @@ pub struct ModEntry {
     pub description: Option<String>,
     pub authors: Option<String>,
     pub modloader: ModLoader,
+    /// The game versions the mod declares to work on
+    #[serde(default)]
+    pub game_versions: Option<VersionRange>,
+    /// Other ids the mod can be depended on as (Ex. the id of a mod it replaces)
+    #[serde(default)]
+    pub provides: Vec<String>,
+    /// The ids of the mods this one cannot be loaded without
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// The mod this one is a part of (Ex. the modules of Fabric API)
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Labels the mod gives itself for Mod Menu (Ex. `library`)
+    #[serde(default)]
+    pub badges: Vec<String>,
+    /// Extra links by their Mod Menu translation key (Ex. `modmenu.discord`)
+    #[serde(default)]
+    pub links: BTreeMap<String, String>,
     #[serde(skip_serializing, skip_deserializing)]
     pub icon: Option<Vec<u8>>,
 }
 
+/// A mod nested inside the file of another one
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundledMod {
+    pub id: String,
+    pub version: String,
+    pub provides: Vec<String>,
+}
+
+/// Text from the Forge mod template, which says nothing about the mod when left in
+const PLACEHOLDER_DESCRIPTIONS: [&str; 2] = [
+    "This is a long form description of the mod",
+    "You can write whatever you want here",
+];
+
+/// Ids the game and the loaders provide, which no mod file has to
+const LOADER_PROVIDED_IDS: [&str; 5] = ["minecraft", "java", "fabricloader", "forge", "javafml"];
+
 // Middleman "ModLoader" enum to convert between those of the other crates
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ModLoader {
@@ -60,16 +118,6 @@ impl fmt::Display for ModLoader {
     }
 }
 
-impl From<ModLoader> for FeModLoader {
-    fn from(modloader: ModLoader) -> Self {
-        match modloader {
-            ModLoader::Forge => Self::Forge,
-            ModLoader::Fabric => Self::Fabric,
-            ModLoader::Both => Self::Fabric,
-        }
-    }
-}
-
 impl From<McModLoader> for ModLoader {
     fn from(modloader: McModLoader) -> Self {
         match modloader {
@@ -93,6 +141,32 @@ pub struct CurseForgeData;
 pub struct ModrinthData {
     pub id: String,
     pub latest_valid_version: Option<VersionFile>,
+    /// The ID of the version [latest_valid_version](Self::latest_valid_version) belongs to
+    #[serde(default)]
+    pub latest_version_id: Option<String>,
+    /// The version number of the version [latest_valid_version](Self::latest_valid_version) belongs to
+    #[serde(default)]
+    pub latest_version_number: Option<String>,
+    /// Whether the update information comes from cached data that could not be refreshed
+    #[serde(default)]
+    pub stale: bool,
+    /// The project the file belongs to, once it has been fetched
+    #[serde(default)]
+    pub project: Option<ProjectInfo>,
+}
+
+/// What Modrinth shows about a project, used for what the manifests of a file leave out
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub title: String,
+    pub summary: String,
+    pub categories: Vec<String>,
+    /// Where to find out more about the project, by what the link is for (Ex. "Issues")
+    pub links: Vec<(String, String)>,
+    pub icon_url: Option<String>,
+    /// The icon as RGBA pixels, loaded from the icon cache as it is not stored with the rest
+    #[serde(skip_serializing, skip_deserializing)]
+    pub icon: Option<Vec<u8>>,
 }
 
 #[allow(dead_code)]
@@ -102,6 +176,17 @@ pub enum FileState {
     Outdated,
     Invalid,
     Local,
+    /// The file is pinned, or the only update available is ignored
+    Pinned,
+}
+
+/// Keeps a file from being offered updates
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Pin {
+    /// Stay on the current file
+    CurrentFile,
+    /// Skip a specific version when checking for updates
+    IgnoreVersion { version_id: String },
 }
 
 #[allow(dead_code)]
@@ -119,9 +204,58 @@ impl fmt::Display for CurrentSource {
     }
 }
 
+impl ModFile {
+    /// An identifier shared by every version of the same mod
+    #[must_use]
+    pub fn history_key(&self) -> String {
+        self.entries
+            .first()
+            .map_or_else(|| self.path.display().to_string(), |entry| entry.id.clone())
+    }
+}
+
 impl ModEntry {
-    #[instrument(skip(forge_mod_entry), level = "debug")]
-    fn from_forge_manifest(forge_mod_entry: ForgeModEntry) -> Self {
+    /// The description from the manifest, unless there is none or it was left as the placeholder of the template
+    #[must_use]
+    pub fn own_description(&self) -> Option<&str> {
+        self.description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .filter(|description| {
+                !PLACEHOLDER_DESCRIPTIONS
+                    .iter()
+                    .any(|placeholder| description.contains(placeholder))
+            })
+    }
+
+    /// Whether the mod only exists for other mods to use
+    #[must_use]
+    pub fn is_library(&self) -> bool {
+        self.badges.iter().any(|badge| badge == "library")
+    }
+
+    #[instrument(skip(forge_mod_entry, dependencies), level = "debug")]
+    fn from_forge_manifest(
+        forge_mod_entry: ForgeModEntry,
+        dependencies: Option<&Vec<Dependency>>,
+    ) -> Self {
+        let game_versions = dependencies
+            .and_then(|dependencies| {
+                dependencies
+                    .iter()
+                    .find(|dependency| dependency.mod_id == "minecraft")
+            })
+            .map(|dependency| VersionRange::Maven(dependency.version_range.clone()));
+
+        let dependencies = dependencies
+            .into_iter()
+            .flatten()
+            .filter(|dependency| dependency.mandatory)
+            .map(|dependency| dependency.mod_id.clone())
+            .filter(|id| !LOADER_PROVIDED_IDS.contains(&id.as_str()))
+            .collect();
+
         Self {
             id: forge_mod_entry.mod_id,
             version: forge_mod_entry.version,
@@ -129,12 +263,20 @@ impl ModEntry {
             description: Some(forge_mod_entry.description),
             authors: forge_mod_entry.authors,
             modloader: ModLoader::Forge,
+            game_versions,
+            provides: Vec::new(),
+            dependencies,
+            parent: None,
+            badges: Vec::new(),
+            links: BTreeMap::new(),
             icon: None,
         }
     }
 
     #[instrument(skip(fabric_manifest), level = "debug")]
     fn from_fabric_manifest(fabric_manifest: FabricManifest) -> Self {
+        let mod_menu = fabric_manifest.mod_menu().unwrap_or_default();
+
         let mod_name = fabric_manifest
             .name
             .unwrap_or_else(|| fabric_manifest.id.clone());
@@ -156,6 +298,23 @@ impl ModEntry {
             },
         );
 
+        let game_versions = fabric_manifest
+            .depends
+            .as_ref()
+            .and_then(|depends| depends.get("minecraft"))
+            .map(|versions| match versions {
+                DependencyVersion::Single(version) => VersionRange::Fabric(vec![version.clone()]),
+                DependencyVersion::Multiple(versions) => VersionRange::Fabric(versions.clone()),
+            });
+
+        let mut dependencies: Vec<String> = fabric_manifest
+            .depends
+            .unwrap_or_default()
+            .into_keys()
+            .filter(|id| !LOADER_PROVIDED_IDS.contains(&id.as_str()))
+            .collect();
+        dependencies.sort();
+
         Self {
             id: fabric_manifest.id,
             version: fabric_manifest.version,
@@ -163,6 +322,12 @@ impl ModEntry {
             description: fabric_manifest.description,
             authors: parsed_authors,
             modloader: ModLoader::Fabric,
+            game_versions,
+            provides: fabric_manifest.provides.unwrap_or_default(),
+            dependencies,
+            parent: mod_menu.parent.map(|parent| parent.id().to_string()),
+            badges: mod_menu.badges,
+            links: mod_menu.links.into_iter().collect(),
             icon: None,
         }
     }
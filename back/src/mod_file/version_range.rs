@@ -0,0 +1,228 @@
+//! Matching versions against the ranges mods declare, in the formats used by Fabric and Forge
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+/// The versions of something a mod declares to work with
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VersionRange {
+    /// Fabric version predicates, a version works if it matches any of them (Ex. `>=1.19 <1.20` or `1.18.x`)
+    Fabric(Vec<String>),
+    /// A Maven version range as used by Forge (Ex. `[1.19,1.20)`)
+    Maven(String),
+}
+
+impl VersionRange {
+    /// Whether `version` is in the range, [None] if either of them cannot be understood (Ex. snapshots)
+    #[must_use]
+    pub fn matches(&self, version: &str) -> Option<bool> {
+        let version = Version::parse(version)?;
+
+        match self {
+            VersionRange::Fabric(predicates) => {
+                let mut unknown = false;
+
+                for predicate in predicates {
+                    match fabric_predicate_matches(predicate, &version) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => unknown = true,
+                    }
+                }
+
+                if unknown {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            VersionRange::Maven(range) => maven_range_matches(range, &version),
+        }
+    }
+}
+
+impl std::fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionRange::Fabric(predicates) => write!(f, "{}", predicates.join(" or ")),
+            VersionRange::Maven(range) => write!(f, "{}", range),
+        }
+    }
+}
+
+/// Compare two versions, [None] if either of them is not made of numbers (Ex. `mc1.19-1.0`)
+#[must_use]
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Some(Version::parse(a)?.cmp(&Version::parse(b)?))
+}
+
+/// A version made of numbers separated by dots, optionally followed by a pre-release (Ex. `1.19-pre1`)
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Version {
+    numbers: Vec<u64>,
+    pre_release: Option<String>,
+}
+
+impl Version {
+    pub(crate) fn parse(version: &str) -> Option<Self> {
+        // Build metadata does not take part in comparisons
+        let version = version.split('+').next()?.trim();
+
+        let (numbers, pre_release) = match version.split_once('-') {
+            Some((numbers, pre_release)) => (numbers, Some(pre_release.to_string())),
+            None => (version, None),
+        };
+
+        let numbers = numbers
+            .split('.')
+            .map(|number| number.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        Some(Self {
+            numbers,
+            pre_release,
+        })
+    }
+
+    fn number(&self, index: usize) -> u64 {
+        self.numbers.get(index).copied().unwrap_or_default()
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let length = self.numbers.len().max(other.numbers.len());
+
+        (0..length)
+            .map(|index| self.number(index).cmp(&other.number(index)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                // A pre-release comes before the release it leads up to
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+/// Every predicate separated by spaces has to match
+fn fabric_predicate_matches(predicates: &str, version: &Version) -> Option<bool> {
+    let mut matches = true;
+
+    for predicate in predicates.split_whitespace() {
+        matches &= single_fabric_predicate_matches(predicate, version)?;
+    }
+
+    Some(matches)
+}
+
+fn single_fabric_predicate_matches(predicate: &str, version: &Version) -> Option<bool> {
+    if predicate == "*" {
+        return Some(true);
+    }
+
+    for (operator, ordering_matches) in [
+        (">=", Ordering::is_ge as fn(Ordering) -> bool),
+        ("<=", Ordering::is_le),
+        (">", Ordering::is_gt),
+        ("<", Ordering::is_lt),
+    ] {
+        if let Some(bound) = predicate.strip_prefix(operator) {
+            return Some(ordering_matches(version.cmp(&Version::parse(bound)?)));
+        }
+    }
+
+    if let Some(bound) = predicate.strip_prefix('^') {
+        // The same major version, at least the given one
+        let bound = Version::parse(bound)?;
+        let upper = Version {
+            numbers: vec![bound.number(0) + 1],
+            pre_release: None,
+        };
+
+        return Some(*version >= bound && *version < upper);
+    }
+
+    if let Some(bound) = predicate.strip_prefix('~') {
+        // The same minor version, at least the given one
+        let bound = Version::parse(bound)?;
+        let upper = Version {
+            numbers: vec![bound.number(0), bound.number(1) + 1],
+            pre_release: None,
+        };
+
+        return Some(*version >= bound && *version < upper);
+    }
+
+    let predicate = predicate.strip_prefix('=').unwrap_or(predicate);
+    let parts: Vec<&str> = predicate.split('.').collect();
+
+    // An X-range matches every version starting with the numbers before the wildcard (Ex. `1.19.x`)
+    if let Some(wildcard) = parts
+        .iter()
+        .position(|part| matches!(*part, "x" | "X" | "*"))
+    {
+        let prefix = parts[..wildcard]
+            .iter()
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        return Some(version.numbers.iter().take(prefix.len()).eq(prefix.iter()));
+    }
+
+    Some(*version == Version::parse(predicate)?)
+}
+
+/// A comma separated list of intervals, a version matches if it is in any of them
+fn maven_range_matches(range: &str, version: &Version) -> Option<bool> {
+    let mut rest = range.trim();
+
+    // A bare version is only a recommendation, so Forge accepts any version
+    if rest.is_empty() || !rest.starts_with(['[', '(']) {
+        return Some(true);
+    }
+
+    let mut matches = false;
+
+    while !rest.is_empty() {
+        let inclusive_lower = rest.starts_with('[');
+        let end = rest.find([']', ')'])?;
+        let inclusive_upper = rest[end..].starts_with(']');
+        let interval = &rest[1..end];
+
+        matches |= match interval.split_once(',') {
+            Some((lower, upper)) => {
+                let lower_ok = match lower.trim() {
+                    "" => true,
+                    lower => {
+                        let ordering = version.cmp(&Version::parse(lower)?);
+                        ordering.is_gt() || (inclusive_lower && ordering.is_eq())
+                    }
+                };
+
+                let upper_ok = match upper.trim() {
+                    "" => true,
+                    upper => {
+                        let ordering = version.cmp(&Version::parse(upper)?);
+                        ordering.is_lt() || (inclusive_upper && ordering.is_eq())
+                    }
+                };
+
+                lower_ok && upper_ok
+            }
+            None => *version == Version::parse(interval)?,
+        };
+
+        rest = rest[end + 1..].trim_start_matches([',', ' ']);
+    }
+
+    Some(matches)
+}
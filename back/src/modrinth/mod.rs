@@ -1,34 +1,71 @@
-use bytes::Bytes;
-use ferinth::{
-    structures::version_structs::{ListVersionsParams, Version, VersionType},
-    Ferinth,
+use std::{collections::HashMap, slice, time::Duration};
+
+use bytes::{Bytes, BytesMut};
+use ferinth::structures::{
+    project_structs::Project,
+    version_structs::{Version, VersionFile, VersionType},
 };
-use tracing::instrument;
+use futures::future;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, instrument};
 
 use crate::{
     error::{self, LibResult},
-    mod_file::{CurrentSource, FileState, Hashes, ModFileData, ModLoader, ModrinthData, Sources},
+    http::{Cached, HTTP},
+    messages::{Changelog, JobPhase},
+    mod_file::{
+        CurrentSource, FileState, Hashes, ModFile, ModFileData, ModLoader, ModrinthData, Pin,
+        Sources,
+    },
+    progress::ProgressReporter,
     settings::CONF,
 };
 
-#[derive(Debug)]
-pub struct Modrinth {
-    ferinth: Ferinth,
+mod project;
+mod search;
+
+pub(crate) use project::load_cached_icons;
+
+/// Every loader name that can show up in the file names of multi-loader versions
+const ALL_LOADER_NAMES: [&str; 3] = ["fabric", "forge", "quilt"];
+
+/// How long cached responses are used without revalidating them
+const VERSION_LIST_TTL: Duration = Duration::from_secs(10 * 60);
+const PROJECT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// The version a file belongs to never changes
+const VERSION_FILE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Serialize)]
+struct HashesQuery<'a> {
+    hashes: &'a [&'a str],
+    algorithm: &'static str,
 }
 
-impl Default for Modrinth {
-    fn default() -> Self {
-        Self {
-            ferinth: Ferinth::new(),
-        }
-    }
+#[derive(Serialize)]
+struct LatestVersionsQuery<'a> {
+    hashes: &'a [&'a str],
+    algorithm: &'static str,
+    loaders: &'a [&'static str],
+    game_versions: [&'a str; 1],
 }
 
+#[derive(Debug)]
+pub struct Modrinth;
+
 impl Modrinth {
     #[instrument(skip(self))]
     pub(crate) async fn get_modrinth_id_from_hash(&self, mod_hash: &str) -> Option<String> {
-        match self.ferinth.get_version_from_file_hash(mod_hash).await {
-            Ok(result) => Some(result.project_id),
+        let path = format!("/version_file/{}", mod_hash);
+
+        match self
+            .get::<Version>(
+                &path,
+                &[("algorithm", "sha1".to_string())],
+                VERSION_FILE_TTL,
+            )
+            .await
+        {
+            Ok(result) => Some(result.value.project_id),
             Err(_err) => None,
         }
     }
@@ -39,88 +76,250 @@ impl Modrinth {
         data: impl Into<&mut ModFileData>,
         hashes: Option<&Hashes>,
         game_version: &str,
+        active_loader: ModLoader,
     ) -> LibResult<()> {
         let mod_data = data.into();
-        // Get and set the modrinth ID, without one the operation cannot proceed
-        if mod_data.sources.modrinth.is_none() && hashes.is_some() {
-            let hashes = hashes.as_ref().unwrap();
-            let modrinth_id = self.get_modrinth_id_from_hash(&hashes.sha1).await;
 
-            if let Some(id) = modrinth_id {
-                mod_data.sources.modrinth = Some(ModrinthData {
-                    id,
-                    latest_valid_version: None,
-                });
+        // Files pinned to their current version are not checked at all
+        if mod_data.pin == Some(Pin::CurrentFile) {
+            mod_data.state = FileState::Pinned;
+            return Ok(());
+        }
 
-                // If the source has not been set by the user, automatically track Modrinth
-                if mod_data.sourced_from == CurrentSource::None {
-                    mod_data.sourced_from = CurrentSource::Modrinth;
+        // Get and set the modrinth ID, without one the operation cannot proceed
+        if mod_data.sources.modrinth.is_none() {
+            if let Some(hashes) = hashes {
+                if let Some(id) = self.get_modrinth_id_from_hash(&hashes.sha1).await {
+                    track_modrinth_project(mod_data, id);
                 }
             }
         }
 
         if mod_data.sourced_from == CurrentSource::Modrinth {
             // This will not always give a result, therefore the data needs to be checked again (In case it is "Some", assume its correct)
-            if let Some(modrinth_data) = &mut mod_data.sources.modrinth {
+            if let Some(modrinth_data) = &mod_data.sources.modrinth {
+                let loader = target_loader(&mod_data.loaders, active_loader);
+
                 // The version list can now be fetched
                 let version_list = self
-                    .list_versions(&modrinth_data.id, &mod_data.loaders, game_version)
+                    .list_versions(&modrinth_data.id, loader, game_version)
                     .await?;
 
-                if version_list.is_empty() {
-                    // No versions could be found that match the criteria, therefore the mod is incompatible for this version
-                    mod_data.state = FileState::Invalid;
-                } else {
-                    // There are results, consider the state to be up to date unless proven otherwise
-                    mod_data.state = FileState::Current;
-
-                    let accepted_version_types = accepted_versions_vec();
-                    let filtered_list: Vec<&Version> = version_list
-                        .iter()
-                        .filter(|version| {
-                            accepted_version_types
-                                .iter()
-                                .any(|ver_type| ver_type == &version.version_type)
-                                && mod_data.loaders.iter().all(|loader| {
-                                    version.loaders.contains(&loader.to_string().to_lowercase())
-                                })
-                                && !version.files.is_empty()
-                        })
-                        .collect();
-
-                    if !filtered_list.is_empty() {
-                        {
-                            if let Some(hashes) = &hashes {
-                                // If the version being checked contains a file with the hash of our local copy, it means it is already on the latest possible version
-                                if !filtered_list[0]
-                                    .files
-                                    .iter()
-                                    .any(|file| file.hashes.sha1 == Some(hashes.sha1.clone()))
-                                {
-                                    modrinth_data.latest_valid_version =
-                                        Some(filtered_list[0].files[0].clone());
-                                    mod_data.state = FileState::Outdated;
-                                }
-                            } else {
-                                // If hashes aren't provided assume its outdated
-                                modrinth_data.latest_valid_version =
-                                    Some(filtered_list[0].files[0].clone());
-                                mod_data.state = FileState::Outdated;
+                apply_version_list(
+                    mod_data,
+                    hashes,
+                    &version_list.value,
+                    loader,
+                    version_list.stale,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check a whole list of files for updates using the bulk endpoints of Modrinth, which only takes a handful of requests regardless of the amount of files.
+    ///
+    /// Files for which the latest version cannot be used as is (Ex. it is ignored or from a channel that is not accepted) fall back to checking their full version list.
+    #[instrument(skip(self, mod_list, progress), fields(amount = mod_list.len()))]
+    pub(crate) async fn check_all_for_updates(
+        &self,
+        mod_list: &mut [ModFile],
+        game_version: &str,
+        active_loader: ModLoader,
+        progress: &ProgressReporter,
+    ) -> Vec<error::Error> {
+        let mut errors = Vec::new();
+
+        // The bulk endpoints cannot be cached, so when offline every file goes through its cached version list instead
+        let offline = CONF.lock().offline_mode;
+
+        // Find the projects of the files that are not tracked yet
+        let untracked: Vec<&str> = mod_list
+            .iter()
+            .filter(|mod_file| mod_file.data.sources.modrinth.is_none())
+            .map(|mod_file| mod_file.hashes.sha1.as_str())
+            .collect();
+
+        if !untracked.is_empty() && !offline {
+            match self.get_versions_from_hashes(&untracked).await {
+                Ok(versions) => {
+                    for mod_file in mod_list.iter_mut() {
+                        if let Some(version) = versions.get(&mod_file.hashes.sha1) {
+                            if mod_file.data.sources.modrinth.is_none() {
+                                track_modrinth_project(
+                                    &mut mod_file.data,
+                                    version.project_id.clone(),
+                                );
                             }
                         }
                     }
                 }
+                Err(error) => errors.push(error),
             }
         }
 
-        Ok(())
+        // The update endpoint applies the same loaders to every hash, so the files are grouped by them
+        let mut groups: HashMap<&'static [&'static str], Vec<usize>> = HashMap::new();
+
+        for (index, mod_file) in mod_list.iter_mut().enumerate() {
+            if mod_file.data.pin == Some(Pin::CurrentFile) {
+                // Files pinned to their current version are not checked at all
+                mod_file.data.state = FileState::Pinned;
+                progress.item_done();
+            } else if mod_file.data.sourced_from == CurrentSource::Modrinth
+                && mod_file.data.sources.modrinth.is_some()
+            {
+                let loader = target_loader(&mod_file.data.loaders, active_loader);
+                groups.entry(loader_names(loader)).or_default().push(index);
+            } else {
+                progress.item_done();
+            }
+        }
+
+        let mut needs_full_check = Vec::new();
+
+        for (loaders, indices) in groups {
+            if offline {
+                needs_full_check.extend(indices);
+                continue;
+            }
+
+            let hashes: Vec<&str> = indices
+                .iter()
+                .map(|index| mod_list[*index].hashes.sha1.as_str())
+                .collect();
+
+            let latest_versions = match self
+                .get_latest_versions_from_hashes(&hashes, loaders, game_version)
+                .await
+            {
+                Ok(latest_versions) => latest_versions,
+                Err(error) => {
+                    errors.push(error);
+                    indices.iter().for_each(|_| progress.item_done());
+                    continue;
+                }
+            };
+
+            for index in indices {
+                let mod_file = &mut mod_list[index];
+                let loader = target_loader(&mod_file.data.loaders, active_loader);
+
+                match latest_versions.get(&mod_file.hashes.sha1) {
+                    Some(version) if is_usable_latest_version(&mod_file.data, version, loader) => {
+                        apply_version_list(
+                            &mut mod_file.data,
+                            Some(&mod_file.hashes),
+                            slice::from_ref(version),
+                            loader,
+                            false,
+                        );
+                        progress.item_done();
+                    }
+                    // Either the latest version cannot be used or the file itself is not on Modrinth
+                    _ => needs_full_check.push(index),
+                }
+            }
+        }
+
+        debug!(
+            amount = needs_full_check.len(),
+            "Checking the full version list of some files"
+        );
+
+        let full_checks = mod_list
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| needs_full_check.contains(index))
+            .map(|(_, mod_file)| async move {
+                let hashes = mod_file.hashes.clone();
+
+                let result = self
+                    .check_for_updates(
+                        &mut mod_file.data,
+                        Some(&hashes),
+                        game_version,
+                        active_loader,
+                    )
+                    .await;
+                progress.item_done();
+
+                result
+            });
+
+        errors.extend(
+            future::join_all(full_checks)
+                .await
+                .into_iter()
+                .filter_map(Result::err),
+        );
+
+        errors
     }
 
-    #[instrument(skip(self, mod_data))]
-    pub(crate) async fn update_mod(&self, mod_data: &ModFileData) -> LibResult<Bytes> {
+    /// Get the versions that contain the files with the given hashes, keyed by hash
+    #[instrument(skip(self, hashes), fields(amount = hashes.len()))]
+    async fn get_versions_from_hashes(
+        &self,
+        hashes: &[&str],
+    ) -> LibResult<HashMap<String, Version>> {
+        self.post(
+            "/version_files",
+            &HashesQuery {
+                hashes,
+                algorithm: "sha1",
+            },
+        )
+        .await
+    }
+
+    /// Get the latest version matching the loaders and game version of the projects the hashes belong to, keyed by hash
+    #[instrument(skip(self, hashes), fields(amount = hashes.len()))]
+    async fn get_latest_versions_from_hashes(
+        &self,
+        hashes: &[&str],
+        loaders: &[&'static str],
+        game_version: &str,
+    ) -> LibResult<HashMap<String, Version>> {
+        self.post(
+            "/version_files/update",
+            &LatestVersionsQuery {
+                hashes,
+                algorithm: "sha1",
+                loaders,
+                game_versions: [game_version],
+            },
+        )
+        .await
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        ttl: Duration,
+    ) -> LibResult<Cached<T>> {
+        let url = format!("{}{}", CONF.lock().active_modrinth_api_url(), path);
+        HTTP.get_json_cached(&url, query, ttl).await
+    }
+
+    /// The POST endpoints used are all lookups, so they are safe to retry
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: &impl Serialize) -> LibResult<T> {
+        let url = format!("{}{}", CONF.lock().active_modrinth_api_url(), path);
+        HTTP.post_json(&url, body, true).await
+    }
+
+    /// Download the latest valid version of a file, the caller is responsible for adding its size to the total of `progress`
+    #[instrument(skip(self, mod_data, progress))]
+    pub(crate) async fn update_mod(
+        &self,
+        mod_data: &ModFileData,
+        progress: &ProgressReporter,
+    ) -> LibResult<Bytes> {
         if let Some(data) = &mod_data.sources.modrinth {
             if let Some(version_file) = &data.latest_valid_version {
-                Ok(self.ferinth.download_version_file(version_file).await?)
+                self.download_version_file(version_file, progress).await
             } else {
                 Err(error::Error::InvalidLatestVersionError)
             }
@@ -129,18 +328,71 @@ impl Modrinth {
         }
     }
 
-    #[instrument(skip(self))]
+    /// Download a file, ensuring its contents match the hashes provided by Modrinth
+    #[instrument(skip(self, version_file, progress), fields(filename = %version_file.filename))]
+    async fn download_version_file(
+        &self,
+        version_file: &VersionFile,
+        progress: &ProgressReporter,
+    ) -> LibResult<Bytes> {
+        // Prefer the strongest hash available, an unverified file is never installed
+        let (expected, is_sha512) = match (&version_file.hashes.sha512, &version_file.hashes.sha1) {
+            (Some(sha512), _) => (sha512, true),
+            (None, Some(sha1)) => (sha1, false),
+            (None, None) => return Err(error::Error::MissingChecksum),
+        };
+
+        let bytes = self.download(version_file.url.as_str(), progress).await?;
+        let hashes = Hashes::get_hashes_from_bytes(&bytes);
+        let found = if is_sha512 {
+            hashes.sha512
+        } else {
+            hashes.sha1
+        };
+
+        if expected.eq_ignore_ascii_case(&found) {
+            Ok(bytes)
+        } else {
+            Err(error::Error::ChecksumMismatch {
+                expected: expected.clone(),
+                found,
+            })
+        }
+    }
+
+    /// Download the contents of `url` chunk by chunk, reporting the downloaded bytes as they come in
+    async fn download(&self, url: &str, progress: &ProgressReporter) -> LibResult<Bytes> {
+        let mut response = HTTP.send(HTTP.get(url), true).await?;
+
+        let mut bytes = BytesMut::with_capacity(response.content_length().unwrap_or(0) as usize);
+
+        while let Some(chunk) = response.chunk().await? {
+            progress.add_bytes(chunk.len() as u64);
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(bytes.freeze())
+    }
+
+    #[instrument(skip(self, progress))]
     pub(crate) async fn create_mod_file(
         &self,
         modrinth_id: String,
         game_version: String,
         modloader: ModLoader,
+        progress: &ProgressReporter,
     ) -> LibResult<(ModFileData, Bytes)> {
-        match self.ferinth.get_project(modrinth_id.as_str()).await {
+        let path = format!("/project/{}", modrinth_id);
+
+        match self.get::<Project>(&path, &[], PROJECT_TTL).await {
             Ok(project) => {
                 let modrinth = ModrinthData {
-                    id: project.id,
+                    id: project.value.id,
                     latest_valid_version: None,
+                    latest_version_id: None,
+                    latest_version_number: None,
+                    stale: false,
+                    project: None,
                 };
 
                 let sources = Sources {
@@ -156,45 +408,305 @@ impl Modrinth {
                     sourced_from: CurrentSource::Modrinth,
                     loaders,
                     sources,
+                    pin: None,
+                    release_channel: None,
+                    state_reason: None,
+                    overridden_state: None,
                 };
 
-                self.check_for_updates(&mut mod_file, None, &game_version)
+                self.check_for_updates(&mut mod_file, None, &game_version, modloader)
                     .await?;
 
-                let bytes = self.update_mod(&mod_file).await?;
+                let size = mod_file
+                    .sources
+                    .modrinth
+                    .as_ref()
+                    .and_then(|modrinth_data| modrinth_data.latest_valid_version.as_ref())
+                    .map_or(0, |version_file| version_file.size);
+
+                progress.set_phase(JobPhase::Downloading, 1);
+                progress.add_bytes_total(size as u64);
+
+                let bytes = self.update_mod(&mod_file, progress).await?;
+                progress.item_done();
                 Ok((mod_file, bytes))
             }
             Err(_err) => Err(error::Error::NotValidModrinthId),
         }
     }
 
+    /// List every version of a project, without any filtering
+    #[instrument(skip(self))]
+    pub(crate) async fn list_all_versions(&self, modrinth_id: &str) -> LibResult<Vec<Version>> {
+        let path = format!("/project/{}/version", modrinth_id);
+        Ok(self.get(&path, &[], VERSION_LIST_TTL).await?.value)
+    }
+
+    /// Gather the changelogs of every version between the installed file and the latest valid version, newest first
+    #[instrument(skip(self, mod_data))]
+    pub(crate) async fn get_changelogs(
+        &self,
+        mod_data: &ModFileData,
+        hashes: &Hashes,
+    ) -> LibResult<Vec<Changelog>> {
+        let modrinth_data = mod_data
+            .sources
+            .modrinth
+            .as_ref()
+            .ok_or(error::Error::NoModrinthDataError)?;
+
+        let latest_file = modrinth_data
+            .latest_valid_version
+            .as_ref()
+            .ok_or(error::Error::InvalidLatestVersionError)?;
+
+        let version_list = self.list_all_versions(&modrinth_data.id).await?;
+
+        let contains_hash = |version: &&Version, sha1: Option<&str>| {
+            version
+                .files
+                .iter()
+                .any(|file| file.hashes.sha1.as_deref() == sha1)
+        };
+
+        let latest = version_list
+            .iter()
+            .find(|version| {
+                Some(&version.id) == modrinth_data.latest_version_id.as_ref()
+                    || contains_hash(version, latest_file.hashes.sha1.as_deref())
+            })
+            .ok_or(error::Error::InvalidLatestVersionError)?;
+
+        let installed = version_list
+            .iter()
+            .find(|version| contains_hash(version, Some(hashes.sha1.as_str())));
+
+        let mut changelogs: Vec<&Version> = match installed {
+            Some(installed) => version_list
+                .iter()
+                .filter(|version| {
+                    version.date_published > installed.date_published
+                        && version.date_published <= latest.date_published
+                })
+                .collect(),
+            // Without knowing where the file stands, only the target version is relevant
+            None => vec![latest],
+        };
+
+        changelogs.sort_by(|a, b| b.date_published.cmp(&a.date_published));
+
+        Ok(changelogs
+            .into_iter()
+            .map(|version| Changelog {
+                version_number: version.version_number.clone(),
+                version_type: version.version_type,
+                changelog: version.changelog.clone(),
+            })
+            .collect())
+    }
+
     #[instrument(skip(self))]
     async fn list_versions(
         &self,
         modrinth_id: &str,
-        loaders: &Vec<ModLoader>,
+        loader: ModLoader,
         game_version: &str,
-    ) -> LibResult<Vec<ferinth::structures::version_structs::Version>> {
-        let query_params = ListVersionsParams {
-            loaders: Some(loaders.iter().map(|loader| (*loader).into()).collect()),
-            game_versions: Some(vec![game_version].iter().map(ToString::to_string).collect()),
-            featured: None,
-        };
+    ) -> LibResult<Cached<Vec<Version>>> {
+        let query = [
+            ("loaders", serde_json::to_string(loader_names(loader))?),
+            ("game_versions", serde_json::to_string(&[game_version])?),
+        ];
 
-        Ok(self
-            .ferinth
-            .list_versions(modrinth_id, Some(query_params))
-            .await?)
+        self.get(
+            &format!("/project/{}/version", modrinth_id),
+            &query,
+            VERSION_LIST_TTL,
+        )
+        .await
     }
 }
 
-fn accepted_versions_vec() -> Vec<VersionType> {
-    let min_ver = CONF.lock().modrinth_version_type;
-    let ver_arr = [VersionType::Release, VersionType::Beta, VersionType::Alpha];
-    let mut allowed_versions = Vec::new();
-    for version in ver_arr {
-        allowed_versions.push(version)
+/// Start tracking a Modrinth project for a file
+fn track_modrinth_project(mod_data: &mut ModFileData, id: String) {
+    mod_data.sources.modrinth = Some(ModrinthData {
+        id,
+        latest_valid_version: None,
+        latest_version_id: None,
+        latest_version_number: None,
+        stale: false,
+        project: None,
+    });
+
+    // If the source has not been set by the user, automatically track Modrinth
+    if mod_data.sourced_from == CurrentSource::None {
+        mod_data.sourced_from = CurrentSource::Modrinth;
+    }
+}
+
+/// Set the state and latest valid version of a file from the versions available for it
+fn apply_version_list(
+    mod_data: &mut ModFileData,
+    hashes: Option<&Hashes>,
+    version_list: &[Version],
+    loader: ModLoader,
+    stale: bool,
+) {
+    let ignored_version = match &mod_data.pin {
+        Some(Pin::IgnoreVersion { version_id }) => Some(version_id.clone()),
+        _ => None,
+    };
+
+    let accepted_version_types = accepted_versions_vec(mod_data);
+
+    let modrinth_data = match &mut mod_data.sources.modrinth {
+        Some(modrinth_data) => modrinth_data,
+        None => return,
+    };
+
+    modrinth_data.stale = stale;
+
+    // The state is decided anew, an earlier one has nothing left to be restored to
+    mod_data.overridden_state = None;
+
+    if version_list.is_empty() {
+        // No versions could be found that match the criteria, therefore the mod is incompatible for this version
+        mod_data.state = FileState::Invalid;
+        return;
+    }
+
+    // There are results, consider the state to be up to date unless proven otherwise
+    mod_data.state = FileState::Current;
+
+    let mut filtered_list: Vec<&Version> = version_list
+        .iter()
+        .filter(|version| is_accepted_version(loader, &accepted_version_types, version))
+        .collect();
+
+    // The API does not guarantee any order, the newest version is the one offered
+    filtered_list.sort_by(|a, b| b.date_published.cmp(&a.date_published));
+
+    let is_local_version = |version: &Version| {
+        hashes.map_or(false, |hashes| {
+            version
+                .files
+                .iter()
+                .any(|file| file.hashes.sha1 == Some(hashes.sha1.clone()))
+        })
+    };
+
+    // An ignored version is never offered, unless it is the one already in use
+    let ignores_update = filtered_list
+        .iter()
+        .any(|version| Some(&version.id) == ignored_version.as_ref() && !is_local_version(version));
+    filtered_list.retain(|version| {
+        Some(&version.id) != ignored_version.as_ref() || is_local_version(version)
+    });
+
+    if let Some(latest) = filtered_list.first() {
+        // If the latest version contains a file with the hash of our local copy, it means it is already on the latest possible version
+        // If hashes aren't provided assume its outdated
+        if !is_local_version(latest) {
+            modrinth_data.latest_valid_version = best_file(latest, loader).cloned();
+            modrinth_data.latest_version_id = Some(latest.id.clone());
+            modrinth_data.latest_version_number = Some(latest.version_number.clone());
+            mod_data.state = FileState::Outdated;
+        }
+    }
+
+    if ignores_update && mod_data.state == FileState::Current {
+        mod_data.state = FileState::Pinned;
+    }
+}
+
+fn is_accepted_version(
+    loader: ModLoader,
+    accepted_version_types: &[VersionType],
+    version: &Version,
+) -> bool {
+    accepted_version_types
+        .iter()
+        .any(|ver_type| ver_type == &version.version_type)
+        && supports_loader(version, loader)
+        && !version.files.is_empty()
+}
+
+/// Whether the latest version returned by the bulk update endpoint is the one that would be picked from the full version list
+fn is_usable_latest_version(mod_data: &ModFileData, version: &Version, loader: ModLoader) -> bool {
+    let is_ignored = match &mod_data.pin {
+        Some(Pin::IgnoreVersion { version_id }) => version_id == &version.id,
+        _ => false,
+    };
+
+    !is_ignored && is_accepted_version(loader, &accepted_versions_vec(mod_data), version)
+}
+
+/// The loader the updates of a file have to support: the active one if the file runs on it, otherwise whatever the file was made for
+///
+/// A jar that ships both manifests is only held to both loaders when the active one is not known, so that an update never drops the loader in use
+fn target_loader(file_loaders: &[ModLoader], active_loader: ModLoader) -> ModLoader {
+    let runs_on = |loader| {
+        file_loaders
+            .iter()
+            .any(|file_loader| *file_loader == loader || *file_loader == ModLoader::Both)
+    };
+
+    if active_loader != ModLoader::Both && runs_on(active_loader) {
+        active_loader
+    } else if runs_on(ModLoader::Forge) && runs_on(ModLoader::Fabric) {
+        ModLoader::Both
+    } else {
+        file_loaders.first().copied().unwrap_or(active_loader)
+    }
+}
+
+/// Whether a version runs on `loader`, which for [ModLoader::Both] means running on every loader
+fn supports_loader(version: &Version, loader: ModLoader) -> bool {
+    loader_names(loader).iter().all(|name| {
+        version
+            .loaders
+            .iter()
+            .any(|version_loader| version_loader == name)
+    })
+}
+
+/// The file of a version meant for `loader`, as multi-loader versions can ship a separate jar for each loader
+fn best_file(version: &Version, loader: ModLoader) -> Option<&VersionFile> {
+    let wanted = loader_names(loader);
+
+    // On ties the earliest file wins, hence the reversal
+    version.files.iter().rev().max_by_key(|file| {
+        let filename = file.filename.to_lowercase();
+        let mentions_wanted = wanted.iter().any(|name| filename.contains(name));
+        let mentions_other = ALL_LOADER_NAMES
+            .iter()
+            .filter(|name| !wanted.contains(name))
+            .any(|name| filename.contains(name));
+
+        (!mentions_other, mentions_wanted, file.primary)
+    })
+}
+
+/// The names Modrinth uses for the loaders a file has to support
+fn loader_names(loader: ModLoader) -> &'static [&'static str] {
+    match loader {
+        ModLoader::Forge => &["forge"],
+        ModLoader::Fabric => &["fabric"],
+        ModLoader::Both => &["fabric", "forge"],
     }
-    allowed_versions.push(min_ver);
-    allowed_versions
+}
+
+/// Every release type at least as stable as the channel of the file, or the global one if it does not override it
+fn accepted_versions_vec(mod_data: &ModFileData) -> Vec<VersionType> {
+    let min_ver = mod_data
+        .release_channel
+        .unwrap_or_else(|| CONF.lock().modrinth_version_type);
+
+    // From most to least stable
+    let ver_arr = [VersionType::Release, VersionType::Beta, VersionType::Alpha];
+    let min_position = ver_arr
+        .iter()
+        .position(|version_type| *version_type == min_ver)
+        .unwrap_or(0);
+
+    ver_arr[..=min_position].to_vec()
 }
@@ -0,0 +1,170 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use futures::future;
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use tracing::{debug, instrument, warn};
+
+use crate::{
+    error::LibResult,
+    http::HTTP,
+    mod_file::{from_file::resize_icon, ModFile, ProjectInfo},
+    paths,
+    settings::CONF,
+};
+
+use super::{Modrinth, PROJECT_TTL};
+
+#[derive(Deserialize)]
+struct ProjectResponse {
+    id: String,
+    title: String,
+    description: String,
+    #[serde(default)]
+    categories: Vec<String>,
+    icon_url: Option<String>,
+    issues_url: Option<String>,
+    source_url: Option<String>,
+    wiki_url: Option<String>,
+    discord_url: Option<String>,
+    #[serde(default)]
+    donation_urls: Vec<DonationUrl>,
+}
+
+#[derive(Deserialize)]
+struct DonationUrl {
+    platform: String,
+    url: String,
+}
+
+impl Modrinth {
+    /// Attach its project to every file tracked on Modrinth, along with the icon of the project
+    #[instrument(skip(self, mod_list), fields(amount = mod_list.len()))]
+    pub(crate) async fn fetch_projects(&self, mod_list: &mut [ModFile]) -> LibResult<()> {
+        let mut ids: Vec<&str> = mod_list
+            .iter()
+            .filter_map(|mod_file| mod_file.data.sources.modrinth.as_ref())
+            .map(|modrinth_data| modrinth_data.id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        // Every project is fetched at once, sorted so that the cached response is found again
+        let projects = self
+            .get::<Vec<ProjectResponse>>(
+                "/projects",
+                &[("ids", serde_json::to_string(&ids)?)],
+                PROJECT_TTL,
+            )
+            .await?
+            .value;
+
+        let projects: HashMap<String, ProjectInfo> =
+            future::join_all(projects.into_iter().map(|project| async move {
+                let icon = match &project.icon_url {
+                    Some(url) if !url.is_empty() => cached_icon(url).await,
+                    _ => None,
+                };
+
+                (project.id.clone(), project_info(project, icon))
+            }))
+            .await
+            .into_iter()
+            .collect();
+
+        for mod_file in mod_list {
+            if let Some(modrinth_data) = &mut mod_file.data.sources.modrinth {
+                if let Some(project) = projects.get(&modrinth_data.id) {
+                    modrinth_data.project = Some(project.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Load the icons of the projects that were fetched before, without sending any request
+#[instrument(skip(mod_list), fields(amount = mod_list.len()))]
+pub(crate) fn load_cached_icons(mod_list: &mut [ModFile]) {
+    let projects = mod_list
+        .iter_mut()
+        .filter_map(|mod_file| mod_file.data.sources.modrinth.as_mut())
+        .filter_map(|modrinth_data| modrinth_data.project.as_mut())
+        .filter(|project| project.icon.is_none());
+
+    for project in projects {
+        if let Some(url) = &project.icon_url {
+            project.icon = fs::read(icon_path(url))
+                .ok()
+                .and_then(|raw| resize_icon(&raw).ok());
+        }
+    }
+}
+
+fn project_info(project: ProjectResponse, icon: Option<Vec<u8>>) -> ProjectInfo {
+    let links = [
+        ("Issues", project.issues_url),
+        ("Source", project.source_url),
+        ("Wiki", project.wiki_url),
+        ("Discord", project.discord_url),
+    ]
+    .into_iter()
+    .filter_map(|(name, url)| Some((name.to_string(), url?)))
+    .chain(
+        project
+            .donation_urls
+            .into_iter()
+            .map(|donation| (donation.platform, donation.url)),
+    )
+    .filter(|(_, url)| !url.is_empty())
+    .collect();
+
+    ProjectInfo {
+        title: project.title,
+        summary: project.description,
+        categories: project.categories,
+        links,
+        icon_url: project.icon_url,
+        icon,
+    }
+}
+
+/// Icons are only decoration, so any failure just leaves the project without one
+async fn cached_icon(url: &str) -> Option<Vec<u8>> {
+    let path = icon_path(url);
+
+    let raw = match fs::read(&path) {
+        Ok(raw) => raw,
+        Err(_) if CONF.lock().offline_mode => return None,
+        Err(_) => {
+            let bytes = match HTTP.send(HTTP.get(url), true).await {
+                Ok(response) => response.bytes().await.ok()?,
+                Err(error) => {
+                    debug!(%url, %error, "Could not fetch the icon of a project");
+                    return None;
+                }
+            };
+
+            // The icon can still be shown without being cached
+            if let Err(error) = fs::create_dir_all(paths::ICON_CACHE_DIR.as_path())
+                .and_then(|()| fs::write(&path, &bytes))
+            {
+                warn!(%error, "Could not cache the icon of a project");
+            }
+
+            bytes.to_vec()
+        }
+    };
+
+    resize_icon(&raw).ok()
+}
+
+/// Icons are stored by the hash of their URL, which changes along with the icon
+fn icon_path(url: &str) -> PathBuf {
+    paths::ICON_CACHE_DIR.join(hex::encode(Sha1::digest(url.as_bytes())))
+}
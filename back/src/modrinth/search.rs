@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use futures::future;
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use crate::{
+    error::LibResult,
+    http::HTTP,
+    messages::{ModSide, SearchHit, SearchPage, SearchQuery},
+    mod_file::from_file::resize_icon,
+};
+
+use super::{loader_names, Modrinth, ALL_LOADER_NAMES};
+
+/// The amount of mods shown per page of results
+pub(crate) const SEARCH_PAGE_SIZE: usize = 20;
+
+/// Search results change often, but not so much that paging back and forth should fetch them again
+const SEARCH_TTL: Duration = Duration::from_secs(5 * 60);
+const CATEGORIES_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    hits: Vec<SearchResult>,
+    total_hits: usize,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    project_id: String,
+    title: String,
+    description: String,
+    author: String,
+    downloads: u64,
+    #[serde(default)]
+    display_categories: Vec<String>,
+    icon_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Category {
+    name: String,
+    project_type: String,
+}
+
+impl Modrinth {
+    /// Fetch a page of mods matching `query`, along with their icons
+    #[instrument(skip(self))]
+    pub(crate) async fn search(&self, query: &SearchQuery) -> LibResult<SearchPage> {
+        let params = [
+            ("query", query.text.clone()),
+            ("facets", facets(query)),
+            ("offset", (query.page * SEARCH_PAGE_SIZE).to_string()),
+            ("limit", SEARCH_PAGE_SIZE.to_string()),
+        ];
+
+        let response = self
+            .get::<SearchResponse>("/search", &params, SEARCH_TTL)
+            .await?
+            .value;
+
+        let hits = future::join_all(response.hits.into_iter().map(|result| async move {
+            let icon = match &result.icon_url {
+                Some(url) if !url.is_empty() => fetch_icon(url).await,
+                _ => None,
+            };
+
+            SearchHit {
+                project_id: result.project_id,
+                title: result.title,
+                description: result.description,
+                author: result.author,
+                downloads: result.downloads,
+                // The loaders are already part of the query
+                categories: result
+                    .display_categories
+                    .into_iter()
+                    .filter(|category| !ALL_LOADER_NAMES.contains(&category.as_str()))
+                    .collect(),
+                icon,
+            }
+        }))
+        .await;
+
+        Ok(SearchPage {
+            hits,
+            page: query.page,
+            total_pages: (response.total_hits + SEARCH_PAGE_SIZE - 1) / SEARCH_PAGE_SIZE,
+        })
+    }
+
+    /// The names of the categories mods can be in, without the loaders
+    #[instrument(skip(self))]
+    pub(crate) async fn list_categories(&self) -> LibResult<Vec<String>> {
+        let categories = self
+            .get::<Vec<Category>>("/tag/category", &[], CATEGORIES_TTL)
+            .await?
+            .value;
+
+        Ok(categories
+            .into_iter()
+            .filter(|category| category.project_type == "mod")
+            .map(|category| category.name)
+            .collect())
+    }
+}
+
+/// Build the facets of a search, the inner lists are joined with OR and the outer one with AND
+fn facets(query: &SearchQuery) -> String {
+    let mut facets = vec![vec!["project_type:mod".to_string()]];
+
+    facets.push(
+        loader_names(query.modloader)
+            .iter()
+            .map(|loader| format!("categories:{}", loader))
+            .collect(),
+    );
+
+    if let Some(game_version) = &query.game_version {
+        facets.push(vec![format!("versions:{}", game_version)]);
+    }
+
+    for category in &query.categories {
+        facets.push(vec![format!("categories:{}", category)]);
+    }
+
+    if let Some(side) = query.side {
+        let side = match side {
+            ModSide::Client => "client_side",
+            ModSide::Server => "server_side",
+        };
+
+        facets.push(vec![
+            format!("{}:required", side),
+            format!("{}:optional", side),
+        ]);
+    }
+
+    serde_json::to_string(&facets).expect("A list of strings can always be serialized")
+}
+
+/// Icons are only decoration, so any failure just leaves the mod without one
+async fn fetch_icon(url: &str) -> Option<Vec<u8>> {
+    let result = async {
+        let bytes = HTTP.send(HTTP.get(url), true).await?.bytes().await?;
+        resize_icon(&bytes)
+    }
+    .await;
+
+    match result {
+        Ok(icon) => Some(icon),
+        Err(error) => {
+            debug!(%url, %error, "Could not fetch the icon of a search result");
+            None
+        }
+    }
+}
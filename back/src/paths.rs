@@ -1,34 +1,52 @@
 use std::path::PathBuf;
 use tracing::instrument;
 
+pub const CONFIG_DIR_VAR: &str = "MCUBED_CONFIG_DIR";
+
 lazy_static::lazy_static! {
     static ref BASE_DIRS: directories::BaseDirs = directories::BaseDirs::new().expect("Could not get base dirs");
     static ref HOME_DIR: std::path::PathBuf = BASE_DIRS.home_dir().to_owned();
-    pub static ref CONFIG_DIR: std::path::PathBuf = BASE_DIRS.config_dir().join("mCubed");
+    /// Can be moved with [CONFIG_DIR_VAR], which keeps tests away from the real configuration
+    pub static ref CONFIG_DIR: std::path::PathBuf = std::env::var_os(CONFIG_DIR_VAR)
+        .map_or_else(|| BASE_DIRS.config_dir().join("mCubed"), PathBuf::from);
+    pub static ref BACKUP_DIR: std::path::PathBuf = CONFIG_DIR.join("backups");
+    pub static ref VERSIONS_DIR: std::path::PathBuf = CONFIG_DIR.join("versions");
+    pub static ref MOD_CACHE_DIR: std::path::PathBuf = CONFIG_DIR.join("mod_cache");
+    pub static ref HTTP_CACHE_DIR: std::path::PathBuf = CONFIG_DIR.join("http_cache");
+    pub static ref ICON_CACHE_DIR: std::path::PathBuf = CONFIG_DIR.join("icon_cache");
 }
 
 #[cfg(target_os = "windows")]
 #[instrument(level = "trace")]
-pub fn default_mod_dir() -> PathBuf {
-    HOME_DIR
-        .join("AppData")
-        .join("Roaming")
-        .join(".minecraft")
-        .join("mods")
+pub fn default_minecraft_dir() -> PathBuf {
+    HOME_DIR.join("AppData").join("Roaming").join(".minecraft")
 }
 
 #[cfg(target_os = "linux")]
 #[instrument(level = "trace")]
-pub fn default_mod_dir() -> PathBuf {
-    HOME_DIR.join(".minecraft").join("mods")
+pub fn default_minecraft_dir() -> PathBuf {
+    HOME_DIR.join(".minecraft")
 }
 
 #[cfg(target_os = "macos")]
 #[instrument(level = "trace")]
-pub fn default_mod_dir() -> PathBuf {
+pub fn default_minecraft_dir() -> PathBuf {
     HOME_DIR
         .join("Library")
         .join("ApplicationSupport")
         .join("minecraft")
-        .join("mods")
+}
+
+#[instrument(level = "trace")]
+pub fn default_mod_dir() -> PathBuf {
+    default_minecraft_dir().join("mods")
+}
+
+/// Where applications keep their data (Ex. `~/.local/share` or `%APPDATA%`)
+pub fn data_dir() -> PathBuf {
+    BASE_DIRS.data_dir().to_owned()
+}
+
+pub fn home_dir() -> PathBuf {
+    HOME_DIR.clone()
 }
@@ -1,47 +1,85 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use tracing::{info, instrument, warn};
 
-use crate::{
-    error::{self, LibResult},
-    mod_file::ModFile,
-    paths,
-};
+use crate::{error::LibResult, mod_file::ModFile, paths, settings::CONF};
+
+use super::storage_trait::{read_json, write_json};
 
-use super::storage_trait::StorageTrait;
+/// Bumped whenever the layout of the cache changes, along with a step in [migrate]
+pub const CACHE_SCHEMA_VERSION: u64 = 1;
 
-#[derive(Debug, Clone, Deserialize, Default, Serialize)]
+/// The name of the cache older versions kept inside the mods folder
+const LEGACY_FILE_NAME: &str = "mods.mCubed.json";
+
+/// The data of every file seen in a mods folder, kept in the config dir with one cache per folder
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CacheStorage {
+    /// The layout the cache was written with, caches from before versioning count as 0
+    #[serde(default)]
+    pub schema_version: u64,
+    /// The mods folder the cache belongs to
+    #[serde(default)]
+    pub mod_folder: PathBuf,
     pub storage: Vec<ModFile>,
 }
 
-impl<'a> StorageTrait<'a> for CacheStorage {
-    const FILE_NAME: &'static str = "mods.mCubed.json";
-
-    fn get_folder() -> PathBuf {
-        paths::default_mod_dir()
+impl Default for CacheStorage {
+    fn default() -> Self {
+        Self {
+            schema_version: CACHE_SCHEMA_VERSION,
+            mod_folder: PathBuf::new(),
+            storage: Vec::new(),
+        }
     }
 }
 
 impl CacheStorage {
+    /// Load the cache of the mods folder in the settings, migrating it if it was written by an older version
+    #[instrument(skip(self))]
     pub fn load_list_cache(&mut self) -> LibResult<()> {
-        match Self::load() {
-            Ok(cache) => {
-                self.storage = cache.storage;
-                Ok(())
-            }
-            Err(error) => match error {
-                error::Error::SerdeError(err) => {
-                    tracing::error!("Failed to parse cache: {}", err);
-                    Err(error::Error::FailedToParseEntryCache { err })
+        let mod_folder = CONF.lock().mod_folder_path.clone();
+
+        *self = Self {
+            mod_folder: mod_folder.clone(),
+            ..Self::default()
+        };
+
+        if let Some(value) = read_json(&cache_path(&mod_folder))? {
+            self.storage = migrate(value);
+            return Ok(());
+        }
+
+        // Nothing is stored for this folder yet, pick up what older versions kept in the mods folder itself
+        for legacy_path in legacy_paths(&mod_folder) {
+            if let Some(value) = read_json(&legacy_path)? {
+                info!(path = %legacy_path.display(), "Moving the cache out of the mods folder");
+
+                self.storage = migrate(value);
+                self.save_list_cache()?;
+
+                // The cache in the default folder was shared by every folder, so it stays until that folder is used
+                if legacy_path.starts_with(&mod_folder) {
+                    if let Err(error) = fs::remove_file(&legacy_path) {
+                        warn!(%error, "Could not remove the old cache");
+                    }
                 }
-                _ => Err(error),
-            },
+
+                break;
+            }
         }
+
+        Ok(())
     }
 
     pub fn save_list_cache(&self) -> LibResult<()> {
-        self.save()
+        write_json(&cache_path(&self.mod_folder), self)
     }
 
     pub fn get_cache(&self) -> &Vec<ModFile> {
@@ -52,3 +90,68 @@ impl CacheStorage {
         &mut self.storage
     }
 }
+
+/// Caches are stored by the hash of the folder they belong to, so that moving between folders does not mix their entries
+fn cache_path(mod_folder: &Path) -> PathBuf {
+    let mod_folder = mod_folder
+        .canonicalize()
+        .unwrap_or_else(|_| mod_folder.to_path_buf());
+    let name = hex::encode(Sha1::digest(mod_folder.to_string_lossy().as_bytes()));
+
+    paths::MOD_CACHE_DIR.join(format!("{}.json", name))
+}
+
+/// Where older versions stored the cache, which regardless of the settings was the default mods folder
+fn legacy_paths(mod_folder: &Path) -> Vec<PathBuf> {
+    let mut legacy_paths = vec![mod_folder.join(LEGACY_FILE_NAME)];
+
+    let default_path = paths::default_mod_dir().join(LEGACY_FILE_NAME);
+    if !legacy_paths.contains(&default_path) {
+        legacy_paths.push(default_path);
+    }
+
+    legacy_paths
+}
+
+/// Bring a cache up to the current layout and read its entries
+///
+/// Entries that still cannot be read are dropped one by one, so that a single bad entry does not take the rest with it
+fn migrate(mut value: Value) -> Vec<ModFile> {
+    let schema_version = value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    if schema_version > CACHE_SCHEMA_VERSION {
+        warn!(
+            schema_version,
+            "The cache was written by a newer version, only the entries that can be read are kept"
+        );
+    }
+
+    for from in schema_version..CACHE_SCHEMA_VERSION {
+        info!(from, "Migrating the cache");
+
+        match from {
+            // The unversioned cache has the same entries, every field added since then has a default
+            0 => {}
+            _ => unreachable!("Every older version has a migration step"),
+        }
+    }
+
+    let entries = match value.get_mut("storage").map(Value::take) {
+        Some(Value::Array(entries)) => entries,
+        _ => Vec::new(),
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|entry| match serde_json::from_value(entry) {
+            Ok(mod_file) => Some(mod_file),
+            Err(error) => {
+                warn!(%error, "Dropping a cache entry that could not be read");
+                None
+            }
+        })
+        .collect()
+}
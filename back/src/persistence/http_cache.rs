@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tracing::{debug, instrument};
+
+use crate::{error::LibResult, paths};
+
+use super::storage_trait::write_json;
+
+/// A response kept on disk, reused as is while it is fresh and revalidated with its ETag once it is not
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedResponse {
+    pub etag: Option<String>,
+    /// When the response was fetched or last revalidated, in seconds since the Unix epoch
+    pub fetched_at: u64,
+    pub body: String,
+}
+
+impl CachedResponse {
+    pub(crate) fn new(etag: Option<String>, body: String) -> Self {
+        Self {
+            etag,
+            fetched_at: now(),
+            body,
+        }
+    }
+
+    /// Load the response stored for `url`, if any
+    #[instrument(level = "trace")]
+    pub(crate) fn load(url: &str) -> Option<Self> {
+        let contents = fs::read(path_for(url)).ok()?;
+
+        match serde_json::from_slice(&contents) {
+            Ok(cached) => Some(cached),
+            Err(error) => {
+                debug!(%error, "Ignoring unreadable cached response");
+                None
+            }
+        }
+    }
+
+    /// Store the response for `url`, replacing the previous one only once it is fully written
+    pub(crate) fn save(&self, url: &str) -> LibResult<()> {
+        write_json(&path_for(url), self)
+    }
+
+    /// Mark the response as confirmed to be up to date
+    pub(crate) fn touch(&mut self) {
+        self.fetched_at = now();
+    }
+
+    pub(crate) fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+}
+
+/// Responses are stored by the hash of their URL, as URLs can contain characters that are not valid in file names
+fn path_for(url: &str) -> PathBuf {
+    let name = hex::encode(Sha1::digest(url.as_bytes()));
+    paths::HTTP_CACHE_DIR.join(format!("{}.json", name))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
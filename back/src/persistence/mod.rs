@@ -1,3 +1,7 @@
 pub mod cache;
+pub(crate) mod http_cache;
 pub mod settings;
 mod storage_trait;
+pub mod versions;
+
+pub(crate) use storage_trait::take_load_warnings;
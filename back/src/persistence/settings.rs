@@ -1,28 +1,87 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{env, fs, path::PathBuf, sync::Arc};
 
 use ferinth::structures::version_structs::VersionType;
 use lazy_static::lazy_static;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
-use crate::{error::LibResult, paths};
+use crate::{
+    error::{self, LibResult},
+    instances::Instance,
+    mod_file::ModLoader,
+    paths,
+};
 
 use super::storage_trait::StorageTrait;
 
+/// Overrides [modrinth_api_url](SettingsBuilder::modrinth_api_url) without changing the saved settings
+pub const MODRINTH_API_URL_VAR: &str = "MCUBED_MODRINTH_API_URL";
+/// Overrides [curseforge_api_url](SettingsBuilder::curseforge_api_url) without changing the saved settings
+pub const CURSEFORGE_API_URL_VAR: &str = "MCUBED_CURSEFORGE_API_URL";
+/// Overrides [version_manifest_url](SettingsBuilder::version_manifest_url) without changing the saved settings
+pub const VERSION_MANIFEST_URL_VAR: &str = "MCUBED_VERSION_MANIFEST_URL";
+
 lazy_static! {
     pub static ref CONF: Arc<Mutex<SettingsBuilder>> = Arc::new(Mutex::new(
         SettingsBuilder::load_from_file().unwrap_or_default()
     ));
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
 pub struct SettingsBuilder {
     /// The size of the images the icon of a mod will be resized to
     pub icon_resize_size: u32,
+    /// Show the icon and summary of the Modrinth project of a mod over the ones in its files
+    pub prefer_remote_metadata: bool,
     /// The minimum release type when fetching from modrinth
     pub modrinth_version_type: VersionType,
     /// The path to the "mods" folder
     pub mod_folder_path: PathBuf,
+    /// How many replaced versions of a mod are kept to allow rolling back
+    pub max_stored_versions: usize,
+    /// How many files can be downloaded at the same time during bulk updates
+    pub max_concurrent_downloads: usize,
+    /// How many requests can be sent to external services at the same time
+    pub max_concurrent_requests: usize,
+    /// How many times a request that failed for transient reasons is retried
+    pub max_request_retries: u32,
+    /// The delay before the first retry of a request in milliseconds, doubled on every further attempt
+    pub retry_base_delay_ms: u64,
+    /// How long a request can take in seconds, downloading the response included, before it is given up on
+    pub request_timeout_secs: u64,
+    /// Whether to wait when a service reports its rate limit as exceeded instead of failing
+    pub respect_rate_limits: bool,
+    /// Work only from cached responses, without sending any request
+    pub offline_mode: bool,
+    /// The base URL of the Modrinth API
+    pub modrinth_api_url: String,
+    /// The base URL of the CurseForge API
+    pub curseforge_api_url: String,
+    /// The URL of the Minecraft version manifest
+    pub version_manifest_url: String,
+    /// The mods folders adopted from launchers, which can be switched between
+    pub profiles: Vec<Profile>,
+}
+
+/// A mods folder along with what is known about the game that loads it
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub mod_folder_path: PathBuf,
+    pub game_version: Option<String>,
+    pub modloader: Option<ModLoader>,
+}
+
+impl From<&Instance> for Profile {
+    fn from(instance: &Instance) -> Self {
+        Self {
+            name: format!("{} ({})", instance.name, instance.launcher),
+            mod_folder_path: instance.mods_dir(),
+            game_version: instance.game_version.clone(),
+            modloader: instance.modloader,
+        }
+    }
 }
 
 impl SettingsBuilder {
@@ -49,6 +108,13 @@ impl SettingsBuilder {
         self
     }
 
+    /// Set whether the metadata from Modrinth is shown over the one in the files
+    #[must_use]
+    pub fn prefer_remote_metadata(mut self, prefer: bool) -> Self {
+        self.prefer_remote_metadata = prefer;
+        self
+    }
+
     /// Set the modrinth release type
     #[must_use]
     pub fn modrinth_version_type(mut self, version_type: VersionType) -> Self {
@@ -63,6 +129,174 @@ impl SettingsBuilder {
         self
     }
 
+    /// Set how many replaced versions of a mod are kept
+    #[must_use]
+    pub fn max_stored_versions(mut self, amount: usize) -> Self {
+        self.max_stored_versions = amount;
+        self
+    }
+
+    /// Set how many files can be downloaded at the same time
+    #[must_use]
+    pub fn max_concurrent_downloads(mut self, amount: usize) -> Self {
+        self.max_concurrent_downloads = amount;
+        self
+    }
+
+    /// Set how many requests can be sent at the same time
+    #[must_use]
+    pub fn max_concurrent_requests(mut self, amount: usize) -> Self {
+        self.max_concurrent_requests = amount;
+        self
+    }
+
+    /// Set how many times a failed request is retried
+    #[must_use]
+    pub fn max_request_retries(mut self, amount: u32) -> Self {
+        self.max_request_retries = amount;
+        self
+    }
+
+    /// Set the delay before the first retry of a request
+    #[must_use]
+    pub fn retry_base_delay_ms(mut self, delay: u64) -> Self {
+        self.retry_base_delay_ms = delay;
+        self
+    }
+
+    /// Set how long a request can take before it is given up on
+    #[must_use]
+    pub fn request_timeout_secs(mut self, timeout: u64) -> Self {
+        self.request_timeout_secs = timeout;
+        self
+    }
+
+    /// Set whether rate limits are waited out
+    #[must_use]
+    pub fn respect_rate_limits(mut self, respect: bool) -> Self {
+        self.respect_rate_limits = respect;
+        self
+    }
+
+    /// Set whether to work offline
+    #[must_use]
+    pub fn offline_mode(mut self, offline: bool) -> Self {
+        self.offline_mode = offline;
+        self
+    }
+
+    /// Set the base URL of the Modrinth API
+    #[must_use]
+    pub fn modrinth_api_url(mut self, url: impl Into<String>) -> Self {
+        self.modrinth_api_url = url.into();
+        self
+    }
+
+    /// Set the base URL of the CurseForge API
+    #[must_use]
+    pub fn curseforge_api_url(mut self, url: impl Into<String>) -> Self {
+        self.curseforge_api_url = url.into();
+        self
+    }
+
+    /// Set the URL of the Minecraft version manifest
+    #[must_use]
+    pub fn version_manifest_url(mut self, url: impl Into<String>) -> Self {
+        self.version_manifest_url = url.into();
+        self
+    }
+
+    /// Add a profile, replacing the one for the same mods folder if there is one
+    #[must_use]
+    pub fn profile(mut self, profile: Profile) -> Self {
+        match self
+            .profiles
+            .iter_mut()
+            .find(|other| other.mod_folder_path == profile.mod_folder_path)
+        {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+        self
+    }
+
+    /// The profile of the mods folder in use, if it was adopted as one
+    #[must_use]
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.mod_folder_path == self.mod_folder_path)
+    }
+
+    /// The Modrinth API URL in use, taking [MODRINTH_API_URL_VAR] into account
+    #[must_use]
+    pub fn active_modrinth_api_url(&self) -> String {
+        env::var(MODRINTH_API_URL_VAR).unwrap_or_else(|_| self.modrinth_api_url.clone())
+    }
+
+    /// The CurseForge API URL in use, taking [CURSEFORGE_API_URL_VAR] into account
+    #[must_use]
+    pub fn active_curseforge_api_url(&self) -> String {
+        env::var(CURSEFORGE_API_URL_VAR).unwrap_or_else(|_| self.curseforge_api_url.clone())
+    }
+
+    /// The version manifest URL in use, taking [VERSION_MANIFEST_URL_VAR] into account
+    #[must_use]
+    pub fn active_version_manifest_url(&self) -> String {
+        env::var(VERSION_MANIFEST_URL_VAR).unwrap_or_else(|_| self.version_manifest_url.clone())
+    }
+
+    /// Check that the settings can be used, the mods folder is only checked if it differs from the one in `current`
+    pub fn validate(&self, current: &Self) -> LibResult<()> {
+        let invalid = |reason: String| Err(error::Error::InvalidSetting(reason));
+
+        if self.mod_folder_path != current.mod_folder_path {
+            let path = &self.mod_folder_path;
+
+            if !path.is_dir() {
+                return invalid(format!("The mods folder {} does not exist", path.display()));
+            }
+
+            // The only reliable way to know whether a folder can be written to is trying to
+            let probe = path.join(".mCubed-write-test");
+            if fs::write(&probe, [])
+                .and_then(|()| fs::remove_file(&probe))
+                .is_err()
+            {
+                return invalid(format!(
+                    "The mods folder {} cannot be written to",
+                    path.display()
+                ));
+            }
+        }
+
+        if self.max_concurrent_downloads == 0 || self.max_concurrent_requests == 0 {
+            return invalid(
+                "At least one download and request have to be allowed at a time".to_string(),
+            );
+        }
+
+        if self.request_timeout_secs == 0 {
+            return invalid("Requests need some time to complete".to_string());
+        }
+
+        if self.max_request_retries > 10 {
+            return invalid("A request cannot be retried more than 10 times".to_string());
+        }
+
+        if !(100..=10_000).contains(&self.retry_base_delay_ms) {
+            return invalid(
+                "The retry delay has to be between 100 milliseconds and 10 seconds".to_string(),
+            );
+        }
+
+        if self.icon_resize_size == 0 {
+            return invalid("Icons cannot be resized to nothing".to_string());
+        }
+
+        Ok(())
+    }
+
     /// Apply the configuration
     pub fn apply(self) {
         let mut changer = CONF.lock();
@@ -74,8 +308,21 @@ impl Default for SettingsBuilder {
     fn default() -> Self {
         Self {
             icon_resize_size: 128,
+            prefer_remote_metadata: false,
             modrinth_version_type: VersionType::Release,
             mod_folder_path: paths::default_mod_dir(),
+            max_stored_versions: 3,
+            max_concurrent_downloads: 4,
+            max_concurrent_requests: 8,
+            max_request_retries: 3,
+            retry_base_delay_ms: 500,
+            request_timeout_secs: 300,
+            respect_rate_limits: true,
+            offline_mode: false,
+            modrinth_api_url: "https://api.modrinth.com/v2".to_string(),
+            curseforge_api_url: "https://api.curseforge.com/v1".to_string(),
+            version_manifest_url: daedalus::minecraft::VERSION_MANIFEST_URL.to_string(),
+            profiles: Vec::new(),
         }
     }
 }
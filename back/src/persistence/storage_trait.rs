@@ -1,10 +1,19 @@
-use crate::error::LibResult;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use crate::error::{self, LibResult};
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, de::IgnoredAny, Deserialize, Serialize};
 use std::{
+    ffi::OsString,
     fs::{self, File},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
+use tracing::{error, warn};
+
+lazy_static! {
+    /// Damaged files found while loading, kept until the frontend can be told about them
+    static ref LOAD_WARNINGS: Mutex<Vec<error::Error>> = Mutex::new(Vec::new());
+}
 
 pub(super) trait StorageTrait<'a>
 where
@@ -16,40 +25,128 @@ where
     fn get_folder() -> PathBuf;
 
     fn load() -> LibResult<Self> {
-        let folder_path = Self::get_folder();
+        let path = Self::get_folder().join(Self::FILE_NAME);
 
-        if !folder_path.exists() {
-            fs::create_dir_all(&folder_path)?;
-        }
-        let path = folder_path.join(Self::FILE_NAME);
-        match std::fs::File::open(&path) {
-            Ok(file) => {
-                let reader = std::io::BufReader::new(file);
-                Ok(serde_json::de::from_reader(reader)?)
+        match read_json(&path)? {
+            Some(value) => Ok(value),
+            None => {
+                let new_value = Self::default();
+                write_json(&path, &new_value)?;
+                Ok(new_value)
             }
-            Err(err) => match err.kind() {
-                std::io::ErrorKind::NotFound => {
-                    let new_value = Self::default();
-                    let mut file = File::create(path)?;
-                    file.write_all(serde_json::to_string(&new_value)?.as_bytes())?;
-                    Ok(new_value)
-                }
-
-                _ => Err(err.into()),
-            },
         }
     }
 
     fn save(&self) -> LibResult<()> {
-        let folder_path = Self::get_folder();
+        write_json(&Self::get_folder().join(Self::FILE_NAME), self)
+    }
+}
+
+/// Take the problems found while loading files since the last call
+pub(crate) fn take_load_warnings() -> Vec<error::Error> {
+    std::mem::take(&mut *LOAD_WARNINGS.lock())
+}
+
+/// Read and parse a JSON file, [None] if it does not exist
+///
+/// A file that cannot be parsed is moved out of the way and replaced by its backup, if the backup can be parsed.
+/// Either way a warning is recorded for [take_load_warnings], failing to move the file only changes what the warning says.
+pub(super) fn read_json<T: DeserializeOwned>(path: &Path) -> LibResult<Option<T>> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) => match err.kind() {
+            std::io::ErrorKind::NotFound => return Ok(None),
+            _ => return Err(err.into()),
+        },
+    };
+
+    let parse_error = match serde_json::from_slice(&contents) {
+        Ok(value) => return Ok(Some(value)),
+        Err(err) => err,
+    };
+
+    error!(path = %path.display(), %parse_error, "Found a damaged file");
+
+    // The damaged file is kept, it may still hold something worth recovering by hand
+    let damaged_path = sibling(path, "damaged");
+    let damaged_path = match fs::rename(path, &damaged_path) {
+        Ok(()) => Some(damaged_path),
+        Err(rename_error) => {
+            error!(%rename_error, "Could not set the damaged file aside");
+            None
+        }
+    };
+
+    let backup_path = sibling(path, "bak");
+    let backup = fs::read(&backup_path)
+        .ok()
+        .and_then(|contents| serde_json::from_slice(&contents).ok());
+
+    let warning = match (&backup, damaged_path) {
+        (Some(_), _) => {
+            // The backup is used either way, it only has to be copied for the next load to find it
+            if let Err(copy_error) = fs::copy(&backup_path, path) {
+                error!(%copy_error, "Could not put the backup in place");
+            }
 
+            error::Error::RestoredFromBackup {
+                path: path.to_path_buf(),
+            }
+        }
+        (None, Some(damaged_path)) => error::Error::DamagedFile {
+            path: path.to_path_buf(),
+            damaged_path,
+        },
+        (None, None) => error::Error::DamagedFileNotMoved {
+            path: path.to_path_buf(),
+        },
+    };
+
+    warn!(%warning);
+    LOAD_WARNINGS.lock().push(warning);
+
+    Ok(backup)
+}
+
+/// Write `value` as JSON, creating the folders leading to `path` if needed
+///
+/// The contents are written to a temporary file that then replaces the old one, so a crash leaves either the old or the new contents in place.
+/// The old contents are kept as a backup, unless they were already damaged.
+pub(super) fn write_json(path: &Path, value: &impl Serialize) -> LibResult<()> {
+    if let Some(folder_path) = path.parent() {
         if !folder_path.exists() {
-            fs::create_dir_all(&folder_path)?;
+            fs::create_dir_all(folder_path)?;
         }
-        let path = folder_path.join(Self::FILE_NAME);
-        let mut file = File::create(path)?;
-        let stringified_json = serde_json::to_string(&self)?;
+    }
+
+    let temp_path = sibling(path, "tmp");
+    {
+        let mut file = File::create(&temp_path)?;
+        let stringified_json = serde_json::to_string(value)?;
         file.write_all(stringified_json.as_bytes())?;
-        Ok(())
+
+        // The new contents have to be on the disk before they replace the old ones
+        file.sync_all()?;
+    }
+
+    if is_readable(path) {
+        fs::copy(path, sibling(path, "bak"))?;
     }
+
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+fn is_readable(path: &Path) -> bool {
+    fs::read(path).map_or(false, |contents| {
+        serde_json::from_slice::<IgnoredAny>(&contents).is_ok()
+    })
+}
+
+/// The path of a file next to `path`, with `extension` added to its name (Ex. `settings.json.bak`)
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
 }
@@ -0,0 +1,176 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, instrument};
+
+use crate::{
+    error::LibResult,
+    mod_file::{Hashes, ModFile, ModFileData},
+    paths,
+    settings::CONF,
+};
+
+use super::storage_trait::StorageTrait;
+
+/// A jar that was replaced by an update and kept around to allow rolling back to it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StoredVersion {
+    /// The mod this version belongs to, see [ModFile::history_key](ModFile::history_key)
+    pub key: String,
+    /// The name the file had in the mods folder
+    pub file_name: String,
+    /// The version reported by the mod itself
+    pub version: String,
+    pub hashes: Hashes,
+    pub data: ModFileData,
+}
+
+impl StoredVersion {
+    /// The path to the stored jar
+    #[must_use]
+    pub fn path(&self) -> PathBuf {
+        paths::VERSIONS_DIR.join(format!("{}.jar", self.hashes.sha1))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, Serialize)]
+pub struct VersionStorage {
+    pub storage: Vec<StoredVersion>,
+}
+
+impl<'a> StorageTrait<'a> for VersionStorage {
+    const FILE_NAME: &'static str = "versions.mCubed.json";
+
+    fn get_folder() -> PathBuf {
+        paths::VERSIONS_DIR.to_path_buf()
+    }
+}
+
+impl VersionStorage {
+    pub fn load_versions(&mut self) -> LibResult<()> {
+        self.storage = Self::load()?.storage;
+        Ok(())
+    }
+
+    pub fn save_versions(&self) -> LibResult<()> {
+        self.save()
+    }
+
+    pub fn get_versions(&self) -> &Vec<StoredVersion> {
+        &self.storage
+    }
+
+    pub fn find(&self, sha1: &str) -> Option<&StoredVersion> {
+        self.storage
+            .iter()
+            .find(|version| version.hashes.sha1 == sha1)
+    }
+
+    /// Move the jar at `jar_path` into the store as a previous version of `mod_file`, dropping the oldest versions past the configured limit
+    ///
+    /// If the history can not be saved, it is left as it was and the jar is moved back to `jar_path`
+    #[instrument(skip(self, mod_file))]
+    pub fn store(&mut self, mod_file: &ModFile, jar_path: &Path) -> LibResult<()> {
+        let key = mod_file.history_key();
+
+        let stored = StoredVersion {
+            key: key.clone(),
+            file_name: mod_file
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            version: mod_file
+                .entries
+                .first()
+                .map(|entry| entry.version.clone())
+                .unwrap_or_default(),
+            hashes: mod_file.hashes.clone(),
+            data: mod_file.data.clone(),
+        };
+
+        fs::create_dir_all(paths::VERSIONS_DIR.as_path())?;
+        let already_stored = self.find(&stored.hashes.sha1).is_some();
+        if fs::rename(jar_path, stored.path()).is_err() {
+            fs::copy(jar_path, stored.path())?;
+            fs::remove_file(jar_path)?;
+        }
+
+        // Only touch the store on disk once the history pointing into it is saved
+        let previous = self.storage.clone();
+
+        // Newest versions are kept at the front
+        self.storage
+            .retain(|version| version.hashes.sha1 != stored.hashes.sha1);
+        self.storage.insert(0, stored.clone());
+
+        let pruned = self.prune(&key);
+
+        if let Err(error) = self.save_versions() {
+            self.storage = previous;
+
+            // A jar that was stored before is still referenced by the old history
+            if !already_stored {
+                if let Err(error) = fs::rename(stored.path(), jar_path) {
+                    error!(%error, "Could not move the jar back out of the store");
+                }
+            }
+
+            return Err(error);
+        }
+
+        for version in pruned {
+            debug!(file_name = %version.file_name, "Removing old stored version");
+
+            if let Err(error) = fs::remove_file(version.path()) {
+                error!(%error, "Could not remove stored version");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a version from the store, returning its data along with where it was, so that it can be put back
+    pub fn take(&mut self, sha1: &str) -> Option<(usize, StoredVersion)> {
+        let index = self
+            .storage
+            .iter()
+            .position(|version| version.hashes.sha1 == sha1)?;
+
+        Some((index, self.storage.remove(index)))
+    }
+
+    /// Undo a [take](Self::take), the jar of the version has to still be in the store
+    pub fn put_back(&mut self, index: usize, version: StoredVersion) {
+        let index = index.min(self.storage.len());
+
+        self.storage.insert(index, version);
+    }
+
+    /// Drop the oldest versions of `key` past the configured limit, returning them so that their jars can be removed
+    fn prune(&mut self, key: &str) -> Vec<StoredVersion> {
+        let max_versions = CONF.lock().max_stored_versions;
+        let mut kept = 0;
+        let mut pruned = Vec::new();
+
+        self.storage.retain(|version| {
+            if version.key != key {
+                return true;
+            }
+
+            kept += 1;
+
+            if kept <= max_versions {
+                true
+            } else {
+                pruned.push(version.clone());
+                false
+            }
+        });
+
+        pruned
+    }
+}
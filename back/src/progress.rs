@@ -0,0 +1,111 @@
+use crossbeam_channel::Sender;
+use parking_lot::Mutex;
+
+use crate::messages::{BackendResponse, JobPhase, JobProgress, RequestId, ToFrontend};
+
+/// Only send byte updates every time this many bytes have been downloaded, to avoid flooding the frontend
+const BYTES_REPORT_STEP: u64 = 256 * 1024;
+
+/// Reports the progress of a job to the frontend, marking it as finished once dropped
+///
+/// Every request has a single reporter, owned by its [`Responder`](crate::responder::Responder), so that
+/// the steps of a job show up as the phases of one entry. Nothing is shown until the first phase starts.
+pub(crate) struct ProgressReporter {
+    job_id: RequestId,
+    back_tx: Sender<BackendResponse>,
+    egui_context: eframe::egui::Context,
+    /// Only jobs running on their own can be cancelled
+    cancellable: bool,
+    progress: Mutex<Option<JobProgress>>,
+}
+
+impl ProgressReporter {
+    pub(crate) fn new(
+        job_id: RequestId,
+        back_tx: &Sender<BackendResponse>,
+        egui_context: &eframe::egui::Context,
+    ) -> Self {
+        Self {
+            job_id,
+            back_tx: back_tx.clone(),
+            egui_context: egui_context.clone(),
+            cancellable: false,
+            progress: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn allow_cancel(&mut self) {
+        self.cancellable = true;
+    }
+
+    /// Move on to a new phase, resetting the counts
+    pub(crate) fn set_phase(&self, phase: JobPhase, items_total: usize) {
+        let mut progress = self.progress.lock();
+
+        let progress = progress.get_or_insert_with(|| JobProgress {
+            job_id: self.job_id,
+            phase,
+            cancellable: self.cancellable,
+            items_done: 0,
+            items_total: 0,
+            bytes_done: 0,
+            bytes_total: 0,
+        });
+        progress.phase = phase;
+        progress.items_done = 0;
+        progress.items_total = items_total;
+        progress.bytes_done = 0;
+        progress.bytes_total = 0;
+
+        self.send(progress.clone());
+    }
+
+    pub(crate) fn item_done(&self) {
+        self.update(|progress| progress.items_done += 1);
+    }
+
+    pub(crate) fn add_bytes_total(&self, bytes: u64) {
+        self.update(|progress| progress.bytes_total += bytes);
+    }
+
+    pub(crate) fn add_bytes(&self, bytes: u64) {
+        let mut progress = self.progress.lock();
+        let progress = match progress.as_mut() {
+            Some(progress) => progress,
+            None => return,
+        };
+
+        let before = progress.bytes_done;
+        progress.bytes_done += bytes;
+
+        if before / BYTES_REPORT_STEP != progress.bytes_done / BYTES_REPORT_STEP
+            || progress.bytes_done >= progress.bytes_total
+        {
+            self.send(progress.clone());
+        }
+    }
+
+    /// Change the current phase, doing nothing if none was started
+    fn update(&self, change: impl FnOnce(&mut JobProgress)) {
+        if let Some(progress) = self.progress.lock().as_mut() {
+            change(progress);
+            self.send(progress.clone());
+        }
+    }
+
+    fn send(&self, progress: JobProgress) {
+        // The frontend may already be gone when shutting down, which is not a problem
+        let _ = self.back_tx.send(BackendResponse {
+            id: self.job_id,
+            message: ToFrontend::Progress { progress },
+        });
+
+        self.egui_context.request_repaint();
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        self.update(|progress| progress.phase = JobPhase::Finished);
+    }
+}
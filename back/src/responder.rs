@@ -0,0 +1,62 @@
+use std::fmt::Debug;
+
+use crossbeam_channel::{SendError, Sender};
+
+use crate::{
+    messages::{BackendResponse, RequestId, ToFrontend},
+    progress::ProgressReporter,
+};
+
+/// Sends messages to the frontend on behalf of a request, tagging them with its id
+pub(crate) struct Responder {
+    id: RequestId,
+    back_tx: Sender<BackendResponse>,
+    egui_context: eframe::egui::Context,
+    progress: ProgressReporter,
+}
+
+impl Debug for Responder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Responder").field("id", &self.id).finish()
+    }
+}
+
+impl Responder {
+    pub(crate) fn new(
+        id: RequestId,
+        back_tx: &Sender<BackendResponse>,
+        egui_context: &eframe::egui::Context,
+    ) -> Self {
+        Self {
+            id,
+            back_tx: back_tx.clone(),
+            egui_context: egui_context.clone(),
+            progress: ProgressReporter::new(id, back_tx, egui_context),
+        }
+    }
+
+    pub(crate) fn id(&self) -> RequestId {
+        self.id
+    }
+
+    /// The progress of the job started by the request, which is marked as finished once the responder is dropped
+    pub(crate) fn progress(&self) -> &ProgressReporter {
+        &self.progress
+    }
+
+    /// Let the user cancel the job, for jobs that are not run in the main loop
+    pub(crate) fn allow_cancel(&mut self) {
+        self.progress.allow_cancel();
+    }
+
+    pub(crate) fn send(&self, message: ToFrontend) -> Result<(), SendError<BackendResponse>> {
+        let result = self.back_tx.send(BackendResponse {
+            id: self.id,
+            message,
+        });
+
+        self.egui_context.request_repaint();
+
+        result
+    }
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::ModLoader,
+};
+use common::{fabric_jar, MockServer, TestBackend};
+
+#[test]
+fn fetches_the_version_manifest() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+
+    let backend = TestBackend::start("manifest", &server);
+    backend.send(ToBackend::GetVersionMetadata);
+
+    let (manifest, stale) = backend.wait_for(|message| match message {
+        ToFrontend::SetVersionMetadata { manifest, stale } => Some((manifest, stale)),
+        _ => None,
+    });
+
+    assert_eq!(manifest.versions[0].id, "1.18.2");
+    assert!(!stale);
+}
+
+#[test]
+fn adds_a_mod_from_modrinth() {
+    let server = MockServer::start();
+    let jar = fabric_jar("example", "1.0.0");
+    let version = server.host_jar("1.0.0", &jar);
+
+    server.mock(
+        "GET",
+        "/project/example-mod",
+        server.fixture("project.json", &[]),
+    );
+    server.mock("GET", "/project/AABBCCDD/version", format!("[{}]", version));
+
+    let backend = TestBackend::start("add", &server);
+    backend.send(ToBackend::AddMod {
+        modrinth_id: "example-mod".to_string(),
+        game_version: "1.18.2".to_string(),
+        modloader: ModLoader::Fabric,
+    });
+
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].entries[0].id, "example");
+    assert_eq!(
+        std::fs::read(backend.mods_dir.join("example-1.0.0.jar")).unwrap(),
+        jar
+    );
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use back::mod_file::{FileState, ModFile};
+use common::{fabric_jar, MockServer, TestBackend};
+use sha1::Digest;
+
+const LOOKUP_PATH: &str = "/version_files";
+const UPDATE_PATH: &str = "/version_files/update";
+
+fn sha1(bytes: &[u8]) -> String {
+    hex::encode(sha1::Sha1::digest(bytes))
+}
+
+/// Install version 1.0.0 of every mod in `names` with 1.1.0 as their update, along with a jar that is not on Modrinth
+fn setup(name: &str, names: &[&str]) -> (MockServer, TestBackend, Vec<String>) {
+    let server = MockServer::start();
+    let backend = TestBackend::start(name, &server);
+
+    let mut hashes = Vec::new();
+    let mut versions = Vec::new();
+    let mut updates = Vec::new();
+
+    for name in names {
+        let old_jar = fabric_jar(name, "1.0.0");
+        let old_sha1 = sha1(&old_jar);
+        let old_version = server.host_mod(name, "1.0.0", &old_jar);
+        let new_version = server.host_mod(name, "1.1.0", &fabric_jar(name, "1.1.0"));
+
+        backend.install(&format!("{}-1.0.0.jar", name), &old_jar);
+        versions.push(format!(r#""{}": {}"#, old_sha1, old_version));
+        updates.push(format!(r#""{}": {}"#, old_sha1, new_version));
+        hashes.push(old_sha1);
+    }
+
+    server.mock("POST", LOOKUP_PATH, format!("{{{}}}", versions.join(", ")));
+    server.mock("POST", UPDATE_PATH, format!("{{{}}}", updates.join(", ")));
+
+    (server, backend, hashes)
+}
+
+/// The hashes a request body asks about, sorted
+fn requested_hashes(body: &serde_json::Value) -> Vec<String> {
+    let mut hashes: Vec<String> = body["hashes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hash| hash.as_str().unwrap().to_string())
+        .collect();
+    hashes.sort();
+
+    hashes
+}
+
+fn find<'a>(mod_list: &'a [ModFile], file_name: &str) -> &'a ModFile {
+    mod_list
+        .iter()
+        .find(|mod_file| mod_file.path.file_name().unwrap() == file_name)
+        .unwrap()
+}
+
+#[test]
+fn looks_up_every_file_in_one_request() {
+    let (server, backend, mut hashes) = setup("bulk-lookup", &["alpha", "beta", "gamma"]);
+    let local_jar = fabric_jar("local", "1.0.0");
+    backend.install("local-1.0.0.jar", &local_jar);
+
+    let mod_list = backend.check_for_updates();
+    hashes.sort();
+
+    // The files on Modrinth are checked for updates together, the one that is not is left alone
+    let updates = server.bodies(UPDATE_PATH);
+    assert_eq!(updates.len(), 1);
+    assert_eq!(requested_hashes(&updates[0]), hashes);
+    assert_eq!(updates[0]["algorithm"], "sha1");
+    assert_eq!(updates[0]["loaders"], serde_json::json!(["fabric"]));
+    assert_eq!(updates[0]["game_versions"], serde_json::json!(["1.18.2"]));
+
+    hashes.push(sha1(&local_jar));
+    hashes.sort();
+    let lookups = server.bodies(LOOKUP_PATH);
+    assert_eq!(lookups.len(), 1);
+    assert_eq!(requested_hashes(&lookups[0]), hashes);
+
+    for name in ["alpha", "beta", "gamma"] {
+        let mod_file = find(&mod_list, &format!("{}-1.0.0.jar", name));
+        let modrinth_data = mod_file.data.sources.modrinth.as_ref().unwrap();
+
+        assert_eq!(modrinth_data.id, name.to_uppercase());
+        assert_eq!(mod_file.data.state, FileState::Outdated);
+    }
+    assert!(find(&mod_list, "local-1.0.0.jar")
+        .data
+        .sources
+        .modrinth
+        .is_none());
+}
+
+#[test]
+fn only_looks_up_the_projects_of_untracked_files() {
+    let (server, backend, _) = setup("bulk-lookup-tracked", &["alpha", "beta"]);
+
+    backend.check_for_updates();
+    let mod_list = backend.check_for_updates();
+
+    assert_eq!(server.bodies(LOOKUP_PATH).len(), 1);
+    assert_eq!(server.bodies(UPDATE_PATH).len(), 2);
+    assert!(mod_list
+        .iter()
+        .all(|mod_file| mod_file.data.state == FileState::Outdated));
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::ModFile,
+    settings::SettingsBuilder,
+};
+use common::{fabric_jar, ExampleUpdate, MockServer, TestBackend};
+use sha1::Digest;
+
+/// A cache entry in the layout from before the cache was versioned
+fn legacy_entry(jar: &[u8]) -> serde_json::Value {
+    serde_json::json!({
+        "entries": [],
+        "data": {
+            "sources": {
+                "curseforge": null,
+                "modrinth": { "id": "AABBCCDD", "latest_valid_version": null }
+            },
+            "sourced_from": "Modrinth",
+            "state": "Current",
+            "loaders": ["Fabric"]
+        },
+        "hashes": {
+            "sha1": hex::encode(sha1::Sha1::digest(jar)),
+            "sha512": hex::encode(sha2::Sha512::digest(jar))
+        },
+        "path": "example-1.0.0.jar"
+    })
+}
+
+/// Where the cache of `mods_dir` is stored
+fn cache_path(mods_dir: &Path) -> PathBuf {
+    let mods_dir = mods_dir.canonicalize().unwrap();
+    let name = hex::encode(sha1::Sha1::digest(mods_dir.to_string_lossy().as_bytes()));
+
+    TestBackend::config_dir()
+        .join("mod_cache")
+        .join(format!("{}.json", name))
+}
+
+fn start_up(backend: &TestBackend) -> Vec<ModFile> {
+    backend.send(ToBackend::Startup {
+        icon_resize_size: 128,
+    });
+
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    })
+}
+
+#[test]
+fn moves_the_legacy_cache_out_of_the_mods_folder() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+
+    let backend = TestBackend::start("legacy-cache", &server);
+
+    let jar = fabric_jar("example", "1.0.0");
+    backend.install("example-1.0.0.jar", &jar);
+
+    let legacy_path = backend.mods_dir.join("mods.mCubed.json");
+    let legacy_cache = serde_json::json!({ "storage": [legacy_entry(&jar)] });
+    fs::write(&legacy_path, legacy_cache.to_string()).unwrap();
+
+    let mod_list = start_up(&backend);
+
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    assert_eq!(modrinth_data.id, "AABBCCDD");
+    assert!(!legacy_path.exists());
+
+    // Other tests store their caches in the same place
+    let caches: Vec<serde_json::Value> = fs::read_dir(TestBackend::config_dir().join("mod_cache"))
+        .unwrap()
+        .map(|entry| serde_json::from_slice(&fs::read(entry.unwrap().path()).unwrap()).unwrap())
+        .filter(|cache: &serde_json::Value| {
+            cache["mod_folder"].as_str() == backend.mods_dir.to_str()
+        })
+        .collect();
+
+    assert_eq!(caches.len(), 1);
+    assert_eq!(caches[0]["schema_version"], 1);
+    assert_eq!(caches[0]["storage"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn keeps_the_entries_that_can_be_read() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+
+    let backend = TestBackend::start("partial-cache", &server);
+
+    let jar = fabric_jar("example", "1.0.0");
+    backend.install("example-1.0.0.jar", &jar);
+
+    let legacy_cache = serde_json::json!({
+        "storage": [{ "unknown": "layout" }, legacy_entry(&jar)]
+    });
+    fs::write(
+        backend.mods_dir.join("mods.mCubed.json"),
+        legacy_cache.to_string(),
+    )
+    .unwrap();
+
+    let mod_list = start_up(&backend);
+
+    assert!(mod_list[0].data.sources.modrinth.is_some());
+}
+
+#[test]
+fn restores_a_damaged_cache_from_its_backup() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+
+    let backend = TestBackend::start("damaged-cache", &server);
+
+    let jar = fabric_jar("example", "1.0.0");
+    backend.install("example-1.0.0.jar", &jar);
+
+    let path = cache_path(&backend.mods_dir);
+    let backup = serde_json::json!({ "schema_version": 1, "storage": [legacy_entry(&jar)] });
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, r#"{"schema_version": 1, "stor"#).unwrap();
+    fs::write(path.with_extension("json.bak"), backup.to_string()).unwrap();
+
+    backend.send(ToBackend::Startup {
+        icon_resize_size: 128,
+    });
+
+    let warning = backend.wait_for(|message| match message {
+        ToFrontend::BackendWarning { warning } => Some(warning),
+        _ => None,
+    });
+    assert!(warning
+        .error
+        .to_string()
+        .contains("restored from its backup"));
+
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    assert!(mod_list[0].data.sources.modrinth.is_some());
+    assert!(path.with_extension("json.damaged").exists());
+}
+
+#[test]
+fn keeps_a_cache_per_mods_folder() {
+    let ExampleUpdate {
+        server, backend, ..
+    } = ExampleUpdate::setup("cache-per-folder", "release");
+    server.mock_version_manifest();
+    let mods_dir = backend.mods_dir.clone();
+    let other_dir = mods_dir.with_file_name("other-mods");
+    fs::create_dir_all(&other_dir).unwrap();
+    fs::write(
+        other_dir.join("other-1.0.0.jar"),
+        fabric_jar("other", "1.0.0"),
+    )
+    .unwrap();
+
+    start_up(&backend);
+    backend.check_for_updates();
+
+    let switch_to = |folder: &PathBuf| {
+        backend.send(ToBackend::UpdateSettings {
+            settings: Box::new(SettingsBuilder::from_current().mod_folder_path(folder.clone())),
+        });
+        backend.wait_for(|message| match message {
+            ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+            _ => None,
+        })
+    };
+
+    let mod_list = switch_to(&other_dir);
+    assert_eq!(mod_list.len(), 1);
+    assert!(mod_list[0].data.sources.modrinth.is_none());
+
+    // What was found out about the mods of the first folder is still there when going back to it
+    let mod_list = switch_to(&mods_dir);
+    assert_eq!(mod_list.len(), 1);
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    assert_eq!(modrinth_data.id, "AABBCCDD");
+
+    let folders: Vec<String> = fs::read_dir(TestBackend::config_dir().join("mod_cache"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(OsStr::new("json")))
+        .map(|path| {
+            let cache: serde_json::Value =
+                serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+            cache["mod_folder"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(folders.len(), 2);
+    assert!(folders.contains(&other_dir.to_string_lossy().to_string()));
+}
+
+#[test]
+fn does_not_carry_the_list_over_to_another_folder() {
+    let update = ExampleUpdate::setup("cache-folder-switch", "release");
+    let backend = &update.backend;
+    update.server.mock_version_manifest();
+
+    // The same file is in both folders
+    let other_dir = backend.mods_dir.with_file_name("other-mods");
+    fs::create_dir_all(&other_dir).unwrap();
+    fs::write(other_dir.join("example-1.0.0.jar"), &update.old_jar).unwrap();
+
+    start_up(backend);
+    backend.check_for_updates();
+
+    backend.send(ToBackend::UpdateSettings {
+        settings: Box::new(SettingsBuilder::from_current().mod_folder_path(other_dir)),
+    });
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    assert_eq!(mod_list.len(), 1);
+    assert!(mod_list[0].data.sources.modrinth.is_none());
+}
+
+#[test]
+fn reports_a_damaged_cache_of_a_folder_switched_to() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+    let backend = TestBackend::start("damaged-cache-switch", &server);
+    start_up(&backend);
+
+    let other_dir = backend.mods_dir.with_file_name("other-mods");
+    let jar = fabric_jar("example", "1.0.0");
+    fs::create_dir_all(&other_dir).unwrap();
+    fs::write(other_dir.join("example-1.0.0.jar"), &jar).unwrap();
+
+    let path = cache_path(&other_dir);
+    let backup = serde_json::json!({ "schema_version": 1, "storage": [legacy_entry(&jar)] });
+    fs::write(&path, r#"{"schema_version": 1, "stor"#).unwrap();
+    fs::write(path.with_extension("json.bak"), backup.to_string()).unwrap();
+
+    backend.send(ToBackend::UpdateSettings {
+        settings: Box::new(SettingsBuilder::from_current().mod_folder_path(other_dir)),
+    });
+
+    let warning = backend.wait_for(|message| match message {
+        ToFrontend::BackendWarning { warning } => Some(warning),
+        _ => None,
+    });
+    assert!(warning
+        .error
+        .to_string()
+        .contains("restored from its backup"));
+
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+    assert!(mod_list[0].data.sources.modrinth.is_some());
+}
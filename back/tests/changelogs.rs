@@ -0,0 +1,57 @@
+mod common;
+
+use back::messages::{ToBackend, ToFrontend};
+use common::{fabric_jar, ExampleUpdate};
+use sha1::Digest;
+
+#[test]
+fn gathers_the_changelogs_between_the_installed_file_and_the_update() {
+    let update = ExampleUpdate::setup("changelogs", "release");
+    let server = &update.server;
+    let backend = &update.backend;
+
+    let older = server.host_other_version("0.9.0", "2021-12-01", &fabric_jar("example", "0.9.0"));
+    let latest = server.host_other_version("1.2.0", "2022-05-01", &fabric_jar("example", "1.2.0"));
+    // Newer than the update, but not in the accepted release channel
+    let beta = server
+        .host_other_version("1.3.0", "2022-06-01", &fabric_jar("example", "1.3.0"))
+        .replace(r#""version_type": "release""#, r#""version_type": "beta""#);
+    server.mock(
+        "GET",
+        "/project/AABBCCDD/version",
+        format!(
+            "[{}, {}, {}, {}, {}]",
+            beta,
+            older,
+            server.host_jar("1.0.0", &update.old_jar),
+            server.host_jar("1.1.0", &update.new_jar),
+            latest
+        ),
+    );
+    let old_sha1 = hex::encode(sha1::Sha1::digest(&update.old_jar));
+    server.mock(
+        "POST",
+        "/version_files/update",
+        format!(r#"{{"{}": {}}}"#, old_sha1, latest),
+    );
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::GetChangelogs {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    let changelogs = backend.wait_for(|message| match message {
+        ToFrontend::Changelogs { changelogs, .. } => Some(changelogs),
+        _ => None,
+    });
+
+    // Newest first, leaving out the installed version and everything around the range
+    let versions: Vec<&str> = changelogs
+        .iter()
+        .map(|changelog| changelog.version_number.as_str())
+        .collect();
+    assert_eq!(versions, ["1.2.0", "1.1.0"]);
+    assert_eq!(
+        changelogs[1].changelog.as_deref(),
+        Some("Changes for 1.1.0")
+    );
+}
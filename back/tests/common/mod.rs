@@ -0,0 +1,566 @@
+//! A small in-process stand-in for the Modrinth API and Mojang's version manifest, along with a backend wired to it
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    ffi::OsStr,
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use back::{
+    messages::{BackendError, BackendRequest, BackendResponse, RequestId, ToBackend, ToFrontend},
+    mod_file::{ModFile, ModLoader},
+    settings::SettingsBuilder,
+    Back, CONFIG_DIR_VAR,
+};
+use crossbeam_channel::{Receiver, Sender};
+use sha1::Digest;
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The path where the version manifest is served
+pub const VERSION_MANIFEST_PATH: &str = "/mc/game/version_manifest_v2.json";
+
+type Routes = Arc<Mutex<HashMap<(String, String), VecDeque<MockResponse>>>>;
+type Requests = Arc<Mutex<Vec<ReceivedRequest>>>;
+
+/// A request the server received
+struct ReceivedRequest {
+    /// The path along with the query
+    target: String,
+    /// The headers, with lowercase names
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+lazy_static::lazy_static! {
+    /// The settings are global, so the tests of a binary take turns
+    static ref SERIAL: Mutex<()> = Mutex::new(());
+}
+
+/// What the server answers a request with
+#[derive(Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Duration,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Wait before answering, to stand in for a stalled server
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// An HTTP server answering every registered route with a fixed response, and with a 404 otherwise
+pub struct MockServer {
+    base_url: String,
+    routes: Routes,
+    requests: Requests,
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let routes = Routes::default();
+        let requests = Requests::default();
+
+        let server_routes = routes.clone();
+        let server_requests = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = server_routes.clone();
+                let requests = server_requests.clone();
+                thread::spawn(move || handle_connection(stream, &routes, &requests));
+            }
+        });
+
+        Self {
+            base_url,
+            routes,
+            requests,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Answer `method` requests to `path` with `body`, the query is not taken into account
+    pub fn mock(&self, method: &str, path: &str, body: impl Into<Vec<u8>>) {
+        self.mock_sequence(method, path, vec![MockResponse::new(200, body)]);
+    }
+
+    /// Answer `method` requests to `path` with `responses` in order, the last one being repeated once the others are used up
+    pub fn mock_sequence(&self, method: &str, path: &str, responses: Vec<MockResponse>) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert((method.to_string(), path.to_string()), responses.into());
+    }
+
+    /// The query parameters of every request received for `path`, in order
+    pub fn queries(&self, path: &str) -> Vec<HashMap<String, String>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|request| {
+                let url =
+                    reqwest::Url::parse(&format!("{}{}", self.base_url, request.target)).ok()?;
+                (url.path() == path).then(|| url.query_pairs().into_owned().collect())
+            })
+            .collect()
+    }
+
+    /// The headers of every request received for `path`, with lowercase names
+    pub fn headers(&self, path: &str) -> Vec<HashMap<String, String>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.target.split('?').next() == Some(path))
+            .map(|request| request.headers.clone())
+            .collect()
+    }
+
+    /// The JSON bodies of every request received for `path`
+    pub fn bodies(&self, path: &str) -> Vec<serde_json::Value> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.target.split('?').next() == Some(path))
+            .map(|request| serde_json::from_slice(&request.body).unwrap_or_default())
+            .collect()
+    }
+
+    /// Read a fixture, replacing `{{BASE_URL}}` and every `{{KEY}}` in `values`
+    pub fn fixture(&self, name: &str, values: &[(&str, String)]) -> String {
+        let mut contents = fs::read_to_string(Path::new(FIXTURES_DIR).join(name)).unwrap();
+        contents = contents.replace("{{BASE_URL}}", &self.base_url);
+
+        for (key, value) in values {
+            contents = contents.replace(&format!("{{{{{}}}}}", key), value);
+        }
+
+        contents
+    }
+
+    /// Serve a generated jar, returning the Modrinth version fixture that points to it
+    pub fn host_jar(&self, version: &str, jar: &[u8]) -> String {
+        self.mock("GET", &format!("/data/example-{}.jar", version), jar);
+
+        self.fixture(
+            &format!("version_{}.json", version),
+            &[
+                ("SHA1", hex::encode(sha1::Sha1::digest(jar))),
+                ("SHA512", hex::encode(sha2::Sha512::digest(jar))),
+                ("SIZE", jar.len().to_string()),
+            ],
+        )
+    }
+
+    /// Serve a jar as a version of the mod `name`, which has a project of its own with `name` in uppercase as its id
+    pub fn host_mod(&self, name: &str, version: &str, jar: &[u8]) -> String {
+        self.mock("GET", &format!("/data/{}-{}.jar", name, version), jar);
+
+        self.fixture(
+            &format!("version_{}.json", version),
+            &[
+                ("SHA1", hex::encode(sha1::Sha1::digest(jar))),
+                ("SHA512", hex::encode(sha2::Sha512::digest(jar))),
+                ("SIZE", jar.len().to_string()),
+            ],
+        )
+        .replace("example-", &format!("{}-", name))
+        .replace("AABBCCDD", &name.to_uppercase())
+        .replace(r#""id": "v"#, &format!(r#""id": "{}-v"#, name))
+    }
+
+    /// Serve a jar as a version of the example mod that has no fixture of its own, published on `date` (Ex. `2022-05-01`)
+    pub fn host_other_version(&self, version: &str, date: &str, jar: &[u8]) -> String {
+        self.mock("GET", &format!("/data/example-{}.jar", version), jar);
+
+        self.fixture(
+            "version_1.1.0.json",
+            &[
+                ("SHA1", hex::encode(sha1::Sha1::digest(jar))),
+                ("SHA512", hex::encode(sha2::Sha512::digest(jar))),
+                ("SIZE", jar.len().to_string()),
+            ],
+        )
+        .replace("v1100000", &format!("v{}", version))
+        .replace("1.1.0", version)
+        .replace("2022-04-01", date)
+    }
+
+    pub fn mock_version_manifest(&self) {
+        let manifest = self.fixture("version_manifest.json", &[]);
+        self.mock("GET", VERSION_MANIFEST_PATH, manifest);
+    }
+}
+
+fn handle_connection(stream: TcpStream, routes: &Routes, requests: &Requests) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+
+        if header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    requests.lock().unwrap().push(ReceivedRequest {
+        target: target.to_string(),
+        headers,
+        body,
+    });
+
+    let response = routes
+        .lock()
+        .unwrap()
+        .get_mut(&(method, path))
+        .and_then(|responses| {
+            if responses.len() > 1 {
+                responses.pop_front()
+            } else {
+                responses.front().cloned()
+            }
+        })
+        .unwrap_or_else(|| MockResponse::new(404, "{}"));
+
+    thread::sleep(response.delay);
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nContent-Type: application/json\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    // The client may have given up already
+    let mut stream = stream;
+    let _ = stream
+        .write_all(head.as_bytes())
+        .and_then(|()| stream.write_all(&response.body));
+}
+
+/// A backend running on its own thread, using a temporary mods folder and configuration directory
+pub struct TestBackend {
+    tx: Sender<BackendRequest>,
+    rx: Receiver<BackendResponse>,
+    pub mods_dir: PathBuf,
+    _serial: MutexGuard<'static, ()>,
+}
+
+impl TestBackend {
+    /// Start the backend against `server`, this must happen before anything else touches the settings
+    pub fn start(name: &str, server: &MockServer) -> Self {
+        let serial = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let root = env::temp_dir().join(format!("mcubed-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let mods_dir = root.join("mods");
+        fs::create_dir_all(&mods_dir).unwrap();
+
+        // Nothing stored or changed by an earlier test should leak into this one
+        let _ = fs::remove_dir_all(Self::config_dir());
+        env::set_var(CONFIG_DIR_VAR, Self::config_dir());
+
+        SettingsBuilder::default()
+            .mod_folder_path(mods_dir.clone())
+            .modrinth_api_url(server.url())
+            .version_manifest_url(format!("{}{}", server.url(), VERSION_MANIFEST_PATH))
+            .max_request_retries(0)
+            .apply();
+
+        let (front_tx, front_rx) = crossbeam_channel::unbounded();
+        let (back_tx, back_rx) = crossbeam_channel::unbounded();
+
+        thread::spawn(move || {
+            Back::new(back_tx, front_rx, eframe::egui::Context::default()).init();
+        });
+
+        Self {
+            tx: front_tx,
+            rx: back_rx,
+            mods_dir,
+            _serial: serial,
+        }
+    }
+
+    /// The configuration directory is only read once, so every backend of a binary shares it
+    pub fn config_dir() -> PathBuf {
+        env::temp_dir().join(format!("mcubed-config-{}", process::id()))
+    }
+
+    /// Send a message, returning the id its responses are tagged with
+    pub fn send(&self, message: ToBackend) -> RequestId {
+        let request = BackendRequest::from(message);
+        let id = request.id;
+        self.tx.send(request).unwrap();
+
+        id
+    }
+
+    /// Wait for the first message `matcher` accepts, failing on any backend error
+    pub fn wait_for<T>(&self, mut matcher: impl FnMut(ToFrontend) -> Option<T>) -> T {
+        self.wait_for_any(|message| {
+            if let ToFrontend::BackendError { error } = &message {
+                panic!("{}: {}", error.message, error.error);
+            }
+
+            matcher(message)
+        })
+    }
+
+    /// Wait for the first backend error
+    pub fn wait_for_error(&self) -> BackendError {
+        self.wait_for_any(|message| match message {
+            ToFrontend::BackendError { error } => Some(error),
+            _ => None,
+        })
+    }
+
+    fn wait_for_any<T>(&self, mut matcher: impl FnMut(ToFrontend) -> Option<T>) -> T {
+        self.wait_for_response(|response| matcher(response.message))
+    }
+
+    /// Wait for the first response `matcher` accepts, along with the id of the request it answers
+    pub fn wait_for_response<T>(&self, mut matcher: impl FnMut(BackendResponse) -> Option<T>) -> T {
+        let deadline = Instant::now() + TIMEOUT;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let response = self
+                .rx
+                .recv_timeout(remaining)
+                .expect("Timed out waiting for the backend");
+
+            if let Some(value) = matcher(response) {
+                return value;
+            }
+        }
+    }
+
+    /// Write a file into the mods folder
+    pub fn install(&self, file_name: &str, contents: &[u8]) {
+        fs::write(self.mods_dir.join(file_name), contents).unwrap();
+    }
+
+    /// Check for Fabric updates for 1.18.2, returning the list that comes back
+    pub fn check_for_updates(&self) -> Vec<ModFile> {
+        self.send(ToBackend::CheckForUpdates {
+            game_version: "1.18.2".to_string(),
+            modloader: ModLoader::Fabric,
+        });
+
+        self.wait_for(|message| match message {
+            ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+            _ => None,
+        })
+    }
+}
+
+/// The example mod installed at 1.0.0, with 1.1.0 served as its update
+pub struct ExampleUpdate {
+    pub server: MockServer,
+    pub backend: TestBackend,
+    pub old_jar: Vec<u8>,
+    pub new_jar: Vec<u8>,
+}
+
+impl ExampleUpdate {
+    /// Serve both versions of the example mod, the update being of `new_version_type`, and install the first one
+    pub fn setup(name: &str, new_version_type: &str) -> Self {
+        Self::setup_with(name, new_version_type, fabric_jar("example", "1.0.0"))
+    }
+
+    /// The same as [setup](Self::setup), with `old_jar` as the installed version
+    pub fn setup_with(name: &str, new_version_type: &str, old_jar: Vec<u8>) -> Self {
+        let server = MockServer::start();
+
+        let new_jar = fabric_jar("example", "1.1.0");
+        let old_version = server.host_jar("1.0.0", &old_jar);
+        let new_version = server.host_jar("1.1.0", &new_jar).replace(
+            r#""version_type": "release""#,
+            &format!(r#""version_type": "{}""#, new_version_type),
+        );
+        let old_sha1 = hex::encode(sha1::Sha1::digest(&old_jar));
+
+        server.mock(
+            "POST",
+            "/version_files",
+            format!(r#"{{"{}": {}}}"#, old_sha1, old_version),
+        );
+        server.mock(
+            "POST",
+            "/version_files/update",
+            format!(r#"{{"{}": {}}}"#, old_sha1, new_version),
+        );
+        // Oldest first, the order should not matter
+        server.mock(
+            "GET",
+            "/project/AABBCCDD/version",
+            format!("[{}, {}]", old_version, new_version),
+        );
+
+        let backend = TestBackend::start(name, &server);
+        backend.install("example-1.0.0.jar", &old_jar);
+
+        Self {
+            server,
+            backend,
+            old_jar,
+            new_jar,
+        }
+    }
+}
+
+/// Build a minimal Fabric mod
+pub fn fabric_jar(id: &str, version: &str) -> Vec<u8> {
+    mod_jar(id, version, false)
+}
+
+/// Build a minimal mod that ships both a Fabric and a Forge manifest
+pub fn multi_loader_jar(id: &str, version: &str) -> Vec<u8> {
+    mod_jar(id, version, true)
+}
+
+/// Build a Fabric mod from a full `fabric.mod.json`
+pub fn fabric_jar_with(manifest: serde_json::Value) -> Vec<u8> {
+    jar(&[("fabric.mod.json", manifest.to_string().as_bytes())])
+}
+
+/// Build a Forge mod from a full `mods.toml`
+pub fn forge_jar_with(manifest: &str) -> Vec<u8> {
+    jar(&[("META-INF/mods.toml", manifest.as_bytes())])
+}
+
+fn mod_jar(id: &str, version: &str, with_forge_manifest: bool) -> Vec<u8> {
+    let manifest = format!(
+        r#"{{"schemaVersion": 1, "id": "{}", "version": "{}", "name": "Example Mod"}}"#,
+        id, version
+    );
+
+    // Only the presence of the Forge manifest matters for multi-loader jars
+    if with_forge_manifest {
+        jar(&[
+            ("fabric.mod.json", manifest.as_bytes()),
+            ("META-INF/mods.toml", b""),
+        ])
+    } else {
+        jar(&[("fabric.mod.json", manifest.as_bytes())])
+    }
+}
+
+/// Encode a blank, non-square PNG, icons are stretched to the size the frontend expects
+pub fn icon_png() -> Vec<u8> {
+    let image = image::RgbaImage::new(64, 32);
+    let mut png = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .unwrap();
+
+    png.into_inner()
+}
+
+/// The responses in the HTTP cache along with their paths, backups left out
+pub fn cached_responses() -> Vec<(PathBuf, serde_json::Value)> {
+    fs::read_dir(TestBackend::config_dir().join("http_cache"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(OsStr::new("json")))
+        .map(|path| {
+            let cached = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+            (path, cached)
+        })
+        .collect()
+}
+
+/// Make every response in the HTTP cache older than any TTL
+pub fn expire_http_cache() {
+    for (path, mut cached) in cached_responses() {
+        cached["fetched_at"] = 0.into();
+        fs::write(path, cached.to_string()).unwrap();
+    }
+}
+
+/// Write a file, creating the folders above it
+pub fn write(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+/// Build a zip archive holding `files`, by path and contents
+pub fn jar(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut buffer = std::io::Cursor::new(Vec::new());
+
+    {
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+
+        for (path, contents) in files {
+            zip.start_file(*path, zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(contents).unwrap();
+        }
+
+        zip.finish().unwrap();
+    }
+
+    buffer.into_inner()
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{FileState, ModFile, ModLoader, Pin},
+};
+use common::{fabric_jar_with, forge_jar_with, ExampleUpdate, MockServer, TestBackend};
+
+fn forge_manifest(id: &str, minecraft_range: &str) -> String {
+    format!(
+        r#"
+modLoader = "javafml"
+loaderVersion = "[40,)"
+license = "MIT"
+issueTrackerURL = ""
+
+[[mods]]
+modId = "{id}"
+version = "1.0.0"
+displayName = "{id}"
+description = ""
+
+[[dependencies.{id}]]
+modId = "minecraft"
+mandatory = true
+versionRange = "{range}"
+ordering = "NONE"
+"#,
+        id = id,
+        range = minecraft_range
+    )
+}
+
+fn check(backend: &TestBackend, game_version: &str) -> Vec<ModFile> {
+    check_on(backend, game_version, ModLoader::Fabric)
+}
+
+fn check_on(backend: &TestBackend, game_version: &str, modloader: ModLoader) -> Vec<ModFile> {
+    backend.send(ToBackend::CheckCompatibility {
+        game_version: game_version.to_string(),
+        modloader,
+    });
+
+    let mut mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+    mod_list.sort_by(|a, b| a.entries[0].id.cmp(&b.entries[0].id));
+
+    mod_list
+}
+
+#[test]
+fn marks_mods_that_declare_another_game_version_as_invalid() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("compatibility", &server);
+
+    backend.install(
+        "fabric-old.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "fabric-old",
+            "name": "Old Fabric Mod",
+            "version": "1.0.0",
+            "depends": {"minecraft": "1.18.x"}
+        })),
+    );
+    backend.install(
+        "fabric-any.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "fabric-any",
+            "version": "1.0.0"
+        })),
+    );
+    backend.install(
+        "forge-new.jar",
+        &forge_jar_with(&forge_manifest("forge-new", "[1.19,1.20)")),
+    );
+    backend.send(ToBackend::ScanFolder);
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { .. } => Some(()),
+        _ => None,
+    });
+
+    let mod_list = check(&backend, "1.19.2");
+
+    let states: Vec<(&str, FileState)> = mod_list
+        .iter()
+        .map(|mod_file| (mod_file.entries[0].id.as_str(), mod_file.data.state))
+        .collect();
+    assert_eq!(
+        states,
+        [
+            ("fabric-any", FileState::Local),
+            ("fabric-old", FileState::Invalid),
+            ("forge-new", FileState::Local),
+        ]
+    );
+
+    let reason = mod_list[1].data.state_reason.as_deref().unwrap();
+    assert!(reason.contains("Old Fabric Mod"), "{}", reason);
+    assert!(reason.contains("1.18.x"), "{}", reason);
+
+    // Going back to a version the mod supports clears the explanation
+    let mod_list = check(&backend, "1.18.2");
+
+    assert_eq!(mod_list[1].data.state, FileState::Local);
+    assert_eq!(mod_list[1].data.state_reason, None);
+    assert_eq!(mod_list[2].data.state, FileState::Invalid);
+}
+
+/// The example mod, declaring to only work on 1.18, with an update served for it
+fn tracked_for_1_18(name: &str) -> TestBackend {
+    let old_jar = fabric_jar_with(serde_json::json!({
+        "schemaVersion": 1,
+        "id": "example",
+        "version": "1.0.0",
+        "depends": {"minecraft": "1.18.x"}
+    }));
+
+    ExampleUpdate::setup_with(name, "release", old_jar).backend
+}
+
+#[test]
+fn incompatibility_wins_over_an_available_update() {
+    let backend = tracked_for_1_18("compatibility-outdated");
+
+    let mod_list = backend.check_for_updates();
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+
+    // An update is no use for a game version the mod does not support
+    let mod_list = check(&backend, "1.19.2");
+    assert_eq!(mod_list[0].data.state, FileState::Invalid);
+
+    let mod_list = check(&backend, "1.18.2");
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+    assert_eq!(mod_list[0].data.state_reason, None);
+}
+
+#[test]
+fn restores_the_pin_once_compatible_again() {
+    let backend = tracked_for_1_18("compatibility-pinned");
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::SetPin {
+        sha1: mod_list[0].hashes.sha1.clone(),
+        pin: Some(Pin::CurrentFile),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { .. } => Some(()),
+        _ => None,
+    });
+
+    let mod_list = check(&backend, "1.19.2");
+    assert_eq!(mod_list[0].data.state, FileState::Invalid);
+
+    let mod_list = check(&backend, "1.18.2");
+    assert_eq!(mod_list[0].data.state, FileState::Pinned);
+    assert_eq!(mod_list[0].data.pin, Some(Pin::CurrentFile));
+}
+
+#[test]
+fn only_checks_the_dependencies_of_the_active_loader() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("compatibility-loader", &server);
+
+    let manifest = forge_manifest("forge-mod", "[1.18,1.19)")
+        + r#"
+[[dependencies.forge-mod]]
+modId = "forge-library"
+mandatory = true
+versionRange = "[1,)"
+ordering = "NONE"
+"#;
+    backend.install("forge-mod.jar", &forge_jar_with(&manifest));
+    backend.install(
+        "fabric-mod.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "fabric-mod",
+            "version": "1.0.0",
+            "depends": {"fabric-library": "*"}
+        })),
+    );
+    backend.send(ToBackend::ScanFolder);
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { .. } => Some(()),
+        _ => None,
+    });
+
+    let mod_list = check_on(&backend, "1.18.2", ModLoader::Fabric);
+    assert_eq!(mod_list[0].entries[0].id, "fabric-mod");
+    assert_eq!(mod_list[0].data.state, FileState::Invalid);
+    assert_eq!(mod_list[1].data.state, FileState::Local);
+
+    let mod_list = check_on(&backend, "1.18.2", ModLoader::Forge);
+    assert_eq!(mod_list[0].data.state, FileState::Local);
+    assert_eq!(mod_list[1].data.state, FileState::Invalid);
+    let reason = mod_list[1].data.state_reason.as_deref().unwrap();
+    assert!(reason.contains("forge-library"), "{}", reason);
+}
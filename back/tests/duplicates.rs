@@ -0,0 +1,144 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::duplicates::{DuplicateGroup, IdClaim},
+};
+use common::{fabric_jar, fabric_jar_with, jar, MockServer, TestBackend};
+
+fn scan(backend: &TestBackend) -> Vec<DuplicateGroup> {
+    backend.send(ToBackend::ScanFolder);
+
+    backend.wait_for(|message| match message {
+        ToFrontend::DuplicateMods { groups } => Some(groups),
+        _ => None,
+    })
+}
+
+/// A Fabric mod that bundles `nested` as jar-in-jar
+fn bundling_jar(id: &str, nested: &[u8]) -> Vec<u8> {
+    let manifest = serde_json::json!({
+        "schemaVersion": 1,
+        "id": id,
+        "version": "1.0.0",
+        "jars": [{"file": "META-INF/jars/nested.jar"}]
+    });
+
+    jar(&[
+        ("fabric.mod.json", manifest.to_string().as_bytes()),
+        ("META-INF/jars/nested.jar", nested),
+    ])
+}
+
+#[test]
+fn groups_the_files_that_contain_the_same_mod() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("duplicates", &server);
+
+    backend.install("example-1.jar", &fabric_jar("example", "1.0.0"));
+    backend.install("example-2.jar", &fabric_jar("example", "1.2.0"));
+    backend.install("other.jar", &fabric_jar("other", "1.0.0"));
+
+    let groups = scan(&backend);
+    let ids: Vec<&str> = groups.iter().map(|group| group.id.as_str()).collect();
+    assert_eq!(ids, ["example"]);
+
+    // The newest copy comes first and is the one that is kept
+    let example = &groups[0];
+    assert_eq!(example.copies[0].version, "1.2.0");
+    assert_eq!(
+        example
+            .older_copies()
+            .iter()
+            .map(|copy| copy.path.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>(),
+        ["example-1.jar"]
+    );
+
+    // Trashing the older copy leaves the mod in the folder once
+    backend.send(ToBackend::DeleteMod {
+        path: example.older_copies()[0].path.clone(),
+    });
+
+    let groups = backend.wait_for(|message| match message {
+        ToFrontend::DuplicateMods { groups } => Some(groups),
+        _ => None,
+    });
+    assert!(groups.iter().all(|group| group.id != "example"));
+}
+
+#[test]
+fn sorts_copies_without_a_comparable_version_last() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("duplicates-unordered", &server);
+
+    backend.install("example-a.jar", &fabric_jar("example", "mc1.18-2.0"));
+    backend.install("example-b.jar", &fabric_jar("example", "1.0.0"));
+    backend.install("example-c.jar", &fabric_jar("example", "1.2.0"));
+
+    let groups = scan(&backend);
+
+    let versions: Vec<&str> = groups[0]
+        .copies
+        .iter()
+        .map(|copy| copy.version.as_str())
+        .collect();
+    assert_eq!(versions, ["1.2.0", "1.0.0", "mc1.18-2.0"]);
+}
+
+#[test]
+fn counts_provided_ids_as_the_same_mod() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("duplicates-provided", &server);
+
+    backend.install(
+        "alias.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "alias",
+            "version": "2.0.0",
+            "provides": ["other"]
+        })),
+    );
+    backend.install("other.jar", &fabric_jar("other", "1.0.0"));
+
+    let groups = scan(&backend);
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].id, "other");
+    assert_eq!(groups[0].copies[0].claim, IdClaim::Provided);
+    assert_eq!(groups[0].copies[1].claim, IdClaim::Own);
+}
+
+#[test]
+fn leaves_bundled_copies_to_the_loader() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("duplicates-bundled", &server);
+
+    let library = fabric_jar("library", "0.5.0");
+    backend.install("first.jar", &bundling_jar("first", &library));
+    backend.install("second.jar", &bundling_jar("second", &library));
+
+    // Both mods bundling the library is something the loader sorts out on its own
+    assert_eq!(scan(&backend), []);
+
+    // And so is a standalone copy next to the bundled ones
+    backend.install("library-1.jar", &fabric_jar("library", "0.4.0"));
+    assert_eq!(scan(&backend), []);
+
+    backend.install("library-2.jar", &fabric_jar("library", "0.3.0"));
+
+    let groups = scan(&backend);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].id, "library");
+    assert_eq!(groups[0].copies.len(), 4);
+    assert_eq!(groups[0].copies[0].claim, IdClaim::Bundled);
+
+    // The newest standalone copy is kept even though a bundled one is newer
+    let older: Vec<&str> = groups[0]
+        .older_copies()
+        .iter()
+        .map(|copy| copy.path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(older, ["library-2.jar"]);
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    settings::SettingsBuilder,
+};
+use common::{MockResponse, MockServer, TestBackend};
+
+const CATEGORIES_PATH: &str = "/tag/category";
+
+/// Start a backend that retries requests `retries` times, waiting `base_delay_ms` before the first retry
+fn start(name: &str, server: &MockServer, retries: u32, base_delay_ms: u64) -> TestBackend {
+    let backend = TestBackend::start(name, server);
+
+    SettingsBuilder::from_current()
+        .max_request_retries(retries)
+        .retry_base_delay_ms(base_delay_ms)
+        .apply();
+
+    backend
+}
+
+fn categories(server: &MockServer, status: u16) -> MockResponse {
+    MockResponse::new(status, server.fixture("categories.json", &[]))
+}
+
+/// The categories take a single request to fetch
+fn wait_for_categories(backend: &TestBackend) {
+    backend.wait_for(|message| match message {
+        ToFrontend::SearchCategories { .. } => Some(()),
+        _ => None,
+    });
+}
+
+#[test]
+fn retries_server_errors_with_a_growing_delay() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![
+            MockResponse::new(503, "{}"),
+            MockResponse::new(502, "{}"),
+            categories(&server, 200),
+        ],
+    );
+    let backend = start("http-retry", &server, 2, 100);
+
+    let started = Instant::now();
+    backend.send(ToBackend::GetSearchCategories);
+    wait_for_categories(&backend);
+
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 3);
+    // The delay is doubled after the first retry
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn does_not_retry_rejected_requests() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![MockResponse::new(400, "{}"), categories(&server, 200)],
+    );
+    let backend = start("http-rejected", &server, 3, 10);
+
+    backend.send(ToBackend::GetSearchCategories);
+    backend.wait_for_error();
+
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 1);
+}
+
+#[test]
+fn waits_out_an_exceeded_rate_limit() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![
+            MockResponse::new(429, "{}").header("Retry-After", "1"),
+            categories(&server, 200),
+        ],
+    );
+    let backend = start("http-rate-limit", &server, 1, 10);
+    SettingsBuilder::from_current()
+        .respect_rate_limits(true)
+        .apply();
+
+    let started = Instant::now();
+    backend.send(ToBackend::GetSearchCategories);
+    wait_for_categories(&backend);
+
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 2);
+    // The server asked for a second, which is longer than the backoff
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[test]
+fn fails_instead_of_waiting_out_an_absurd_rate_limit() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![
+            MockResponse::new(429, "{}").header("Retry-After", &u64::MAX.to_string()),
+            categories(&server, 200),
+        ],
+    );
+    let backend = start("http-rate-limit-absurd", &server, 1, 10);
+    SettingsBuilder::from_current()
+        .respect_rate_limits(true)
+        .apply();
+
+    backend.send(ToBackend::GetSearchCategories);
+    let error = backend.wait_for_error();
+
+    assert!(
+        error.error.to_string().contains("rate limit"),
+        "{}",
+        error.error
+    );
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 1);
+}
+
+#[test]
+fn gives_up_on_a_stalled_server() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![categories(&server, 200).delay(Duration::from_secs(5))],
+    );
+    let backend = start("http-timeout", &server, 0, 10);
+    SettingsBuilder::from_current()
+        .request_timeout_secs(1)
+        .apply();
+
+    let started = Instant::now();
+    backend.send(ToBackend::GetSearchCategories);
+    let error = backend.wait_for_error();
+
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "{}",
+        error.error
+    );
+}
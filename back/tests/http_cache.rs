@@ -0,0 +1,125 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    settings::SettingsBuilder,
+};
+use common::{cached_responses, expire_http_cache, MockResponse, MockServer, TestBackend};
+
+const CATEGORIES_PATH: &str = "/tag/category";
+
+fn categories(server: &MockServer) -> MockResponse {
+    MockResponse::new(200, server.fixture("categories.json", &[])).header("ETag", "\"v1\"")
+}
+
+fn fetch_categories(backend: &TestBackend) -> Vec<String> {
+    backend.send(ToBackend::GetSearchCategories);
+    backend.wait_for(|message| match message {
+        ToFrontend::SearchCategories { categories } => Some(categories),
+        _ => None,
+    })
+}
+
+#[test]
+fn reuses_a_fresh_response() {
+    let server = MockServer::start();
+    server.mock_sequence("GET", CATEGORIES_PATH, vec![categories(&server)]);
+    let backend = TestBackend::start("http-cache-fresh", &server);
+
+    let first = fetch_categories(&backend);
+    let second = fetch_categories(&backend);
+
+    assert_eq!(first, ["utility", "worldgen"]);
+    assert_eq!(second, first);
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 1);
+}
+
+#[test]
+fn revalidates_an_expired_response_with_its_etag() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![categories(&server), MockResponse::new(304, "")],
+    );
+    let backend = TestBackend::start("http-cache-etag", &server);
+
+    fetch_categories(&backend);
+    expire_http_cache();
+    let categories = fetch_categories(&backend);
+
+    assert_eq!(categories, ["utility", "worldgen"]);
+    let headers = server.headers(CATEGORIES_PATH);
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers[0].get("if-none-match"), None);
+    assert_eq!(headers[1]["if-none-match"], "\"v1\"");
+
+    // The revalidated response counts as fresh again
+    let cached = cached_responses();
+    assert_eq!(cached.len(), 1);
+    assert!(cached[0].1["fetched_at"].as_u64().unwrap() > 0);
+    assert_eq!(cached[0].1["etag"], "\"v1\"");
+    // It was written next to the previous one before taking its place
+    assert!(cached[0].0.with_extension("json.bak").exists());
+}
+
+#[test]
+fn refetches_an_expired_response_that_changed() {
+    let server = MockServer::start();
+    let changed = server
+        .fixture("categories.json", &[])
+        .replace("worldgen", "magic");
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![
+            categories(&server),
+            MockResponse::new(200, changed).header("ETag", "\"v2\""),
+        ],
+    );
+    let backend = TestBackend::start("http-cache-expired", &server);
+
+    fetch_categories(&backend);
+    expire_http_cache();
+    let categories = fetch_categories(&backend);
+
+    assert_eq!(categories, ["utility", "magic"]);
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 2);
+    assert_eq!(cached_responses()[0].1["etag"], "\"v2\"");
+}
+
+#[test]
+fn uses_expired_responses_in_offline_mode() {
+    let server = MockServer::start();
+    server.mock_sequence("GET", CATEGORIES_PATH, vec![categories(&server)]);
+    let backend = TestBackend::start("http-cache-offline", &server);
+
+    fetch_categories(&backend);
+    expire_http_cache();
+    SettingsBuilder::from_current().offline_mode(true).apply();
+    let categories = fetch_categories(&backend);
+
+    assert_eq!(categories, ["utility", "worldgen"]);
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 1);
+}
+
+#[test]
+fn falls_back_to_an_expired_response_when_the_server_is_down() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        CATEGORIES_PATH,
+        vec![categories(&server), MockResponse::new(503, "{}")],
+    );
+    let backend = TestBackend::start("http-cache-unavailable", &server);
+    SettingsBuilder::from_current()
+        .max_request_retries(0)
+        .apply();
+
+    fetch_categories(&backend);
+    expire_http_cache();
+    let categories = fetch_categories(&backend);
+
+    assert_eq!(categories, ["utility", "worldgen"]);
+    assert_eq!(server.queries(CATEGORIES_PATH).len(), 2);
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use std::{env, fs, process};
+
+use back::{
+    instances::{infer_game, SearchRoots},
+    messages::{ToBackend, ToFrontend},
+    mod_file::{ModFile, ModLoader, VersionRange},
+};
+use common::{fabric_jar_with, forge_jar_with, write, MockServer, TestBackend};
+
+/// Search roots with nothing in them, so that only the folder around the mods is looked at
+fn empty_roots(name: &str) -> (SearchRoots, std::path::PathBuf) {
+    let root = env::temp_dir().join(format!("mcubed-infer-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    let roots = SearchRoots {
+        minecraft_dir: root.join("home/.minecraft"),
+        data_dir: root.join("home/.local/share"),
+        home_dir: root.join("home"),
+    };
+
+    (roots, root)
+}
+
+fn releases() -> Vec<String> {
+    ["1.19.2", "1.19.1", "1.19", "1.18.2", "1.18.1", "1.16.5"]
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn reads_the_versions_of_a_prism_instance() {
+    let (roots, root) = empty_roots("prism");
+    let instance_dir = root.join("PrismLauncher/instances/pack");
+
+    write(&instance_dir.join("instance.cfg"), "name=Pack\n");
+    write(
+        &instance_dir.join("mmc-pack.json"),
+        r#"{"components": [
+            {"uid": "net.minecraft", "version": "1.18.2"},
+            {"uid": "net.minecraftforge", "version": "40.1.0"}
+        ]}"#,
+    );
+
+    let inferred = infer_game(
+        &instance_dir.join(".minecraft/mods"),
+        &roots,
+        &[],
+        &releases(),
+    );
+
+    assert_eq!(inferred.game_version.as_deref(), Some("1.18.2"));
+    assert_eq!(inferred.modloader, Some(ModLoader::Forge));
+    assert!(inferred.source.unwrap().contains("Prism Launcher"));
+}
+
+#[test]
+fn follows_the_versions_a_launcher_profile_inherits_from() {
+    let (roots, root) = empty_roots("vanilla");
+    let versions_dir = roots.minecraft_dir.join("versions");
+    let game_dir = root.join("modded");
+
+    write(
+        &roots.minecraft_dir.join("launcher_profiles.json"),
+        &serde_json::json!({
+            "profiles": {
+                "fabric": {
+                    "name": "Fabric",
+                    "type": "custom",
+                    "gameDir": game_dir,
+                    "lastVersionId": "my-fabric"
+                }
+            }
+        })
+        .to_string(),
+    );
+
+    // A renamed version, so that only the files can tell what it runs
+    write(
+        &versions_dir.join("my-fabric/my-fabric.json"),
+        r#"{"id": "my-fabric", "inheritsFrom": "fabric-base", "libraries": []}"#,
+    );
+    write(
+        &versions_dir.join("fabric-base/fabric-base.json"),
+        r#"{"id": "fabric-base", "inheritsFrom": "1.19.2", "libraries": [{"name": "net.fabricmc:fabric-loader:0.14.9"}]}"#,
+    );
+    write(
+        &versions_dir.join("1.19.2/1.19.2.json"),
+        r#"{"id": "1.19.2", "libraries": []}"#,
+    );
+
+    let inferred = infer_game(&game_dir.join("mods"), &roots, &[], &releases());
+
+    assert_eq!(inferred.game_version.as_deref(), Some("1.19.2"));
+    assert_eq!(inferred.modloader, Some(ModLoader::Fabric));
+}
+
+#[test]
+fn falls_back_to_what_the_mods_require() {
+    let (roots, root) = empty_roots("mods");
+    let mods_dir = root.join("loose/mods");
+    fs::create_dir_all(&mods_dir).unwrap();
+
+    let jars = [
+        fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "a",
+            "version": "1.0.0",
+            "depends": {"minecraft": ">=1.18 <1.19.2"}
+        })),
+        fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "b",
+            "version": "1.0.0",
+            "depends": {"minecraft": ["1.18.x", "~1.19"]}
+        })),
+        fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "c",
+            "version": "1.0.0",
+            "depends": {"minecraft": "1.19.x"}
+        })),
+    ];
+
+    let mod_list: Vec<ModFile> = jars
+        .iter()
+        .enumerate()
+        .map(|(index, jar)| {
+            let path = mods_dir.join(format!("{}.jar", index));
+            fs::write(&path, jar).unwrap();
+            ModFile::from_path(path).unwrap()
+        })
+        .collect();
+
+    let inferred = infer_game(&mods_dir, &roots, &mod_list, &releases());
+
+    // 1.19.1 is the newest release all three mods work on
+    assert_eq!(inferred.game_version.as_deref(), Some("1.19.1"));
+    assert_eq!(inferred.modloader, Some(ModLoader::Fabric));
+}
+
+#[test]
+fn reads_the_game_versions_of_forge_mods() {
+    let (_, root) = empty_roots("forge");
+    let path = root.join("forge.jar");
+    fs::create_dir_all(&root).unwrap();
+
+    fs::write(
+        &path,
+        forge_jar_with(
+            r#"
+modLoader = "javafml"
+loaderVersion = "[40,)"
+license = "MIT"
+issueTrackerURL = ""
+
+[[mods]]
+modId = "example"
+version = "1.0.0"
+displayName = "Example"
+description = ""
+
+[[dependencies.example]]
+modId = "minecraft"
+mandatory = true
+versionRange = "[1.18.2,1.19)"
+ordering = "NONE"
+"#,
+        ),
+    )
+    .unwrap();
+
+    let mod_file = ModFile::from_path(path).unwrap();
+    let range = mod_file.entries[0].game_versions.clone().unwrap();
+
+    assert_eq!(range, VersionRange::Maven("[1.18.2,1.19)".to_string()));
+    assert_eq!(range.matches("1.18.2"), Some(true));
+    assert_eq!(range.matches("1.19"), Some(false));
+    assert_eq!(range.matches("22w14a"), None);
+}
+
+#[test]
+fn sends_the_inferred_game_with_the_version_manifest() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+    let backend = TestBackend::start("infer-game", &server);
+
+    // The mods folder is directly inside an ATLauncher instance
+    write(
+        &backend.mods_dir.parent().unwrap().join("instance.json"),
+        r#"{"id": "1.18.1", "launcher": {"name": "Pack", "loaderVersion": {"type": "Fabric", "version": "0.13.3"}}}"#,
+    );
+
+    backend.send(ToBackend::GetVersionMetadata);
+
+    let game = backend.wait_for(|message| match message {
+        ToFrontend::InferredGame { game } => Some(game),
+        _ => None,
+    });
+
+    assert_eq!(game.game_version.as_deref(), Some("1.18.1"));
+    assert_eq!(game.modloader, Some(ModLoader::Fabric));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::fs;
+
+use back::messages::ToBackend;
+use common::{ExampleUpdate, MockServer, TestBackend};
+use sha1::Digest;
+
+/// Serve `version` as the update of the installed example mod
+fn offer_update(server: &MockServer, old_jar: &[u8], version: &str) {
+    let old_sha1 = hex::encode(sha1::Sha1::digest(old_jar));
+
+    server.mock(
+        "POST",
+        "/version_files/update",
+        format!(r#"{{"{}": {}}}"#, old_sha1, version),
+    );
+}
+
+/// Try to update the only mod in the list, returning the message of the error it fails with
+fn failed_update(backend: &TestBackend) -> String {
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+
+    backend.wait_for_error().error.to_string()
+}
+
+/// The files in the mods folder, hidden ones included
+fn folder_contents(backend: &TestBackend) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(&backend.mods_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+
+    names
+}
+
+fn assert_untouched(update: &ExampleUpdate) {
+    assert_eq!(folder_contents(&update.backend), ["example-1.0.0.jar"]);
+    assert_eq!(
+        fs::read(update.backend.mods_dir.join("example-1.0.0.jar")).unwrap(),
+        update.old_jar
+    );
+}
+
+#[test]
+fn rejects_a_download_that_does_not_match_its_hash() {
+    let update = ExampleUpdate::setup("install-mismatch", "release");
+    update
+        .server
+        .mock("GET", "/data/example-1.1.0.jar", b"tampered".to_vec());
+
+    let error = failed_update(&update.backend);
+
+    assert!(
+        error.contains("does not match the expected hash"),
+        "{}",
+        error
+    );
+    assert_untouched(&update);
+}
+
+#[test]
+fn rejects_a_download_without_hashes() {
+    let update = ExampleUpdate::setup("install-unverified", "release");
+
+    let mut version: serde_json::Value =
+        serde_json::from_str(&update.server.host_jar("1.1.0", &update.new_jar)).unwrap();
+    version["files"][0]["hashes"] = serde_json::json!({});
+    offer_update(&update.server, &update.old_jar, &version.to_string());
+
+    let error = failed_update(&update.backend);
+
+    assert!(error.contains("did not provide a hash"), "{}", error);
+    assert_untouched(&update);
+}
+
+#[test]
+fn keeps_the_old_file_when_the_new_one_is_not_a_mod() {
+    let update = ExampleUpdate::setup("install-invalid", "release");
+
+    // The hashes match, but the contents can not be read as a mod
+    let version = update.server.host_jar("1.1.0", b"not a jar");
+    offer_update(&update.server, &update.old_jar, &version);
+
+    failed_update(&update.backend);
+
+    assert_untouched(&update);
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use std::{env, fs, path::PathBuf, process};
+
+use back::{
+    instances::{detect_instances_in, Instance, Launcher, SearchRoots},
+    messages::{ToBackend, ToFrontend},
+    mod_file::ModLoader,
+};
+use common::{write, MockServer, TestBackend};
+
+/// A home folder with one instance of every supported launcher
+fn fake_home(name: &str) -> SearchRoots {
+    let home_dir = env::temp_dir().join(format!("mcubed-home-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&home_dir);
+
+    let roots = SearchRoots {
+        minecraft_dir: home_dir.join(".minecraft"),
+        data_dir: home_dir.join(".local/share"),
+        home_dir,
+    };
+
+    let modded_dir = roots.home_dir.join("modded");
+    write(
+        &roots.minecraft_dir.join("launcher_profiles.json"),
+        &serde_json::json!({
+            "profiles": {
+                "a": {
+                    "name": "",
+                    "type": "latest-release",
+                    "lastVersionId": "latest-release",
+                    "lastUsed": "2022-01-01T00:00:00.000Z"
+                },
+                "b": {
+                    "name": "Modded",
+                    "type": "custom",
+                    "gameDir": modded_dir,
+                    "lastVersionId": "fabric-loader-0.14.9-1.19.2",
+                    "lastUsed": "2022-06-01T00:00:00.000Z"
+                },
+                "c": {
+                    "name": "Old Forge",
+                    "type": "custom",
+                    "lastVersionId": "1.12.2-forge1.12.2-14.23.5.2859",
+                    "lastUsed": "2021-01-01T00:00:00.000Z"
+                }
+            }
+        })
+        .to_string(),
+    );
+
+    let prism_instance = roots.data_dir.join("PrismLauncher/instances/fabric-pack");
+    write(
+        &prism_instance.join("instance.cfg"),
+        "InstanceType=OneSix\nname=Fabric Pack\n",
+    );
+    write(
+        &prism_instance.join("mmc-pack.json"),
+        r#"{"components": [
+            {"uid": "net.minecraft", "version": "1.18.2"},
+            {"uid": "net.fabricmc.fabric-loader", "version": "0.14.8"}
+        ]}"#,
+    );
+    fs::create_dir_all(prism_instance.join("minecraft")).unwrap();
+
+    write(
+        &roots
+            .data_dir
+            .join("ATLauncher/instances/forge-pack/instance.json"),
+        r#"{"id": "1.16.5", "launcher": {"name": "Forge Pack", "loaderVersion": {"type": "Forge", "version": "36.2.39"}}}"#,
+    );
+
+    write(
+        &roots
+            .data_dir
+            .join("gdlauncher_next/instances/gd-pack/config.json"),
+        r#"{"loader": {"loaderType": "fabric", "mcVersion": "1.19", "loaderVersion": "0.14.7"}}"#,
+    );
+
+    roots
+}
+
+fn find<'a>(instances: &'a [Instance], name: &str) -> &'a Instance {
+    instances
+        .iter()
+        .find(|instance| instance.name == name)
+        .unwrap_or_else(|| panic!("{} was not detected in {:#?}", name, instances))
+}
+
+#[test]
+fn detects_the_instances_of_every_launcher() {
+    let roots = fake_home("detect");
+    let instances = detect_instances_in(&roots);
+
+    // The latest release and the old Forge profiles share the default folder, so only the more recent one is kept
+    assert_eq!(instances.len(), 5, "{:#?}", instances);
+
+    let modded = find(&instances, "Modded");
+    assert_eq!(modded.launcher, Launcher::Official);
+    assert_eq!(modded.game_dir, roots.home_dir.join("modded"));
+    assert_eq!(modded.game_version.as_deref(), Some("1.19.2"));
+    assert_eq!(modded.modloader, Some(ModLoader::Fabric));
+    assert_eq!(modded.loader_version.as_deref(), Some("0.14.9"));
+
+    let latest = find(&instances, "Latest release");
+    assert_eq!(latest.game_dir, roots.minecraft_dir);
+    assert_eq!(latest.game_version, None);
+
+    let prism = find(&instances, "Fabric Pack");
+    assert_eq!(prism.launcher, Launcher::Prism);
+    assert_eq!(
+        prism.mods_dir(),
+        roots
+            .data_dir
+            .join("PrismLauncher/instances/fabric-pack/minecraft/mods")
+    );
+    assert_eq!(prism.game_version.as_deref(), Some("1.18.2"));
+    assert_eq!(prism.modloader, Some(ModLoader::Fabric));
+
+    let atlauncher = find(&instances, "Forge Pack");
+    assert_eq!(atlauncher.launcher, Launcher::AtLauncher);
+    assert_eq!(atlauncher.game_version.as_deref(), Some("1.16.5"));
+    assert_eq!(atlauncher.modloader, Some(ModLoader::Forge));
+    assert_eq!(atlauncher.loader_version.as_deref(), Some("36.2.39"));
+
+    let gdlauncher = find(&instances, "gd-pack");
+    assert_eq!(gdlauncher.launcher, Launcher::GdLauncher);
+    assert_eq!(gdlauncher.game_version.as_deref(), Some("1.19"));
+}
+
+#[test]
+fn adopting_an_instance_switches_to_it_as_a_profile() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("adopt-instance", &server);
+
+    let game_dir: PathBuf = backend.mods_dir.with_file_name("instance");
+    let instance = Instance {
+        name: "Fabric Pack".to_string(),
+        launcher: Launcher::Prism,
+        game_dir: game_dir.clone(),
+        game_version: Some("1.19.2".to_string()),
+        modloader: Some(ModLoader::Fabric),
+        loader_version: Some("0.14.9".to_string()),
+    };
+
+    backend.send(ToBackend::AdoptInstance {
+        instance: Box::new(instance),
+    });
+
+    let settings = backend.wait_for(|message| match message {
+        ToFrontend::SettingsChanged { settings } => Some(*settings),
+        _ => None,
+    });
+
+    // The mods folder did not exist yet, so it was created
+    assert!(game_dir.join("mods").is_dir());
+    assert_eq!(settings.mod_folder_path, game_dir.join("mods"));
+
+    let profile = settings.active_profile().unwrap();
+    assert_eq!(profile.name, "Fabric Pack (Prism Launcher)");
+    assert_eq!(profile.game_version.as_deref(), Some("1.19.2"));
+    assert_eq!(profile.modloader, Some(ModLoader::Fabric));
+}
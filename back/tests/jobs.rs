@@ -0,0 +1,113 @@
+mod common;
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use back::messages::{BackendResponse, JobPhase, ToBackend, ToFrontend};
+use common::{ExampleUpdate, MockResponse, MockServer, TestBackend};
+
+const STALL: Duration = Duration::from_secs(5);
+
+#[test]
+fn cancels_a_running_update() {
+    let update = ExampleUpdate::setup("jobs-cancel", "release");
+    let backend = &update.backend;
+    update.server.mock_sequence(
+        "GET",
+        "/data/example-1.1.0.jar",
+        vec![MockResponse::new(200, update.new_jar.clone()).delay(STALL)],
+    );
+
+    let mod_list = backend.check_for_updates();
+    let id = backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::Progress { progress }
+            if progress.job_id == id && progress.phase == JobPhase::Downloading =>
+        {
+            Some(())
+        }
+        _ => None,
+    });
+
+    let started = Instant::now();
+    backend.send(ToBackend::Cancel { id });
+    backend.wait_for_response(|response| match response {
+        BackendResponse {
+            id: response_id,
+            message: ToFrontend::Cancelled,
+        } if response_id == id => Some(()),
+        _ => None,
+    });
+
+    assert!(started.elapsed() < STALL);
+    let names: Vec<String> = fs::read_dir(&backend.mods_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, ["example-1.0.0.jar"]);
+}
+
+#[test]
+fn answers_other_requests_while_a_job_runs() {
+    let server = MockServer::start();
+    server.mock_sequence(
+        "GET",
+        "/tag/category",
+        vec![
+            MockResponse::new(200, server.fixture("categories.json", &[]))
+                .delay(Duration::from_secs(1)),
+        ],
+    );
+    let backend = TestBackend::start("jobs-concurrent", &server);
+
+    let categories_id = backend.send(ToBackend::GetSearchCategories);
+    let scan_id = backend.send(ToBackend::ScanFolder);
+
+    let mut answered = Vec::new();
+    backend.wait_for_response(|BackendResponse { id, message }| {
+        match message {
+            ToFrontend::UpdateModList { .. } | ToFrontend::SearchCategories { .. } => {
+                answered.push(id);
+            }
+            _ => {}
+        }
+
+        if answered.len() == 2 {
+            Some(())
+        } else {
+            None
+        }
+    });
+
+    // The quick scan is not held up by the slow request, and each answer is tagged with its own request
+    assert_eq!(answered, [scan_id, categories_id]);
+}
+
+#[test]
+fn keeps_running_when_the_mods_folder_cannot_be_read() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("jobs-unreadable", &server);
+    fs::remove_dir_all(&backend.mods_dir).unwrap();
+
+    backend.send(ToBackend::ScanFolder);
+    let error = backend.wait_for_error();
+
+    assert!(
+        error.message.starts_with("Could not read"),
+        "{}",
+        error.message
+    );
+
+    fs::create_dir_all(&backend.mods_dir).unwrap();
+    backend.send(ToBackend::ScanFolder);
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    assert!(mod_list.is_empty());
+}
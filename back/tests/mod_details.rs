@@ -0,0 +1,162 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{
+        details::{DeclaredDependency, DependencyKind, FileDetails},
+        ModFile,
+    },
+};
+use common::{fabric_jar_with, forge_jar_with, MockServer, TestBackend};
+
+fn details(backend: &TestBackend, file_name: &str, jar: &[u8]) -> FileDetails {
+    backend.install(file_name, jar);
+
+    let mod_file = ModFile::from_path(backend.mods_dir.join(file_name)).unwrap();
+    backend.send(ToBackend::GetModDetails {
+        mod_file: Box::new(mod_file),
+    });
+
+    *backend.wait_for(|message| match message {
+        ToFrontend::ModDetails { details } => Some(details),
+        _ => None,
+    })
+}
+
+#[test]
+fn reads_everything_a_fabric_manifest_declares() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("details-fabric", &server);
+
+    let jar = fabric_jar_with(serde_json::json!({
+        "schemaVersion": 1,
+        "id": "example",
+        "name": "Example",
+        "version": "1.0.0",
+        "license": ["MIT", "Apache-2.0"],
+        "contributors": ["Someone", {"name": "Someone Else", "contact": "someone@example.com"}],
+        "contact": {
+            "homepage": "https://example.com",
+            "issues": "https://example.com/issues",
+            "discord": "https://discord.gg/example"
+        },
+        "entrypoints": {
+            "main": ["com.example.Example"],
+            "client": [{"adapter": "kotlin", "value": "com.example.ExampleClient"}],
+            "modmenu": ["com.example.ModMenuApi"]
+        },
+        "mixins": ["example.mixins.json", {"config": "example.client.mixins.json", "environment": "client"}],
+        "depends": {"fabricloader": ">=0.14", "fabric": "*"},
+        "breaks": {"optifabric": "*"}
+    }));
+    let details = details(&backend, "example.jar", &jar);
+
+    assert_eq!(details.size, jar.len() as u64);
+    assert_eq!(details.modrinth_url, None);
+    assert_eq!(details.manifests.len(), 1);
+    assert_eq!(details.manifests[0].0, "fabric.mod.json");
+    assert!(details.manifests[0].1.contains("com.example.Example"));
+
+    let entry = &details.entries[0];
+    assert_eq!(entry.license.as_deref(), Some("MIT, Apache-2.0"));
+    assert_eq!(entry.contributors, ["Someone", "Someone Else"]);
+    assert_eq!(
+        entry.contact,
+        [
+            ("Homepage".to_string(), "https://example.com".to_string()),
+            (
+                "Issues".to_string(),
+                "https://example.com/issues".to_string()
+            ),
+            (
+                "Discord".to_string(),
+                "https://discord.gg/example".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        entry.entrypoints,
+        [
+            ("main".to_string(), "com.example.Example".to_string()),
+            (
+                "client".to_string(),
+                "com.example.ExampleClient (kotlin)".to_string()
+            ),
+            ("modmenu".to_string(), "com.example.ModMenuApi".to_string()),
+        ]
+    );
+    assert_eq!(
+        entry.mixins,
+        ["example.mixins.json", "example.client.mixins.json (client)"]
+    );
+    assert_eq!(
+        entry.dependencies,
+        [
+            DeclaredDependency {
+                id: "fabric".to_string(),
+                kind: DependencyKind::Required,
+                versions: "*".to_string(),
+            },
+            DeclaredDependency {
+                id: "fabricloader".to_string(),
+                kind: DependencyKind::Required,
+                versions: ">=0.14".to_string(),
+            },
+            DeclaredDependency {
+                id: "optifabric".to_string(),
+                kind: DependencyKind::Breaks,
+                versions: "*".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn reads_the_links_and_credits_of_forge_mods() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("details-forge", &server);
+
+    let details = details(
+        &backend,
+        "forge.jar",
+        &forge_jar_with(
+            r#"
+modLoader = "javafml"
+loaderVersion = "[40,)"
+license = "LGPL-3.0"
+issueTrackerURL = "https://example.com/issues"
+
+[[mods]]
+modId = "example"
+version = "1.0.0"
+displayName = "Example"
+displayURL = "https://example.com"
+credits = "Thanks to everyone"
+description = ""
+
+[[dependencies.example]]
+modId = "jei"
+mandatory = false
+versionRange = "[10,)"
+ordering = "AFTER"
+"#,
+        ),
+    );
+
+    let entry = &details.entries[0];
+    assert_eq!(entry.license.as_deref(), Some("LGPL-3.0"));
+    assert_eq!(entry.credits.as_deref(), Some("Thanks to everyone"));
+    assert_eq!(
+        entry.contact,
+        [
+            ("Homepage".to_string(), "https://example.com".to_string()),
+            (
+                "Issues".to_string(),
+                "https://example.com/issues".to_string()
+            ),
+        ]
+    );
+    assert_eq!(entry.dependencies[0].kind, DependencyKind::Optional);
+    assert_eq!(entry.dependencies[0].versions, "[10,)");
+    assert_eq!(details.manifests[0].0, "META-INF/mods.toml");
+}
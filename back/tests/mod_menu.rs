@@ -0,0 +1,130 @@
+mod common;
+
+use std::{env, fs, process};
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{FileState, ModFile},
+};
+use common::{fabric_jar_with, MockServer, TestBackend};
+
+#[test]
+fn reads_the_mod_menu_metadata() {
+    let dir = env::temp_dir().join(format!("mcubed-mod-menu-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let module = dir.join("module.jar");
+    fs::write(
+        &module,
+        fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "fabric-api-base",
+            "version": "0.4.12",
+            "provides": ["fabric-base"],
+            "custom": {
+                "modmenu": {
+                    "badges": ["library"],
+                    "parent": "fabric-api",
+                    "links": {"modmenu.discord": "https://discord.gg/v6v4pMv"}
+                }
+            }
+        })),
+    )
+    .unwrap();
+
+    let entry = &ModFile::from_path(module).unwrap().entries[0];
+    assert_eq!(entry.provides, ["fabric-base"]);
+    assert_eq!(entry.parent.as_deref(), Some("fabric-api"));
+    assert!(entry.is_library());
+    assert_eq!(
+        entry.links.get("modmenu.discord").map(String::as_str),
+        Some("https://discord.gg/v6v4pMv")
+    );
+
+    // A parent can also be described in full, for mods that group under something that is not a mod
+    let grouped = dir.join("grouped.jar");
+    fs::write(
+        &grouped,
+        fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "grouped",
+            "version": "1.0.0",
+            "custom": {"modmenu": {"parent": {"id": "group", "name": "Group"}}}
+        })),
+    )
+    .unwrap();
+
+    let entry = &ModFile::from_path(grouped).unwrap().entries[0];
+    assert_eq!(entry.parent.as_deref(), Some("group"));
+    assert!(!entry.is_library());
+
+    // Metadata Mod Menu would not understand does not keep the mod from being read
+    let broken = dir.join("broken.jar");
+    fs::write(
+        &broken,
+        fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "broken",
+            "version": "1.0.0",
+            "custom": {"modmenu": {"badges": "library"}}
+        })),
+    )
+    .unwrap();
+
+    let entry = &ModFile::from_path(broken).unwrap().entries[0];
+    assert_eq!(entry.parent, None);
+    assert!(entry.badges.is_empty());
+}
+
+#[test]
+fn provided_ids_satisfy_dependencies() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("dependencies", &server);
+
+    backend.install(
+        "fabric-api.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "fabric-api",
+            "version": "0.60.0",
+            "provides": ["fabric"]
+        })),
+    );
+    backend.install(
+        "uses-fabric.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "uses-fabric",
+            "version": "1.0.0",
+            "depends": {"fabricloader": ">=0.14", "minecraft": "1.19.x", "fabric": "*"}
+        })),
+    );
+    backend.install(
+        "uses-missing.jar",
+        &fabric_jar_with(serde_json::json!({
+            "schemaVersion": 1,
+            "id": "uses-missing",
+            "name": "Uses Missing",
+            "version": "1.0.0",
+            "depends": {"fabric-api": "*", "cloth-config": "*"}
+        })),
+    );
+
+    backend.send(ToBackend::ScanFolder);
+
+    let mut mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+    mod_list.sort_by(|a, b| a.entries[0].id.cmp(&b.entries[0].id));
+
+    assert_eq!(mod_list[1].entries[0].dependencies, ["fabric"]);
+    assert_eq!(mod_list[1].data.state, FileState::Current);
+    assert_eq!(mod_list[1].data.state_reason, None);
+
+    assert_eq!(mod_list[2].data.state, FileState::Invalid);
+    let reason = mod_list[2].data.state_reason.as_deref().unwrap();
+    assert!(reason.contains("Uses Missing"), "{}", reason);
+    assert!(reason.contains("cloth-config"), "{}", reason);
+    assert!(!reason.contains("fabric-api"), "{}", reason);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{FileState, ModFile, ModLoader},
+};
+use common::{multi_loader_jar, MockServer, TestBackend};
+use sha1::Digest;
+
+/// Install a jar shipping both manifests, for which Modrinth reports `latest_version` as the newest version
+fn check_for_updates(
+    name: &str,
+    server: &MockServer,
+    latest_version: &str,
+    modloader: ModLoader,
+) -> Vec<ModFile> {
+    let jar = multi_loader_jar("example", "1.0.0");
+    let sha1 = hex::encode(sha1::Sha1::digest(&jar));
+    let old_version = server.host_jar("1.0.0", &jar);
+
+    server.mock(
+        "POST",
+        "/version_files",
+        format!(r#"{{"{}": {}}}"#, sha1, old_version),
+    );
+    server.mock(
+        "POST",
+        "/version_files/update",
+        format!(r#"{{"{}": {}}}"#, sha1, latest_version),
+    );
+    server.mock(
+        "GET",
+        "/project/AABBCCDD/version",
+        format!("[{}]", latest_version),
+    );
+
+    let backend = TestBackend::start(name, server);
+    backend.install("example-1.0.0.jar", &jar);
+
+    backend.send(ToBackend::CheckForUpdates {
+        game_version: "1.18.2".to_string(),
+        modloader,
+    });
+
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    })
+}
+
+#[test]
+fn picks_the_file_for_the_active_loader() {
+    let server = MockServer::start();
+    let values: Vec<(&str, String)> =
+        ["FABRIC_SHA1", "FABRIC_SHA512", "FORGE_SHA1", "FORGE_SHA512"]
+            .iter()
+            .map(|key| (*key, "0".repeat(40)))
+            .chain([
+                ("FABRIC_SIZE", "1".to_string()),
+                ("FORGE_SIZE", "1".to_string()),
+            ])
+            .collect();
+    let latest_version = server.fixture("version_1.1.0_multi.json", &values);
+
+    let mod_list = check_for_updates("multi-forge", &server, &latest_version, ModLoader::Forge);
+
+    assert_eq!(mod_list[0].data.loaders, vec![ModLoader::Both]);
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    let latest_file = modrinth_data.latest_valid_version.as_ref().unwrap();
+    assert_eq!(latest_file.filename, "example-forge-1.1.0.jar");
+}
+
+#[test]
+fn never_drops_the_active_loader() {
+    let server = MockServer::start();
+    // The regular fixture only supports Fabric
+    let latest_version = server.host_jar("1.1.0", b"fabric only");
+
+    let mod_list = check_for_updates(
+        "multi-fabric-only",
+        &server,
+        &latest_version,
+        ModLoader::Forge,
+    );
+
+    assert_eq!(mod_list[0].data.state, FileState::Current);
+
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    assert!(modrinth_data.latest_valid_version.is_none());
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{FileState, ModLoader},
+    settings::SettingsBuilder,
+    VersionType,
+};
+use common::{expire_http_cache, ExampleUpdate, MockServer};
+
+const PATHS: [&str; 3] = [
+    "/version_files",
+    "/version_files/update",
+    "/project/AABBCCDD/version",
+];
+
+fn request_count(server: &MockServer) -> usize {
+    PATHS.iter().map(|path| server.queries(path).len()).sum()
+}
+
+fn go_offline() {
+    SettingsBuilder::from_current().offline_mode(true).apply();
+}
+
+#[test]
+fn checks_for_updates_from_the_cached_version_lists() {
+    // The update is a beta, so the check goes through the full version list, which is then cached
+    let update = ExampleUpdate::setup("offline-check", "beta");
+    let backend = &update.backend;
+
+    let mod_list = backend.check_for_updates();
+    assert_eq!(mod_list[0].data.state, FileState::Current);
+    expire_http_cache();
+
+    backend.send(ToBackend::SetReleaseChannel {
+        sha1: mod_list[0].hashes.sha1.clone(),
+        channel: Some(VersionType::Beta),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { .. } => Some(()),
+        _ => None,
+    });
+    go_offline();
+    let requests = request_count(&update.server);
+    let mod_list = backend.check_for_updates();
+
+    assert_eq!(request_count(&update.server), requests);
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+
+    // The list was too old to be trusted, which is shown along with the update
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    assert_eq!(
+        modrinth_data.latest_version_number.as_deref(),
+        Some("1.1.0")
+    );
+    assert!(modrinth_data.stale);
+}
+
+#[test]
+fn reports_the_files_that_were_never_cached() {
+    let update = ExampleUpdate::setup("offline-uncached", "release");
+    let backend = &update.backend;
+
+    backend.check_for_updates();
+    go_offline();
+    let requests = request_count(&update.server);
+    backend.send(ToBackend::CheckForUpdates {
+        game_version: "1.18.2".to_string(),
+        modloader: ModLoader::Fabric,
+    });
+    let error = backend.wait_for_error();
+
+    assert!(
+        error.error.to_string().contains("Working offline"),
+        "{}",
+        error.error
+    );
+    assert_eq!(request_count(&update.server), requests);
+}
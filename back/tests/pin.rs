@@ -0,0 +1,111 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{FileState, ModFile, Pin},
+};
+use common::{fabric_jar, ExampleUpdate, TestBackend};
+
+const UPDATE_PATH: &str = "/version_files/update";
+const VERSION_LIST_PATH: &str = "/project/AABBCCDD/version";
+
+fn set_pin(backend: &TestBackend, mod_file: &ModFile, pin: Pin) {
+    backend.send(ToBackend::SetPin {
+        sha1: mod_file.hashes.sha1.clone(),
+        pin: Some(pin),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { .. } => Some(()),
+        _ => None,
+    });
+}
+
+fn latest_version(mod_file: &ModFile) -> Option<&str> {
+    let modrinth_data = mod_file.data.sources.modrinth.as_ref()?;
+    modrinth_data.latest_version_number.as_deref()
+}
+
+#[test]
+fn skips_files_pinned_to_their_current_version() {
+    let update = ExampleUpdate::setup("pin-current", "release");
+    let backend = &update.backend;
+
+    let mod_list = backend.check_for_updates();
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+    set_pin(backend, &mod_list[0], Pin::CurrentFile);
+    let checks = update.server.queries(UPDATE_PATH).len();
+
+    for _ in 0..2 {
+        let mod_list = backend.check_for_updates();
+
+        assert_eq!(mod_list[0].data.pin, Some(Pin::CurrentFile));
+        assert_eq!(mod_list[0].data.state, FileState::Pinned);
+    }
+    // The pinned file is not looked up at all
+    assert_eq!(update.server.queries(UPDATE_PATH).len(), checks);
+}
+
+#[test]
+fn does_not_offer_an_ignored_version() {
+    let update = ExampleUpdate::setup("pin-ignore", "release");
+    let backend = &update.backend;
+
+    let mod_list = backend.check_for_updates();
+    set_pin(
+        backend,
+        &mod_list[0],
+        Pin::IgnoreVersion {
+            version_id: "v1100000".to_string(),
+        },
+    );
+
+    for _ in 0..2 {
+        let mod_list = backend.check_for_updates();
+
+        assert_eq!(
+            mod_list[0].data.pin,
+            Some(Pin::IgnoreVersion {
+                version_id: "v1100000".to_string()
+            })
+        );
+        assert_eq!(mod_list[0].data.state, FileState::Pinned);
+    }
+    // The latest version is the ignored one, so the full list is gone through, the second time from the cache
+    assert_eq!(update.server.queries(VERSION_LIST_PATH).len(), 1);
+}
+
+#[test]
+fn offers_versions_newer_than_the_ignored_one() {
+    let update = ExampleUpdate::setup("pin-ignore-newer", "release");
+    let server = &update.server;
+    let backend = &update.backend;
+
+    let mod_list = backend.check_for_updates();
+    set_pin(
+        backend,
+        &mod_list[0],
+        Pin::IgnoreVersion {
+            version_id: "v1100000".to_string(),
+        },
+    );
+
+    // A newer version comes out, while the bulk endpoint still answers with the ignored one
+    let newest_jar = fabric_jar("example", "1.2.0");
+    let newest_version = server.host_other_version("1.2.0", "2022-05-01", &newest_jar);
+    server.mock(
+        "GET",
+        VERSION_LIST_PATH,
+        format!(
+            "[{}, {}, {}]",
+            server.host_jar("1.0.0", &update.old_jar),
+            server.host_jar("1.1.0", &update.new_jar),
+            newest_version
+        ),
+    );
+
+    let mod_list = backend.check_for_updates();
+
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+    assert_eq!(latest_version(&mod_list[0]), Some("1.2.0"));
+    assert!(mod_list[0].data.pin.is_some());
+}
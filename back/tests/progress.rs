@@ -0,0 +1,132 @@
+mod common;
+
+use std::collections::HashMap;
+
+use back::{
+    messages::{JobPhase, JobProgress, ToBackend, ToFrontend},
+    mod_file::ModLoader,
+};
+use common::{ExampleUpdate, TestBackend};
+
+/// Every update of the first job to finish after going through `phase`, in order
+fn wait_for_job(backend: &TestBackend, phase: JobPhase) -> Vec<JobProgress> {
+    let mut jobs: HashMap<_, Vec<JobProgress>> = HashMap::new();
+
+    backend.wait_for(|message| match message {
+        ToFrontend::Progress { progress } => {
+            let job_id = progress.job_id;
+            let finished = progress.phase == JobPhase::Finished;
+            let updates = jobs.entry(job_id).or_default();
+            updates.push(progress);
+
+            let went_through = updates.iter().any(|update| update.phase == phase);
+            (finished && went_through).then(|| jobs.remove(&job_id).unwrap())
+        }
+        _ => None,
+    })
+}
+
+/// The phases a job went through, without repeating the ones reported several times in a row
+fn phases(updates: &[JobProgress]) -> Vec<JobPhase> {
+    let mut phases: Vec<JobPhase> = updates.iter().map(|update| update.phase).collect();
+    phases.dedup();
+
+    phases
+}
+
+#[test]
+fn reports_every_step_of_a_job_as_one_entry() {
+    let update = ExampleUpdate::setup("progress-check", "release");
+
+    update.backend.send(ToBackend::CheckForUpdates {
+        game_version: "1.18.2".to_string(),
+        modloader: ModLoader::Fabric,
+    });
+    let updates = wait_for_job(&update.backend, JobPhase::CheckingForUpdates);
+
+    assert_eq!(
+        phases(&updates),
+        [
+            JobPhase::Scanning,
+            JobPhase::CheckingForUpdates,
+            JobPhase::Finished
+        ]
+    );
+    assert!(updates.iter().all(|update| update.cancellable));
+}
+
+#[test]
+fn keeps_an_update_in_one_entry_until_the_list_is_refreshed() {
+    let update = ExampleUpdate::setup("progress-update", "release");
+
+    let mod_list = update.backend.check_for_updates();
+    update.backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    let updates = wait_for_job(&update.backend, JobPhase::Downloading);
+
+    assert_eq!(
+        phases(&updates),
+        [
+            JobPhase::Downloading,
+            JobPhase::Installing,
+            JobPhase::Scanning,
+            JobPhase::Finished
+        ]
+    );
+}
+
+#[test]
+fn only_offers_to_cancel_jobs_running_on_their_own() {
+    let update = ExampleUpdate::setup("progress-scan", "release");
+
+    update.backend.send(ToBackend::ScanFolder);
+    let updates = wait_for_job(&update.backend, JobPhase::Scanning);
+
+    assert_eq!(phases(&updates), [JobPhase::Scanning, JobPhase::Finished]);
+    assert!(updates.iter().all(|update| !update.cancellable));
+}
+
+#[test]
+fn reports_the_progress_of_a_rollback() {
+    let update = ExampleUpdate::setup("progress-rollback", "release");
+    let backend = &update.backend;
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+    let history = backend.wait_for(|message| match message {
+        ToFrontend::UpdateVersionHistory { history } => Some(history),
+        _ => None,
+    });
+
+    let id = backend.send(ToBackend::RollbackMod {
+        mod_file: Box::new(mod_list[0].clone()),
+        sha1: history[0].hashes.sha1.clone(),
+    });
+    let mut updates = Vec::new();
+    backend.wait_for(|message| match message {
+        ToFrontend::Progress { progress } if progress.job_id == id => {
+            let finished = progress.phase == JobPhase::Finished;
+            updates.push(progress);
+
+            if finished {
+                Some(())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    });
+
+    assert_eq!(
+        phases(&updates),
+        [JobPhase::Installing, JobPhase::Scanning, JobPhase::Finished]
+    );
+    assert!(updates.iter().all(|update| !update.cancellable));
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::{env, fs, process};
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{ModFile, ModLoader},
+    settings::CONF,
+};
+use common::{fabric_jar, forge_jar_with, icon_png, MockServer, TestBackend};
+use sha1::Digest;
+
+#[test]
+fn attaches_the_project_to_files_found_on_modrinth() {
+    let server = MockServer::start();
+
+    let jar = fabric_jar("example", "1.0.0");
+    let sha1 = hex::encode(sha1::Sha1::digest(&jar));
+    let old_version = server.host_jar("1.0.0", &jar);
+
+    server.mock(
+        "POST",
+        "/version_files",
+        format!(r#"{{"{}": {}}}"#, sha1, old_version),
+    );
+    server.mock(
+        "POST",
+        "/version_files/update",
+        format!(r#"{{"{}": {}}}"#, sha1, old_version),
+    );
+
+    let mut project: serde_json::Value =
+        serde_json::from_str(&server.fixture("project.json", &[])).unwrap();
+    project["icon_url"] = format!("{}/data/project-icon.png", server.url()).into();
+    project["issues_url"] = "https://example.com/issues".into();
+    project["donation_urls"] = serde_json::json!([{"id": "ko-fi", "platform": "Ko-fi", "url": "https://ko-fi.com/example"}]);
+    server.mock("GET", "/projects", format!("[{}]", project));
+    server.mock("GET", "/data/project-icon.png", icon_png());
+
+    let backend = TestBackend::start("projects", &server);
+    backend.install("example-1.0.0.jar", &jar);
+
+    backend.send(ToBackend::CheckForUpdates {
+        game_version: "1.18.2".to_string(),
+        modloader: ModLoader::Fabric,
+    });
+
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    let project = modrinth_data.project.as_ref().unwrap();
+    assert_eq!(project.summary, "A mod used to test mCubed");
+    assert_eq!(project.categories, ["utility"]);
+    assert_eq!(
+        project.links,
+        [
+            (
+                "Issues".to_string(),
+                "https://example.com/issues".to_string()
+            ),
+            ("Ko-fi".to_string(), "https://ko-fi.com/example".to_string()),
+        ]
+    );
+
+    let icon_size = CONF.lock().icon_resize_size as usize;
+    assert_eq!(
+        project.icon.as_ref().unwrap().len(),
+        icon_size * icon_size * 4
+    );
+
+    // Every project is asked for at once
+    let queries = server.queries("/projects");
+    assert_eq!(queries.len(), 1);
+    assert_eq!(queries[0]["ids"], r#"["AABBCCDD"]"#);
+
+    // The icon is kept on disk, so that it is there without asking again
+    let cached_icons = fs::read_dir(TestBackend::config_dir().join("icon_cache"))
+        .unwrap()
+        .count();
+    assert_eq!(cached_icons, 1);
+}
+
+#[test]
+fn ignores_the_placeholder_description_of_the_forge_template() {
+    let dir = env::temp_dir().join(format!("mcubed-placeholder-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("forge.jar");
+
+    fs::write(
+        &path,
+        forge_jar_with(
+            r#"
+modLoader = "javafml"
+loaderVersion = "[40,)"
+license = "MIT"
+issueTrackerURL = ""
+
+[[mods]]
+modId = "examplemod"
+version = "1.0.0"
+displayName = "Example Mod"
+description = '''
+This is a long form description of the mod. You can write whatever you want here
+'''
+
+[dependencies]
+"#,
+        ),
+    )
+    .unwrap();
+
+    let mod_file = ModFile::from_path(path).unwrap();
+    assert_eq!(mod_file.entries[0].own_description(), None);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::FileState,
+    VersionType,
+};
+use common::ExampleUpdate;
+
+#[test]
+fn honours_the_minimum_release_channel() {
+    // The update is a beta, while only releases are accepted by default
+    let ExampleUpdate { backend, .. } = ExampleUpdate::setup("channel", "beta");
+
+    let mod_list = backend.check_for_updates();
+
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].data.state, FileState::Current);
+}
+
+#[test]
+fn overrides_the_release_channel_per_mod() {
+    let ExampleUpdate { backend, .. } = ExampleUpdate::setup("channel-override", "beta");
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::SetReleaseChannel {
+        sha1: mod_list[0].hashes.sha1.clone(),
+        channel: Some(VersionType::Beta),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    let mod_list = backend.check_for_updates();
+
+    assert_eq!(mod_list[0].data.release_channel, Some(VersionType::Beta));
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    assert_eq!(
+        modrinth_data.latest_version_number.as_deref(),
+        Some("1.1.0")
+    );
+}
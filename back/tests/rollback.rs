@@ -0,0 +1,203 @@
+mod common;
+
+use std::fs;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::{FileState, ModFile},
+    settings::SettingsBuilder,
+    versions::StoredVersion,
+};
+use common::{fabric_jar, ExampleUpdate, TestBackend};
+use sha1::Digest;
+
+/// Update the only mod in the list, returning the new list along with the stored versions
+fn update(backend: &TestBackend) -> (Vec<ModFile>, Vec<StoredVersion>) {
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+
+    wait_for_history(backend)
+}
+
+/// Wait for the list and the version history sent after it
+fn wait_for_history(backend: &TestBackend) -> (Vec<ModFile>, Vec<StoredVersion>) {
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+    let history = backend.wait_for(|message| match message {
+        ToFrontend::UpdateVersionHistory { history } => Some(history),
+        _ => None,
+    });
+
+    (mod_list, history)
+}
+
+/// The jars kept in the version store
+fn stored_jars() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(TestBackend::config_dir().join("versions"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".jar"))
+        .collect();
+    names.sort();
+
+    names
+}
+
+/// Offer a third version as the update of the second
+fn offer_a_third_version(update_setup: &ExampleUpdate) {
+    let server = &update_setup.server;
+    let old_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.old_jar));
+    let middle_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.new_jar));
+    let newest_jar = fabric_jar("example", "1.2.0");
+    let middle_version = server.host_jar("1.1.0", &update_setup.new_jar);
+    let newest_version = server
+        .host_jar("1.1.0", &newest_jar)
+        .replace("1.1.0", "1.2.0");
+    server.mock("GET", "/data/example-1.2.0.jar", newest_jar);
+    server.mock(
+        "POST",
+        "/version_files",
+        format!(
+            r#"{{"{}": {}, "{}": {}}}"#,
+            old_sha1,
+            server.host_jar("1.0.0", &update_setup.old_jar),
+            middle_sha1,
+            middle_version
+        ),
+    );
+    server.mock(
+        "POST",
+        "/version_files/update",
+        format!(r#"{{"{}": {}}}"#, middle_sha1, newest_version),
+    );
+}
+
+#[test]
+fn rolls_back_to_the_replaced_file() {
+    let update_setup = ExampleUpdate::setup("rollback", "release");
+    let backend = &update_setup.backend;
+    let old_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.old_jar));
+    let new_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.new_jar));
+
+    let (mod_list, history) = update(backend);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].hashes.sha1, old_sha1);
+
+    backend.send(ToBackend::RollbackMod {
+        mod_file: Box::new(mod_list[0].clone()),
+        sha1: old_sha1,
+    });
+    let (mod_list, history) = wait_for_history(backend);
+
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].entries[0].version, "1.0.0");
+    assert_eq!(mod_list[0].data.state, FileState::Pinned);
+    assert!(!backend.mods_dir.join("example-1.1.0.jar").exists());
+    assert_eq!(
+        fs::read(backend.mods_dir.join("example-1.0.0.jar")).unwrap(),
+        update_setup.old_jar
+    );
+
+    // The version that was rolled back from takes the place of the restored one, on disk too
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].hashes.sha1, new_sha1);
+    assert_eq!(stored_jars(), [format!("{}.jar", new_sha1)]);
+
+    let saved: serde_json::Value = serde_json::from_slice(
+        &fs::read(TestBackend::config_dir().join("versions/versions.mCubed.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(saved["storage"].as_array().unwrap().len(), 1);
+    assert_eq!(saved["storage"][0]["hashes"]["sha1"], new_sha1.as_str());
+}
+
+#[test]
+fn keeps_only_the_configured_amount_of_versions() {
+    let update_setup = ExampleUpdate::setup("rollback-prune", "release");
+    let backend = &update_setup.backend;
+    SettingsBuilder::from_current()
+        .max_stored_versions(1)
+        .apply();
+
+    update(backend);
+
+    offer_a_third_version(&update_setup);
+    let middle_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.new_jar));
+
+    let (mod_list, history) = update(backend);
+
+    assert_eq!(mod_list[0].entries[0].version, "1.2.0");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].version, "1.1.0");
+    assert_eq!(stored_jars(), [format!("{}.jar", middle_sha1)]);
+}
+
+#[test]
+fn leaves_the_store_alone_when_the_history_cannot_be_saved() {
+    let update_setup = ExampleUpdate::setup("rollback-unsaved", "release");
+    let backend = &update_setup.backend;
+    let old_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.old_jar));
+    SettingsBuilder::from_current()
+        .max_stored_versions(1)
+        .apply();
+
+    update(backend);
+    offer_a_third_version(&update_setup);
+
+    // Nothing can be written where the new history would be staged
+    let versions_dir = TestBackend::config_dir().join("versions");
+    fs::create_dir_all(versions_dir.join("versions.mCubed.json.tmp/taken")).unwrap();
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    let error = backend.wait_for_error();
+    let (mod_list, history) = wait_for_history(backend);
+
+    assert_eq!(
+        error.message,
+        "Could not keep the previous version of the mod"
+    );
+    assert_eq!(mod_list[0].entries[0].version, "1.2.0");
+
+    // The version that would have been pruned is still there, as the saved history points to it
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].hashes.sha1, old_sha1);
+    assert_eq!(stored_jars(), [format!("{}.jar", old_sha1)]);
+
+    let saved: serde_json::Value =
+        serde_json::from_slice(&fs::read(versions_dir.join("versions.mCubed.json")).unwrap())
+            .unwrap();
+    assert_eq!(saved["storage"][0]["hashes"]["sha1"], old_sha1.as_str());
+}
+
+#[test]
+fn keeps_the_stored_version_when_the_rollback_fails() {
+    let update_setup = ExampleUpdate::setup("rollback-failed", "release");
+    let backend = &update_setup.backend;
+    let old_sha1 = hex::encode(sha1::Sha1::digest(&update_setup.old_jar));
+
+    let (mod_list, _) = update(backend);
+
+    // A file in the way of the restored one can not be overwritten
+    backend.install("example-1.0.0.jar", b"something else");
+    backend.send(ToBackend::RollbackMod {
+        mod_file: Box::new(mod_list[0].clone()),
+        sha1: old_sha1.clone(),
+    });
+    backend.wait_for_error();
+
+    assert_eq!(stored_jars(), [format!("{}.jar", old_sha1)]);
+    assert!(backend.mods_dir.join("example-1.1.0.jar").exists());
+
+    let saved: serde_json::Value = serde_json::from_slice(
+        &fs::read(TestBackend::config_dir().join("versions/versions.mCubed.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(saved["storage"][0]["hashes"]["sha1"], old_sha1.as_str());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use back::{
+    messages::{ModSide, SearchQuery, ToBackend, ToFrontend},
+    mod_file::ModLoader,
+    settings::CONF,
+};
+use common::{icon_png, MockServer, TestBackend};
+
+#[test]
+fn searches_with_facets() {
+    let server = MockServer::start();
+    server.mock("GET", "/search", server.fixture("search.json", &[]));
+    server.mock("GET", "/data/icon.png", icon_png());
+
+    let backend = TestBackend::start("search", &server);
+    backend.send(ToBackend::SearchMods {
+        query: SearchQuery {
+            text: "example".to_string(),
+            game_version: Some("1.18.2".to_string()),
+            modloader: ModLoader::Fabric,
+            categories: vec!["utility".to_string()],
+            side: Some(ModSide::Client),
+            page: 1,
+        },
+    });
+
+    let page = backend.wait_for(|message| match message {
+        ToFrontend::SearchResults { page } => Some(page),
+        _ => None,
+    });
+
+    assert_eq!(page.page, 1);
+    assert_eq!(page.total_pages, 3);
+    assert_eq!(page.hits.len(), 1);
+
+    let hit = &page.hits[0];
+    assert_eq!(hit.project_id, "AABBCCDD");
+    assert_eq!(hit.downloads, 1234);
+    assert_eq!(hit.categories, vec!["utility"]);
+
+    let icon_size = CONF.lock().icon_resize_size as usize;
+    assert_eq!(hit.icon.as_ref().unwrap().len(), icon_size * icon_size * 4);
+
+    let query = &server.queries("/search")[0];
+    assert_eq!(query["query"], "example");
+    assert_eq!(query["offset"], "20");
+    assert_eq!(query["limit"], "20");
+
+    let facets: Vec<Vec<String>> = serde_json::from_str(&query["facets"]).unwrap();
+    assert_eq!(
+        facets,
+        vec![
+            vec!["project_type:mod"],
+            vec!["categories:fabric"],
+            vec!["versions:1.18.2"],
+            vec!["categories:utility"],
+            vec!["client_side:required", "client_side:optional"],
+        ]
+    );
+}
+
+#[test]
+fn lists_the_categories_of_mods() {
+    let server = MockServer::start();
+    server.mock(
+        "GET",
+        "/tag/category",
+        server.fixture("categories.json", &[]),
+    );
+
+    let backend = TestBackend::start("search-categories", &server);
+    backend.send(ToBackend::GetSearchCategories);
+
+    let categories = backend.wait_for(|message| match message {
+        ToFrontend::SearchCategories { categories } => Some(categories),
+        _ => None,
+    });
+
+    assert_eq!(categories, vec!["utility", "worldgen"]);
+}
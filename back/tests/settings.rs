@@ -0,0 +1,122 @@
+mod common;
+
+use std::fs;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    settings::{SettingsBuilder, CONF},
+};
+use common::{fabric_jar, MockServer, TestBackend};
+
+fn wait_for_settings(backend: &TestBackend) -> SettingsBuilder {
+    backend.wait_for(|message| match message {
+        ToFrontend::SettingsChanged { settings } => Some(*settings),
+        _ => None,
+    })
+}
+
+#[test]
+fn rejects_a_missing_mods_folder() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("missing-folder", &server);
+
+    let settings = SettingsBuilder::from_current()
+        .mod_folder_path(backend.mods_dir.join("does-not-exist"))
+        .max_stored_versions(7);
+    backend.send(ToBackend::UpdateSettings {
+        settings: Box::new(settings),
+    });
+
+    let error = backend.wait_for_error();
+    assert!(error.error.to_string().contains("does not exist"));
+
+    // The rejected settings are replaced by the ones still in use
+    let settings = wait_for_settings(&backend);
+    assert_eq!(settings.mod_folder_path, backend.mods_dir);
+    assert_ne!(CONF.lock().max_stored_versions, 7);
+}
+
+#[test]
+fn rejects_retry_settings_outside_of_their_range() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("retry-range", &server);
+
+    for settings in [
+        SettingsBuilder::from_current().retry_base_delay_ms(u64::MAX),
+        SettingsBuilder::from_current().max_request_retries(u32::MAX),
+    ] {
+        backend.send(ToBackend::UpdateSettings {
+            settings: Box::new(settings),
+        });
+
+        let error = backend.wait_for_error();
+        assert!(error.error.to_string().contains("retr"), "{}", error.error);
+    }
+}
+
+#[test]
+fn reloads_the_list_when_the_mods_folder_changes() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("change-folder", &server);
+
+    let other_dir = backend.mods_dir.with_file_name("other-mods");
+    fs::create_dir_all(&other_dir).unwrap();
+    fs::write(
+        other_dir.join("example-1.0.0.jar"),
+        fabric_jar("example", "1.0.0"),
+    )
+    .unwrap();
+
+    let settings = SettingsBuilder::from_current().mod_folder_path(other_dir.clone());
+    backend.send(ToBackend::UpdateSettings {
+        settings: Box::new(settings),
+    });
+
+    let settings = wait_for_settings(&backend);
+    assert_eq!(settings.mod_folder_path, other_dir);
+
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].entries[0].id, "example");
+
+    // The change is saved right away
+    let saved: serde_json::Value =
+        serde_json::from_slice(&fs::read(TestBackend::config_dir().join("settings.json")).unwrap())
+            .unwrap();
+    assert_eq!(saved["mod_folder_path"], other_dir.to_str().unwrap());
+}
+
+#[test]
+fn only_saves_the_settings_when_they_change() {
+    let server = MockServer::start();
+    let backend = TestBackend::start("unchanged-settings", &server);
+    let settings_path = TestBackend::config_dir().join("settings.json");
+    let icon_resize_size = CONF.lock().icon_resize_size;
+
+    // Written when the settings were first loaded, any later write brings it back
+    let _ = fs::remove_file(&settings_path);
+
+    // Starting up with the icon size already in use changes nothing
+    backend.send(ToBackend::Startup { icon_resize_size });
+    wait_for_settings(&backend);
+
+    backend.send(ToBackend::UpdateSettings {
+        settings: Box::new(SettingsBuilder::from_current()),
+    });
+    wait_for_settings(&backend);
+
+    assert!(!settings_path.exists());
+
+    backend.send(ToBackend::Startup {
+        icon_resize_size: icon_resize_size / 2,
+    });
+    let settings = wait_for_settings(&backend);
+    assert_eq!(settings.icon_resize_size, icon_resize_size / 2);
+
+    let saved: serde_json::Value =
+        serde_json::from_slice(&fs::read(&settings_path).unwrap()).unwrap();
+    assert_eq!(saved["icon_resize_size"], icon_resize_size / 2);
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use back::{
+    messages::{BackendError, ToBackend, ToFrontend},
+    versions::StoredVersion,
+};
+use common::{ExampleUpdate, MockServer, TestBackend};
+
+const DAMAGED: &str = r#"{"storage": [{"key": "#;
+
+fn versions_path() -> PathBuf {
+    TestBackend::config_dir().join("versions/versions.mCubed.json")
+}
+
+/// Start up, returning the warning about damaged data along with the version history that was loaded
+fn start_up(backend: &TestBackend) -> (BackendError, Vec<StoredVersion>) {
+    backend.send(ToBackend::Startup {
+        icon_resize_size: 128,
+    });
+
+    let warning = backend.wait_for(|message| match message {
+        ToFrontend::BackendWarning { warning } => Some(warning),
+        _ => None,
+    });
+    let history = backend.wait_for(|message| match message {
+        ToFrontend::UpdateVersionHistory { history } => Some(history),
+        _ => None,
+    });
+
+    (warning, history)
+}
+
+#[test]
+fn restores_the_version_history_from_its_backup() {
+    let update = ExampleUpdate::setup("storage-backup", "release");
+    let backend = &update.backend;
+    update.server.mock_version_manifest();
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateVersionHistory { history } if !history.is_empty() => Some(()),
+        _ => None,
+    });
+
+    // A write that was cut short, after the last good contents were backed up
+    let path = versions_path();
+    fs::copy(&path, path.with_extension("json.bak")).unwrap();
+    fs::write(&path, DAMAGED).unwrap();
+
+    let (warning, history) = start_up(backend);
+
+    assert!(warning
+        .error
+        .to_string()
+        .contains("restored from its backup"));
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].version, "1.0.0");
+    assert_eq!(
+        fs::read_to_string(path.with_extension("json.damaged")).unwrap(),
+        DAMAGED
+    );
+}
+
+#[test]
+fn sets_aside_a_damaged_file_without_a_backup() {
+    let server = MockServer::start();
+    server.mock_version_manifest();
+    let backend = TestBackend::start("storage-damaged", &server);
+
+    let path = versions_path();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, DAMAGED).unwrap();
+
+    let (warning, history) = start_up(&backend);
+
+    assert!(warning.error.to_string().contains("no backup"));
+    assert!(history.is_empty());
+    assert_eq!(
+        fs::read_to_string(path.with_extension("json.damaged")).unwrap(),
+        DAMAGED
+    );
+
+    // A fresh history takes its place, without the damaged contents being kept as a backup
+    let saved: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    assert_eq!(saved["storage"], serde_json::json!([]));
+    assert!(!path.with_extension("json.bak").exists());
+}
+
+#[test]
+fn restores_the_backup_when_the_damaged_file_cannot_be_set_aside() {
+    let update = ExampleUpdate::setup("storage-stuck", "release");
+    let backend = &update.backend;
+    update.server.mock_version_manifest();
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateVersionHistory { history } if !history.is_empty() => Some(()),
+        _ => None,
+    });
+
+    let path = versions_path();
+    fs::copy(&path, path.with_extension("json.bak")).unwrap();
+    fs::write(&path, DAMAGED).unwrap();
+    // Nothing can be moved to where the damaged file would go
+    let damaged_path = path.with_extension("json.damaged");
+    fs::create_dir_all(damaged_path.join("taken")).unwrap();
+
+    let (warning, history) = start_up(backend);
+
+    assert!(warning
+        .error
+        .to_string()
+        .contains("restored from its backup"));
+    assert_eq!(history.len(), 1);
+    assert!(damaged_path.is_dir());
+}
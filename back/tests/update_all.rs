@@ -0,0 +1,185 @@
+mod common;
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use back::{
+    messages::{PlannedUpdate, ToBackend, ToFrontend, UpdateSummary},
+    mod_file::{ModFile, Pin},
+    settings::SettingsBuilder,
+};
+use common::{fabric_jar, MockResponse, MockServer, TestBackend};
+use sha1::Digest;
+
+/// How long every download takes
+const DOWNLOAD_DELAY: Duration = Duration::from_millis(400);
+
+/// Serve a version of the mod `name`, taking a while to download it
+fn host(server: &MockServer, name: &str, version: &str) -> (Vec<u8>, String) {
+    let jar = fabric_jar(name, version);
+    let fixture = server.host_mod(name, version, &jar);
+    server.mock_sequence(
+        "GET",
+        &format!("/data/{}-{}.jar", name, version),
+        vec![MockResponse::new(200, jar.clone()).delay(DOWNLOAD_DELAY)],
+    );
+
+    (jar, fixture)
+}
+
+fn sha1(bytes: &[u8]) -> String {
+    hex::encode(sha1::Sha1::digest(bytes))
+}
+
+/// Install version 1.0.0 of every mod in `names`, with 1.1.0 as their update
+fn setup(name: &str, names: &[&str]) -> (MockServer, TestBackend) {
+    let server = MockServer::start();
+    let backend = TestBackend::start(name, &server);
+
+    let mut versions = Vec::new();
+    let mut updates = Vec::new();
+
+    for name in names {
+        let (old_jar, old_version) = host(&server, name, "1.0.0");
+        let (_, new_version) = host(&server, name, "1.1.0");
+
+        backend.install(&format!("{}-1.0.0.jar", name), &old_jar);
+        versions.push(format!(r#""{}": {}"#, sha1(&old_jar), old_version));
+        updates.push(format!(r#""{}": {}"#, sha1(&old_jar), new_version));
+    }
+
+    server.mock(
+        "POST",
+        "/version_files",
+        format!("{{{}}}", versions.join(", ")),
+    );
+    server.mock(
+        "POST",
+        "/version_files/update",
+        format!("{{{}}}", updates.join(", ")),
+    );
+
+    (server, backend)
+}
+
+fn file_name(mod_file: &ModFile) -> String {
+    mod_file
+        .path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string()
+}
+
+fn plan(backend: &TestBackend) -> Vec<PlannedUpdate> {
+    backend.send(ToBackend::PlanUpdateAll);
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdatePlan { plan } => Some(plan),
+        _ => None,
+    })
+}
+
+fn update_all(backend: &TestBackend, plan: Vec<PlannedUpdate>) -> UpdateSummary {
+    backend.send(ToBackend::UpdateAll { plan });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateSummary { summary } => Some(summary),
+        _ => None,
+    })
+}
+
+#[test]
+fn plans_the_updates_of_every_outdated_mod() {
+    let (_server, backend) = setup("update-all-plan", &["alpha", "beta", "gamma"]);
+
+    let mod_list = backend.check_for_updates();
+    let gamma = mod_list
+        .iter()
+        .find(|mod_file| file_name(mod_file) == "gamma-1.0.0.jar")
+        .unwrap();
+    backend.send(ToBackend::SetPin {
+        sha1: gamma.hashes.sha1.clone(),
+        pin: Some(Pin::CurrentFile),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { .. } => Some(()),
+        _ => None,
+    });
+
+    let plan = plan(&backend);
+
+    let mut planned: Vec<String> = plan
+        .iter()
+        .map(|planned| file_name(&planned.mod_file))
+        .collect();
+    planned.sort();
+    assert_eq!(planned, ["alpha-1.0.0.jar", "beta-1.0.0.jar"]);
+    assert!(plan
+        .iter()
+        .all(|planned| planned.old_version == "1.0.0" && planned.new_version == "1.1.0"));
+}
+
+#[test]
+fn downloads_no_more_than_the_configured_amount_at_once() {
+    let (_server, backend) = setup("update-all-serial", &["alpha", "beta", "gamma"]);
+    SettingsBuilder::from_current()
+        .max_concurrent_downloads(1)
+        .apply();
+
+    backend.check_for_updates();
+    let plan = plan(&backend);
+
+    let started = Instant::now();
+    let summary = update_all(&backend, plan);
+
+    assert!(started.elapsed() >= DOWNLOAD_DELAY * 3);
+    assert_eq!(summary.updated.len(), 3);
+    assert!(summary.failed.is_empty());
+}
+
+#[test]
+fn downloads_the_updates_side_by_side() {
+    let (_server, backend) = setup("update-all-parallel", &["alpha", "beta", "gamma"]);
+    SettingsBuilder::from_current()
+        .max_concurrent_downloads(3)
+        .apply();
+
+    backend.check_for_updates();
+    let plan = plan(&backend);
+
+    let started = Instant::now();
+    let summary = update_all(&backend, plan);
+
+    assert!(started.elapsed() < DOWNLOAD_DELAY * 3);
+    assert_eq!(summary.updated.len(), 3);
+
+    let mut names: Vec<String> = fs::read_dir(&backend.mods_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        ["alpha-1.1.0.jar", "beta-1.1.0.jar", "gamma-1.1.0.jar"]
+    );
+}
+
+#[test]
+fn applies_the_other_updates_when_one_fails() {
+    let (server, backend) = setup("update-all-failed", &["alpha", "beta"]);
+    server.mock_sequence(
+        "GET",
+        "/data/beta-1.1.0.jar",
+        vec![MockResponse::new(404, "{}")],
+    );
+
+    backend.check_for_updates();
+    let plan = plan(&backend);
+    let summary = update_all(&backend, plan);
+
+    assert_eq!(summary.updated.len(), 1);
+    assert_eq!(summary.failed.len(), 1);
+    assert!(backend.mods_dir.join("alpha-1.1.0.jar").exists());
+    assert!(backend.mods_dir.join("beta-1.0.0.jar").exists());
+}
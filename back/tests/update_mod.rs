@@ -0,0 +1,49 @@
+mod common;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::FileState,
+};
+use common::ExampleUpdate;
+
+#[test]
+fn finds_updates() {
+    let ExampleUpdate { backend, .. } = ExampleUpdate::setup("check", "release");
+
+    let mod_list = backend.check_for_updates();
+
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].data.state, FileState::Outdated);
+
+    let modrinth_data = mod_list[0].data.sources.modrinth.as_ref().unwrap();
+    assert_eq!(modrinth_data.id, "AABBCCDD");
+    assert_eq!(
+        modrinth_data.latest_version_number.as_deref(),
+        Some("1.1.0")
+    );
+}
+
+#[test]
+fn updates_a_mod() {
+    let ExampleUpdate {
+        backend, new_jar, ..
+    } = ExampleUpdate::setup("update", "release");
+
+    let mod_list = backend.check_for_updates();
+    backend.send(ToBackend::UpdateMod {
+        mod_file: Box::new(mod_list[0].clone()),
+    });
+
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].entries[0].version, "1.1.0");
+    assert!(!backend.mods_dir.join("example-1.0.0.jar").exists());
+    assert_eq!(
+        std::fs::read(backend.mods_dir.join("example-1.1.0.jar")).unwrap(),
+        new_jar
+    );
+}
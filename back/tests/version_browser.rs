@@ -0,0 +1,82 @@
+mod common;
+
+use std::fs;
+
+use back::{
+    messages::{ToBackend, ToFrontend},
+    mod_file::ModFile,
+    ModrinthVersion,
+};
+use common::{fabric_jar, ExampleUpdate, TestBackend};
+
+const VERSION_LIST_PATH: &str = "/project/AABBCCDD/version";
+
+fn list_versions(backend: &TestBackend, mod_file: &ModFile) -> Vec<ModrinthVersion> {
+    backend.send(ToBackend::ListVersions {
+        mod_file: Box::new(mod_file.clone()),
+    });
+    backend.wait_for(|message| match message {
+        ToFrontend::VersionList { versions, .. } => Some(versions),
+        _ => None,
+    })
+}
+
+#[test]
+fn lists_every_version_of_a_mod() {
+    let update = ExampleUpdate::setup("browser-list", "beta");
+
+    let mod_list = update.backend.check_for_updates();
+    let versions = list_versions(&update.backend, &mod_list[0]);
+
+    // Versions outside of the release channel can still be picked by hand
+    let mut numbers: Vec<&str> = versions
+        .iter()
+        .map(|version| version.version_number.as_str())
+        .collect();
+    numbers.sort_unstable();
+    assert_eq!(numbers, ["1.0.0", "1.1.0"]);
+}
+
+#[test]
+fn installs_a_version_older_than_the_installed_one() {
+    let update = ExampleUpdate::setup("browser-install", "release");
+    let server = &update.server;
+    let backend = &update.backend;
+
+    let older_jar = fabric_jar("example", "0.9.0");
+    server.mock(
+        "GET",
+        VERSION_LIST_PATH,
+        format!(
+            "[{}, {}, {}]",
+            server.host_other_version("0.9.0", "2021-12-01", &older_jar),
+            server.host_jar("1.0.0", &update.old_jar),
+            server.host_jar("1.1.0", &update.new_jar)
+        ),
+    );
+
+    let mod_list = backend.check_for_updates();
+    let versions = list_versions(backend, &mod_list[0]);
+    let older = versions
+        .iter()
+        .find(|version| version.version_number == "0.9.0")
+        .unwrap();
+
+    backend.send(ToBackend::InstallVersion {
+        mod_file: Box::new(mod_list[0].clone()),
+        version_id: older.id.clone(),
+        version_file: older.files[0].clone(),
+    });
+    let mod_list = backend.wait_for(|message| match message {
+        ToFrontend::UpdateModList { mod_list } => Some(mod_list),
+        _ => None,
+    });
+
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].entries[0].version, "0.9.0");
+    assert!(!backend.mods_dir.join("example-1.0.0.jar").exists());
+    assert_eq!(
+        fs::read(backend.mods_dir.join("example-0.9.0.jar")).unwrap(),
+        older_jar
+    );
+}
@@ -111,6 +111,7 @@ pub struct Colors {
     pub light_gray: Color32,
     pub lighter_gray: Color32,
     pub error_message: Color32,
+    pub warning_message: Color32,
     pub mod_card: ModCardTheme,
 }
 
@@ -124,6 +125,7 @@ impl Default for Colors {
             light_gray: Color32::from_rgb(85, 85, 85),
             lighter_gray: Color32::from_rgb(120, 120, 120),
             error_message: Color32::from_rgb(211, 80, 80),
+            warning_message: Color32::from_rgb(196, 138, 48),
             mod_card: ModCardTheme::default(),
         }
     }
@@ -136,6 +138,7 @@ pub struct ModCardTheme {
     pub source: SourceTheme,
     pub modloader: ModloaderTheme,
     pub mod_status_icon_background: Color32,
+    pub pinned_status: Color32,
 }
 
 impl Default for ModCardTheme {
@@ -147,6 +150,7 @@ impl Default for ModCardTheme {
             source: SourceTheme::default(),
             modloader: ModloaderTheme::default(),
             mod_status_icon_background: Color32::from_gray(32),
+            pinned_status: Color32::from_rgb(90, 176, 255),
         }
     }
 }
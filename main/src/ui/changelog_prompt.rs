@@ -0,0 +1,156 @@
+use back::{
+    messages::{BackendRequest, Changelog, RequestId, ToBackend},
+    mod_file::ModFile,
+};
+use crossbeam_channel::Sender;
+use eframe::{
+    egui::{Context, Label, ScrollArea, Spinner, Widget},
+    emath::Vec2,
+};
+
+use super::{
+    pending_request::PendingRequest, text_utils, widgets::screen_prompt::ScreenPrompt, THEME,
+};
+
+/// Shows what an update contains before confirming it
+#[derive(Default)]
+pub struct ChangelogPrompt {
+    mod_file: Option<Box<ModFile>>,
+    changelogs: Vec<Changelog>,
+}
+
+impl ChangelogPrompt {
+    pub const PROMPT_NAME: &'static str = "changelog_prompt";
+
+    /// Open the prompt and request the changelogs of the update from the backend
+    pub fn open(ctx: &Context, mod_file: &ModFile, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            PendingRequest::send(
+                ctx,
+                Self::PROMPT_NAME,
+                tx,
+                ToBackend::GetChangelogs {
+                    mod_file: Box::new(mod_file.clone()),
+                },
+            );
+
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, true);
+        }
+    }
+
+    pub fn set_changelogs(
+        &mut self,
+        ctx: &Context,
+        id: RequestId,
+        mod_file: Box<ModFile>,
+        changelogs: Vec<Changelog>,
+    ) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.mod_file = Some(mod_file);
+            self.changelogs = changelogs;
+        }
+    }
+
+    /// Close the prompt if the request it is waiting on did not go through
+    pub fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, false);
+
+            *self = Self::default();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        let mut close = false;
+
+        ScreenPrompt::new(Self::PROMPT_NAME).show(ctx, |ui, state| {
+            let size = ui.available_size() - Vec2::splat(50.0);
+            ui.set_max_size(size);
+
+            match &self.mod_file {
+                Some(mod_file) => {
+                    let name = mod_file
+                        .entries
+                        .first()
+                        .map_or("Unknown", |entry| entry.display_name.as_str());
+                    ui.heading(format!("Updating {}", name));
+
+                    ScrollArea::vertical()
+                        .max_height(size.y - 50.0)
+                        .show(ui, |ui| {
+                            ui.set_width(size.x);
+                            ui.spacing_mut().item_spacing.y = THEME.spacing.small;
+
+                            if self.changelogs.is_empty() {
+                                ui.label("No changelogs were found for this update");
+                            }
+
+                            for changelog in &self.changelogs {
+                                ui.label(
+                                    text_utils::mod_card_data_header(format!(
+                                        "{} ({:?})",
+                                        changelog.version_number, changelog.version_type
+                                    ))
+                                    .color(THEME.colors.lighter_gray),
+                                );
+
+                                ui.add(
+                                    Label::new(text_utils::mod_card_data_text(
+                                        changelog.changelog.as_deref().unwrap_or("None"),
+                                    ))
+                                    .wrap(true),
+                                );
+
+                                ui.add_space(THEME.spacing.medium);
+                            }
+                        });
+
+                    ui.add_space(THEME.spacing.medium);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Update").clicked() {
+                            if let Some(tx) = &front_tx {
+                                tx.send(
+                                    ToBackend::UpdateMod {
+                                        mod_file: mod_file.clone(),
+                                    }
+                                    .into(),
+                                )
+                                .unwrap();
+                            }
+
+                            close = true;
+                        }
+
+                        if ui.button("Cancel").clicked() {
+                            close = true;
+                        }
+                    });
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        Spinner::new().size(14.0).ui(ui);
+                        ui.label("Fetching changelogs");
+                    });
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                }
+            }
+
+            if close {
+                state.shown(false);
+            }
+        });
+
+        if close {
+            PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+
+            *self = Self::default();
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use back::{
+    messages::{BackendRequest, RequestId, ToBackend},
+    mod_file::{
+        details::{EntryDetails, FileDetails},
+        ModFile,
+    },
+};
+use crossbeam_channel::Sender;
+use eframe::{
+    egui::{CollapsingHeader, Context, Label, RichText, ScrollArea, Spinner, Ui, Widget},
+    emath::Vec2,
+};
+
+use super::{
+    pending_request::PendingRequest, text_utils, widgets::screen_prompt::ScreenPrompt, THEME,
+};
+
+/// Shows everything the manifests of a file declare, along with the file itself
+#[derive(Default)]
+pub struct DetailsPrompt {
+    details: Option<Box<FileDetails>>,
+}
+
+impl DetailsPrompt {
+    pub const PROMPT_NAME: &'static str = "details_prompt";
+
+    /// Open the prompt and request the details of the file from the backend
+    pub fn open(ctx: &Context, mod_file: &ModFile, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            PendingRequest::send(
+                ctx,
+                Self::PROMPT_NAME,
+                tx,
+                ToBackend::GetModDetails {
+                    mod_file: Box::new(mod_file.clone()),
+                },
+            );
+
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, true);
+        }
+    }
+
+    pub fn set_details(&mut self, ctx: &Context, id: RequestId, details: Box<FileDetails>) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.details = Some(details);
+        }
+    }
+
+    /// Close the prompt if the request it is waiting on did not go through
+    pub fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, false);
+
+            *self = Self::default();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        let mut close = false;
+
+        ScreenPrompt::new(Self::PROMPT_NAME).show(ctx, |ui, state| {
+            let size = ui.available_size() - Vec2::splat(50.0);
+            ui.set_max_size(size);
+
+            match &self.details {
+                Some(details) => {
+                    let file_name = details
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    ui.heading(file_name);
+
+                    ScrollArea::vertical()
+                        .max_height(size.y - 50.0)
+                        .show(ui, |ui| {
+                            ui.set_width(size.x);
+                            ui.spacing_mut().item_spacing.y = THEME.spacing.small;
+
+                            Self::show_file(details, ui);
+
+                            for entry in &details.entries {
+                                ui.add_space(THEME.spacing.large);
+
+                                Self::show_entry(entry, ui);
+                            }
+
+                            ui.add_space(THEME.spacing.large);
+
+                            for (path, text) in &details.manifests {
+                                CollapsingHeader::new(
+                                    text_utils::mod_card_data_header(path)
+                                        .color(THEME.colors.lighter_gray),
+                                )
+                                .id_source(("manifest", path))
+                                .show(ui, |ui| {
+                                    ui.add(Label::new(RichText::new(text).monospace()).wrap(true));
+                                });
+                            }
+                        });
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        Spinner::new().size(14.0).ui(ui);
+                        ui.label("Reading the file");
+                    });
+                }
+            }
+
+            ui.add_space(THEME.spacing.medium);
+
+            if ui.button("Close").clicked() {
+                close = true;
+                state.shown(false);
+            }
+        });
+
+        if close {
+            PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+
+            *self = Self::default();
+        }
+    }
+
+    fn show_file(details: &FileDetails, ui: &mut Ui) {
+        info_text("Path:", details.path.display().to_string(), ui);
+        info_text(
+            "Size:",
+            format!(
+                "{:.2} MB ({} bytes)",
+                details.size as f64 / 1_000_000.0,
+                details.size
+            ),
+            ui,
+        );
+
+        for (name, hash) in [
+            ("SHA-1:", &details.hashes.sha1),
+            ("SHA-512:", &details.hashes.sha512),
+        ] {
+            ui.horizontal(|ui| {
+                ui.label(text_utils::mod_card_data_header(name).color(THEME.colors.lighter_gray));
+
+                if ui
+                    .button(text_utils::update_button_text("Copy"))
+                    .on_hover_text(hash)
+                    .clicked()
+                {
+                    ui.output().copied_text = hash.clone();
+                }
+
+                ui.add(Label::new(text_utils::mod_card_data_text(hash)).wrap(true));
+            });
+        }
+
+        if let Some(url) = &details.modrinth_url {
+            ui.hyperlink_to(text_utils::mod_card_data_text("Modrinth page"), url);
+        }
+    }
+
+    fn show_entry(entry: &EntryDetails, ui: &mut Ui) {
+        ui.label(
+            RichText::new(format!("{} ({})", entry.display_name, entry.id))
+                .strong()
+                .size(16.0),
+        );
+
+        if let Some(license) = &entry.license {
+            info_text("License:", license, ui);
+        }
+
+        if !entry.contributors.is_empty() {
+            info_text("Contributors:", entry.contributors.join(", "), ui);
+        }
+
+        if let Some(credits) = &entry.credits {
+            info_text("Credits:", credits, ui);
+        }
+
+        for (name, value) in &entry.contact {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(
+                    text_utils::mod_card_data_header(format!("{}:", name))
+                        .color(THEME.colors.lighter_gray),
+                );
+
+                if value.starts_with("http://") || value.starts_with("https://") {
+                    ui.hyperlink_to(text_utils::mod_card_data_text(value), value);
+                } else {
+                    ui.label(text_utils::mod_card_data_text(value));
+                }
+            });
+        }
+
+        list(
+            "Dependencies:",
+            entry.dependencies.iter().map(|dependency| {
+                format!(
+                    "{}: {} {}",
+                    dependency.kind, dependency.id, dependency.versions
+                )
+            }),
+            ui,
+        );
+
+        list(
+            "Entrypoints:",
+            entry
+                .entrypoints
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value)),
+            ui,
+        );
+
+        list("Mixins:", entry.mixins.iter().cloned(), ui);
+    }
+}
+
+fn info_text(header: impl Into<String>, body: impl Into<String>, ui: &mut Ui) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(text_utils::mod_card_data_header(header).color(THEME.colors.lighter_gray));
+
+        ui.label(text_utils::mod_card_data_text(body));
+    });
+}
+
+/// A header followed by one line per item, left out entirely if there are none
+fn list(header: &str, items: impl Iterator<Item = String>, ui: &mut Ui) {
+    let items: Vec<String> = items.collect();
+
+    if items.is_empty() {
+        return;
+    }
+
+    ui.label(text_utils::mod_card_data_header(header).color(THEME.colors.lighter_gray));
+
+    for item in items {
+        ui.add(Label::new(text_utils::mod_card_data_text(format!("  {}", item))).wrap(true));
+    }
+}
@@ -0,0 +1,179 @@
+use std::path::Path;
+
+use back::{
+    instances::Instance,
+    messages::{BackendRequest, RequestId, ToBackend},
+};
+use crossbeam_channel::Sender;
+use eframe::{
+    egui::{Button, Context, Label, Layout, RichText, ScrollArea, Spinner, Ui, Widget},
+    emath::{Align, Vec2},
+};
+
+use super::{
+    pending_request::PendingRequest, text_utils, widgets::screen_prompt::ScreenPrompt, THEME,
+};
+
+/// Lists the game folders of the installed launchers, so that one of them can be adopted as a profile
+#[derive(Default)]
+pub struct InstancePicker {
+    /// [None] while the instances are being looked for
+    instances: Option<Vec<Instance>>,
+}
+
+impl InstancePicker {
+    pub const PROMPT_NAME: &'static str = "instance_picker";
+
+    /// Open the prompt and look for instances, this can be done from anywhere as the prompt keeps no state until the results arrive
+    pub fn open(ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            PendingRequest::send(ctx, Self::PROMPT_NAME, tx, ToBackend::DetectInstances);
+
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, true);
+        }
+    }
+
+    pub fn set_instances(&mut self, ctx: &Context, id: RequestId, instances: Vec<Instance>) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.instances = Some(instances);
+        }
+    }
+
+    /// Close the prompt if the request it is waiting on did not go through
+    pub fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, false);
+
+            *self = Self::default();
+        }
+    }
+
+    /// Show the prompt, the backend takes care of switching to the instance that is adopted
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        front_tx: &Option<Sender<BackendRequest>>,
+        current_mods_dir: &Path,
+    ) {
+        let mut close = false;
+        let mut adopted = None;
+
+        ScreenPrompt::new(Self::PROMPT_NAME).show(ctx, |ui, state| {
+            let size = ui.available_size() - Vec2::splat(50.0);
+            ui.set_max_size(size);
+
+            ui.heading("Detected installations");
+
+            match &self.instances {
+                None => {
+                    ui.horizontal(|ui| {
+                        Spinner::new().size(14.0).ui(ui);
+                        ui.label("Looking for installations");
+                    });
+                }
+                Some(instances) if instances.is_empty() => {
+                    ui.label("No installations were found, the mods folder can still be chosen by hand in the settings");
+                }
+                Some(instances) => {
+                    ScrollArea::vertical()
+                        .max_height(size.y - 100.0)
+                        .show(ui, |ui| {
+                            ui.set_width(size.x);
+
+                            for instance in instances {
+                                let in_use = instance.mods_dir() == current_mods_dir;
+
+                                if Self::show_instance(instance, in_use, ui) {
+                                    adopted = Some(instance.clone());
+                                }
+
+                                ui.separator();
+                            }
+                        });
+                }
+            }
+
+            ui.add_space(THEME.spacing.medium);
+
+            ui.with_layout(Layout::bottom_up(Align::Center), |ui| {
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+
+            if close || adopted.is_some() {
+                state.shown(false);
+            }
+        });
+
+        if let Some(instance) = &adopted {
+            if let Some(tx) = &front_tx {
+                tx.send(
+                    ToBackend::AdoptInstance {
+                        instance: Box::new(instance.clone()),
+                    }
+                    .into(),
+                )
+                .unwrap();
+            }
+        }
+
+        if close || adopted.is_some() {
+            PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+
+            *self = Self::default();
+        }
+    }
+
+    /// Returns whether the instance was chosen to be adopted
+    fn show_instance(instance: &Instance, in_use: bool, ui: &mut Ui) -> bool {
+        let mut adopt = false;
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(&instance.name).strong());
+                    ui.label(RichText::new(instance.launcher.to_string()).color(THEME.colors.gray));
+                });
+
+                let mut details = instance
+                    .game_version
+                    .clone()
+                    .unwrap_or_else(|| "Unknown version".to_string());
+
+                if let Some(modloader) = instance.modloader {
+                    details.push_str(&format!(" | {:?}", modloader));
+
+                    if let Some(loader_version) = &instance.loader_version {
+                        details.push_str(&format!(" {}", loader_version));
+                    }
+                }
+
+                ui.label(text_utils::mod_card_data_text(details).color(THEME.colors.lighter_gray));
+                ui.add(
+                    Label::new(
+                        text_utils::mod_card_data_text(instance.mods_dir().display().to_string())
+                            .color(THEME.colors.gray),
+                    )
+                    .wrap(true),
+                );
+            });
+
+            ui.with_layout(Layout::right_to_left(), |ui| {
+                let text = if in_use { "In use" } else { "Use" };
+
+                if ui
+                    .add_enabled(!in_use, Button::new(text_utils::update_button_text(text)))
+                    .clicked()
+                {
+                    adopt = true;
+                }
+            });
+        });
+
+        adopt
+    }
+}
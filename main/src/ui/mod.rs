@@ -1,19 +1,29 @@
 use self::{
-    app_theme::AppTheme, image_utils::ImageTextures, mod_card::FileCard, settings::SettingsUi,
+    app_theme::AppTheme, changelog_prompt::ChangelogPrompt, details_prompt::DetailsPrompt,
+    image_utils::ImageTextures, instance_picker::InstancePicker, mod_card::FileCard,
+    mod_search::ModSearch, progress_panel::ProgressPanel, settings::SettingsUi,
+    update_all_prompt::UpdateAllPrompt, version_browser::VersionBrowser,
 };
 use back::{
-    messages::{BackendError, ToBackend, ToFrontend},
-    mod_file::ModLoader,
+    instances::InferredGame,
+    messages::{BackendError, BackendRequest, BackendResponse, RequestId, ToBackend, ToFrontend},
+    mod_file::{
+        duplicates::{DuplicateGroup, IdClaim},
+        ModLoader,
+    },
     settings::SettingsBuilder,
+    versions::StoredVersion,
     Back, GameVersion,
 };
 use crossbeam_channel::{Receiver, Sender};
 use eframe::{
     egui::{
         style::{DebugOptions, Margin},
-        Align, CentralPanel, ComboBox, Context, Frame, ImageButton, InnerResponse, Label, Layout,
-        RichText, ScrollArea, Sense, SidePanel, Spinner, Style, TextEdit, Vec2, Widget,
+        Align, Button, CentralPanel, CollapsingHeader, ComboBox, Context, Frame, ImageButton,
+        InnerResponse, Label, Layout, RichText, ScrollArea, Sense, SidePanel, Style, TextEdit, Ui,
+        Vec2,
     },
+    epaint::Color32,
     CreationContext,
 };
 
@@ -24,11 +34,19 @@ use parking_lot::{Mutex, Once};
 use std::thread;
 
 mod app_theme;
+mod changelog_prompt;
+mod details_prompt;
 mod image_utils;
+mod instance_picker;
 mod misc;
 mod mod_card;
+mod mod_search;
+mod pending_request;
+mod progress_panel;
 mod settings;
 mod text_utils;
+mod update_all_prompt;
+mod version_browser;
 mod widgets;
 
 static SET_LEFT_PANEL_BOTTOM_BUTTONS_WIDTH: Once = Once::new();
@@ -44,17 +62,37 @@ pub struct MCubedAppUI {
     // UI
     search_buf: String,
     add_mod_buf: String,
+    version_browser: VersionBrowser,
+    changelog_prompt: ChangelogPrompt,
+    details_prompt: DetailsPrompt,
+    update_all_prompt: UpdateAllPrompt,
+    mod_search: ModSearch,
+    instance_picker: InstancePicker,
+    /// Whether the mods that only exist for other mods to use are left out of the list
+    hide_libraries: bool,
 
     // Data
     mod_list: Vec<FileCard>,
+    /// The mods more than one file contains
+    duplicates: Vec<DuplicateGroup>,
+    version_history: Vec<StoredVersion>,
     game_version_list: Vec<GameVersion>,
+    /// Whether the game versions come from a cached manifest that could not be refreshed
+    game_version_list_stale: bool,
+    game_version_list_unavailable: bool,
     selected_version: Option<GameVersion>,
     selected_modloader: ModLoader,
+    /// The game the backend found the mods folder to be loaded by, selected by default
+    inferred_game: InferredGame,
+    /// The game version and loader the files were last checked against
+    compatibility_checked_for: Option<(String, ModLoader)>,
+    /// The settings in use, as last reported by the backend
+    settings: SettingsBuilder,
     backend_context: BackendContext,
 
     // Data transferring
-    front_tx: Option<Sender<ToBackend>>,
-    back_rx: Option<Receiver<ToFrontend>>,
+    front_tx: Option<Sender<BackendRequest>>,
+    back_rx: Option<Receiver<BackendResponse>>,
 
     // Misc sizes to combat immediate mode shenanigans
     left_panel_bottom_buttons_width: f32,
@@ -62,8 +100,9 @@ pub struct MCubedAppUI {
 
 #[derive(Default)]
 struct BackendContext {
-    checking_for_updates: bool,
+    progress_panel: ProgressPanel,
     backend_errors: Vec<BackendError>,
+    backend_warnings: Vec<BackendError>,
 }
 
 impl MCubedAppUI {
@@ -84,41 +123,108 @@ impl MCubedAppUI {
         new_app.front_tx = Some(front_tx);
         new_app.back_rx = Some(back_rx);
 
+        // The settings are filled in by the backend once it started
         if let Some(sender) = &new_app.front_tx {
-            sender.send(ToBackend::Startup).unwrap();
+            sender
+                .send(
+                    ToBackend::Startup {
+                        icon_resize_size: ICON_RESIZE_QUALITY,
+                    }
+                    .into(),
+                )
+                .unwrap();
         }
 
-        SettingsBuilder::from_current()
-            .icon_resize_size(ICON_RESIZE_QUALITY)
-            .apply();
-
         new_app
     }
 }
 
 impl eframe::App for MCubedAppUI {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
-        if let Some(rx) = &self.back_rx {
-            match rx.try_recv() {
-                Ok(message) => match message {
-                    ToFrontend::SetVersionMetadata { manifest } => {
-                        self.selected_version = Some(manifest.versions[0].clone());
+        // The receiver is cloned so that the handlers below are free to borrow the app
+        if let Some(rx) = self.back_rx.clone() {
+            // Progress updates can arrive in bursts, so handle everything that is pending at once
+            for BackendResponse { id, message } in rx.try_iter() {
+                match message {
+                    ToFrontend::SetVersionMetadata { manifest, stale } => {
+                        // Snapshots are rarely played with mods, so the latest release is a better default
+                        self.selected_version = manifest
+                            .versions
+                            .iter()
+                            .find(|version| version.id == manifest.latest.release)
+                            .or_else(|| manifest.versions.first())
+                            .cloned();
                         self.game_version_list = manifest.versions;
+                        self.game_version_list_stale = stale;
+                        self.game_version_list_unavailable = false;
+
+                        self.select_inferred_game();
+                    }
+                    ToFrontend::VersionMetadataUnavailable => {
+                        self.game_version_list_unavailable = true;
                     }
                     ToFrontend::UpdateModList { mod_list } => {
-                        self.backend_context.checking_for_updates = false;
                         self.mod_list = mod_list
                             .into_iter()
                             .map(|file| FileCard::new(file, ctx))
                             .collect();
                         ctx.request_repaint();
                     }
+                    ToFrontend::DuplicateMods { groups } => {
+                        self.duplicates = groups;
+                    }
+                    ToFrontend::UpdateVersionHistory { history } => {
+                        self.version_history = history;
+                    }
+                    ToFrontend::Changelogs {
+                        mod_file,
+                        changelogs,
+                    } => {
+                        self.changelog_prompt
+                            .set_changelogs(ctx, id, mod_file, changelogs);
+                    }
+                    ToFrontend::ModDetails { details } => {
+                        self.details_prompt.set_details(ctx, id, details);
+                    }
+                    ToFrontend::UpdatePlan { plan } => {
+                        self.update_all_prompt.set_plan(ctx, id, plan);
+                    }
+                    ToFrontend::UpdateSummary { summary } => {
+                        self.update_all_prompt.set_summary(ctx, id, summary);
+                    }
+                    ToFrontend::VersionList { mod_file, versions } => {
+                        self.version_browser
+                            .set_versions(ctx, id, mod_file, versions);
+                    }
+                    ToFrontend::SettingsChanged { settings } => {
+                        self.settings = *settings;
+                    }
+                    ToFrontend::SearchResults { page } => {
+                        self.mod_search.set_results(ctx, id, page);
+                    }
+                    ToFrontend::SearchCategories { categories } => {
+                        self.mod_search.set_categories(categories);
+                    }
+                    ToFrontend::InstancesDetected { instances } => {
+                        self.instance_picker.set_instances(ctx, id, instances);
+                    }
+                    ToFrontend::InferredGame { game } => {
+                        self.inferred_game = game;
+                        self.select_inferred_game();
+                    }
+                    ToFrontend::Progress { progress } => {
+                        self.backend_context.progress_panel.update(progress);
+                    }
+                    ToFrontend::Cancelled => {
+                        self.request_failed(ctx, id);
+                    }
                     ToFrontend::BackendError { error } => {
+                        self.request_failed(ctx, id);
                         self.backend_context.backend_errors.push(error);
                     }
-                },
-                Err(err) => {
-                    let _ = err;
+                    ToFrontend::BackendWarning { warning } => {
+                        self.backend_context.backend_warnings.push(warning);
+                    }
                 }
             }
         }
@@ -130,7 +236,7 @@ impl eframe::App for MCubedAppUI {
 
             let settings_res = ui.allocate_ui_at_rect(rect.0, |ui| {
                 ScrollArea::new([false, true]).show(ui, |ui| {
-                    SettingsUi::show(ui);
+                    SettingsUi::show(ui, &mut self.settings, &self.front_tx);
                 });
             });
 
@@ -145,29 +251,106 @@ impl eframe::App for MCubedAppUI {
             });
         });
 
+        self.version_browser.show(ctx, &self.front_tx);
+
+        self.changelog_prompt.show(ctx, &self.front_tx);
+
+        self.details_prompt.show(ctx, &self.front_tx);
+
+        self.update_all_prompt.show(ctx, &self.front_tx);
+
+        self.mod_search.show(
+            ctx,
+            &self.front_tx,
+            self.selected_version
+                .as_ref()
+                .map(|version| version.id.as_str()),
+            self.selected_modloader,
+        );
+
+        self.instance_picker
+            .show(ctx, &self.front_tx, &self.settings.mod_folder_path);
+
         self.render_side_panel(ctx);
 
+        self.check_compatibility();
+
         self.render_central_panel(ctx);
     }
 
     fn on_exit(&mut self, _gl: &eframe::glow::Context) {
         if let Some(tx) = &self.front_tx {
-            tx.send(ToBackend::UpdateBackendList {
-                mod_list: self
-                    .mod_list
-                    .iter()
-                    .map(FileCard::mod_file)
-                    .cloned()
-                    .collect(),
-            })
+            tx.send(
+                ToBackend::UpdateBackendList {
+                    mod_list: self
+                        .mod_list
+                        .iter()
+                        .map(FileCard::mod_file)
+                        .cloned()
+                        .collect(),
+                }
+                .into(),
+            )
             .unwrap();
 
-            tx.send(ToBackend::Shutdown).unwrap();
+            tx.send(ToBackend::Shutdown.into()).unwrap();
         }
     }
 }
 
 impl MCubedAppUI {
+    /// Select the game the mods folder was found to be loaded by, as far as the version list allows
+    fn select_inferred_game(&mut self) {
+        if let Some(modloader) = self.inferred_game.modloader {
+            self.selected_modloader = modloader;
+        }
+
+        if let Some(game_version) = &self.inferred_game.game_version {
+            if let Some(version) = self
+                .game_version_list
+                .iter()
+                .find(|version| &version.id == game_version)
+            {
+                self.selected_version = Some(version.clone());
+            }
+        }
+    }
+
+    /// Have the files checked against the selected game version and loader whenever they change
+    fn check_compatibility(&mut self) {
+        let selected = self
+            .selected_version
+            .as_ref()
+            .map(|version| (version.id.clone(), self.selected_modloader));
+
+        if selected == self.compatibility_checked_for {
+            return;
+        }
+
+        if let (Some((game_version, modloader)), Some(tx)) = (&selected, &self.front_tx) {
+            tx.send(
+                ToBackend::CheckCompatibility {
+                    game_version: game_version.clone(),
+                    modloader: *modloader,
+                }
+                .into(),
+            )
+            .unwrap();
+        }
+
+        self.compatibility_checked_for = selected;
+    }
+
+    /// Let the prompts know a request did not go through, in case they are waiting on it
+    fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        self.version_browser.request_failed(ctx, id);
+        self.changelog_prompt.request_failed(ctx, id);
+        self.details_prompt.request_failed(ctx, id);
+        self.update_all_prompt.request_failed(ctx, id);
+        self.mod_search.request_failed(ctx, id);
+        self.instance_picker.request_failed(ctx, id);
+    }
+
     fn render_side_panel(&mut self, ctx: &Context) -> InnerResponse<()> {
         SidePanel::left("options_panel")
             .frame(THEME.default_panel_frame)
@@ -185,6 +368,8 @@ impl MCubedAppUI {
                             .selected_text(
                                 if let Some(selected_value) = self.selected_version.as_ref() {
                                     selected_value.id.as_str()
+                                } else if self.game_version_list_unavailable {
+                                    "Unavailable"
                                 } else if self.game_version_list.is_empty() {
                                     "Loading..."
                                 } else {
@@ -201,6 +386,27 @@ impl MCubedAppUI {
                                     );
                                 }
                             });
+
+                        if self.game_version_list_unavailable {
+                            if ui.small_button("Retry").clicked() {
+                                if let Some(tx) = &self.front_tx {
+                                    tx.send(ToBackend::GetVersionMetadata.into()).unwrap();
+                                    self.game_version_list_unavailable = false;
+                                }
+                            }
+                        } else if self.game_version_list_stale {
+                            ui.label(RichText::new("Cached").color(THEME.colors.gray))
+                                .on_hover_text(
+                                "The version list could not be refreshed and may be out of date",
+                            );
+                        } else if let (Some(selected), Some(source)) =
+                            (&self.selected_version, &self.inferred_game.source)
+                        {
+                            if self.inferred_game.game_version.as_ref() == Some(&selected.id) {
+                                ui.label(RichText::new("Detected").color(THEME.colors.gray))
+                                    .on_hover_text(format!("Read from {}", source));
+                            }
+                        }
                     });
                 });
 
@@ -224,11 +430,14 @@ impl MCubedAppUI {
                         if ui.button("Fetch Mod").clicked() {
                             if let Some(tx) = &self.front_tx {
                                 if let Some(version) = &self.selected_version {
-                                    tx.send(ToBackend::AddMod {
-                                        modrinth_id: self.add_mod_buf.clone(),
-                                        game_version: version.id.clone(),
-                                        modloader: self.selected_modloader,
-                                    })
+                                    tx.send(
+                                        ToBackend::AddMod {
+                                            modrinth_id: self.add_mod_buf.clone(),
+                                            game_version: version.id.clone(),
+                                            modloader: self.selected_modloader,
+                                        }
+                                        .into(),
+                                    )
                                     .unwrap();
                                 };
                             }
@@ -239,6 +448,16 @@ impl MCubedAppUI {
                         ui.radio_value(&mut self.selected_modloader, ModLoader::Forge, "Forge");
                         ui.radio_value(&mut self.selected_modloader, ModLoader::Fabric, "Fabric");
                     });
+
+                    if ui
+                        .add_sized(
+                            Vec2::new(ui.available_width(), 0.0),
+                            Button::new("Search Modrinth"),
+                        )
+                        .clicked()
+                    {
+                        self.mod_search.open(ctx, &self.front_tx);
+                    }
                 });
 
                 ui.with_layout(Layout::bottom_up(Align::Center), |ui| {
@@ -248,17 +467,20 @@ impl MCubedAppUI {
 
                         if rescan_folder_button_res.clicked() {
                             if let Some(tx) = &self.front_tx {
-                                tx.send(ToBackend::UpdateBackendList {
-                                    mod_list: self
-                                        .mod_list
-                                        .iter()
-                                        .map(FileCard::mod_file)
-                                        .cloned()
-                                        .collect(),
-                                })
+                                tx.send(
+                                    ToBackend::UpdateBackendList {
+                                        mod_list: self
+                                            .mod_list
+                                            .iter()
+                                            .map(FileCard::mod_file)
+                                            .cloned()
+                                            .collect(),
+                                    }
+                                    .into(),
+                                )
                                 .unwrap();
 
-                                tx.send(ToBackend::ScanFolder).unwrap();
+                                tx.send(ToBackend::ScanFolder.into()).unwrap();
                             }
                         };
 
@@ -266,10 +488,13 @@ impl MCubedAppUI {
                         if refresh_button_res.clicked() {
                             if let Some(tx) = &self.front_tx {
                                 if let Some(version) = &self.selected_version {
-                                    self.backend_context.checking_for_updates = true;
-                                    tx.send(ToBackend::CheckForUpdates {
-                                        game_version: version.id.clone(),
-                                    })
+                                    tx.send(
+                                        ToBackend::CheckForUpdates {
+                                            game_version: version.id.clone(),
+                                            modloader: self.selected_modloader,
+                                        }
+                                        .into(),
+                                    )
                                     .unwrap();
                                 }
                             }
@@ -294,56 +519,53 @@ impl MCubedAppUI {
                 );
 
                 ui.horizontal(|ui| {
-                    if self.backend_context.checking_for_updates {
-                        ui.horizontal(|ui| {
-                            Spinner::new().size(14.0).ui(ui);
-                            ui.label("Checking for updates");
-                        });
-                    };
+                    self.backend_context.progress_panel.show(ui, &self.front_tx);
 
                     ui.with_layout(Layout::right_to_left(), |ui| {
                         if ui.add(button).clicked() {
                             ScreenPrompt::set_shown(ctx, "settings", true);
                         };
+
+                        if ui.button("Update all").clicked() {
+                            self.update_all_prompt.open(ctx, &self.front_tx);
+                        }
+
+                        if self.settings.offline_mode {
+                            ui.label(RichText::new("Offline").color(THEME.colors.gray))
+                                .on_hover_text(
+                                    "Only cached data is used, which may be out of date. This can be changed in the settings",
+                                );
+                        }
                     });
                 });
 
                 ui.horizontal(|ui| {
-                    ui.vertical_centered_justified(|ui| {
-                        let edit = TextEdit::singleline(&mut self.search_buf).hint_text(
-                            RichText::new("Search installed mods").color(THEME.colors.gray),
-                        );
-                        ui.add(edit);
+                    ui.with_layout(Layout::right_to_left(), |ui| {
+                        ui.checkbox(&mut self.hide_libraries, "Hide libraries")
+                            .on_hover_text("Leave out the mods that only exist for other mods to use");
+
+                        ui.vertical_centered_justified(|ui| {
+                            let edit = TextEdit::singleline(&mut self.search_buf).hint_text(
+                                RichText::new("Search installed mods").color(THEME.colors.gray),
+                            );
+                            ui.add(edit);
+                        });
                     });
                 });
 
-                if !self.backend_context.backend_errors.is_empty() {
-                    ScrollArea::vertical().show(ui, |ui| {
-                        self.backend_context.backend_errors.retain(|error| {
-                            let mut retain = true;
+                Self::render_backend_messages(
+                    &mut self.backend_context.backend_warnings,
+                    THEME.colors.warning_message,
+                    ui,
+                );
 
-                            Frame {
-                                fill: THEME.colors.error_message,
-                                inner_margin: Margin::same(6.0),
-                                rounding: THEME.rounding.big,
-                                ..Frame::default()
-                            }
-                            .show(ui, |ui| {
-                                ui.horizontal(|ui| {
-                                    ui.add(Label::new(&error.message).wrap(true))
-                                        .on_hover_text(error.error.to_string());
-                                    ui.with_layout(Layout::right_to_left(), |ui| {
-                                        if ui.button("Close").clicked() {
-                                            retain = false;
-                                        }
-                                    });
-                                });
-                            });
+                Self::render_backend_messages(
+                    &mut self.backend_context.backend_errors,
+                    THEME.colors.error_message,
+                    ui,
+                );
 
-                            retain
-                        });
-                    });
-                }
+                Self::render_duplicates(&self.duplicates, &self.front_tx, ui);
 
                 ui.vertical_centered_justified(|ui| {
                     Frame {
@@ -376,9 +598,16 @@ impl MCubedAppUI {
                             } else {
                                 ScrollArea::vertical().show(ui, |ui| {
                                     ui.style_mut().spacing.item_spacing.y = THEME.spacing.large;
-                                    for file_card in &mut self.mod_list {
-                                        file_card.show(&self.search_buf, ui, &self.front_tx);
-                                    }
+
+                                    Self::render_mod_list(
+                                        &mut self.mod_list,
+                                        &self.search_buf,
+                                        self.hide_libraries,
+                                        &self.settings,
+                                        &self.version_history,
+                                        &self.front_tx,
+                                        ui,
+                                    );
                                 });
                             }
                         }
@@ -386,6 +615,218 @@ impl MCubedAppUI {
                 });
             })
     }
+
+    /// Show the files in the folder, with the ones that are part of another mod collapsed under it
+    ///
+    /// Everything is shown flat while searching, so that no match is hidden away.
+    fn render_mod_list(
+        mod_list: &mut [FileCard],
+        search: &str,
+        hide_libraries: bool,
+        settings: &SettingsBuilder,
+        version_history: &[StoredVersion],
+        front_tx: &Option<Sender<BackendRequest>>,
+        ui: &mut Ui,
+    ) {
+        let searching = !search.is_empty();
+        let parents = Self::parent_indices(mod_list);
+
+        let shown =
+            |mod_list: &[FileCard], index: usize| !(hide_libraries && mod_list[index].is_library());
+
+        for index in 0..mod_list.len() {
+            if !shown(mod_list, index) || (parents[index].is_some() && !searching) {
+                continue;
+            }
+
+            mod_list[index].show(search, version_history, settings, ui, front_tx);
+
+            if searching {
+                continue;
+            }
+
+            let children: Vec<usize> = (0..mod_list.len())
+                .filter(|child| parents[*child] == Some(index) && shown(mod_list, *child))
+                .collect();
+
+            if children.is_empty() {
+                continue;
+            }
+
+            let parent_name = mod_list[index]
+                .mod_file()
+                .entries
+                .first()
+                .map_or("this mod", |entry| entry.display_name.as_str())
+                .to_string();
+
+            CollapsingHeader::new(format!(
+                "{} more {} part of {}",
+                children.len(),
+                if children.len() == 1 {
+                    "file is"
+                } else {
+                    "files are"
+                },
+                parent_name
+            ))
+            .id_source(("children", &mod_list[index].mod_file().path))
+            .show(ui, |ui| {
+                for child in children {
+                    mod_list[child].show(search, version_history, settings, ui, front_tx);
+                }
+            });
+        }
+    }
+
+    /// The index of the file each file is part of, by the parent it declares for Mod Menu
+    ///
+    /// Only one level is kept, so that a file whose parent is itself part of another one stays visible.
+    fn parent_indices(mod_list: &[FileCard]) -> Vec<Option<usize>> {
+        let parents: Vec<Option<usize>> = mod_list
+            .iter()
+            .enumerate()
+            .map(|(index, file_card)| {
+                let parent = file_card.mod_file().entries.first()?.parent.as_deref()?;
+
+                mod_list
+                    .iter()
+                    .enumerate()
+                    .find(|(other_index, other)| {
+                        *other_index != index
+                            && other.mod_file().entries.iter().any(|entry| {
+                                entry.id == parent || entry.provides.iter().any(|id| id == parent)
+                            })
+                    })
+                    .map(|(parent_index, _)| parent_index)
+            })
+            .collect();
+
+        parents
+            .iter()
+            .map(|parent| parent.filter(|parent| parents[*parent].is_none()))
+            .collect()
+    }
+
+    /// Show every mod that is in the folder more than once, with a way to keep only the newest copy
+    fn render_duplicates(
+        duplicates: &[DuplicateGroup],
+        front_tx: &Option<Sender<BackendRequest>>,
+        ui: &mut Ui,
+    ) {
+        if duplicates.is_empty() {
+            return;
+        }
+
+        ScrollArea::vertical()
+            .id_source("duplicates")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for group in duplicates {
+                    Frame {
+                        fill: THEME.colors.warning_message,
+                        inner_margin: Margin::same(6.0),
+                        rounding: THEME.rounding.big,
+                        ..Frame::default()
+                    }
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} is in {} files, the game will not start like this",
+                                    group.id,
+                                    group.copies.len()
+                                ))
+                                .strong(),
+                            );
+
+                            ui.with_layout(Layout::right_to_left(), |ui| {
+                                let older_copies = group.older_copies();
+
+                                if ui
+                                    .add_enabled(
+                                        !older_copies.is_empty(),
+                                        Button::new("Trash older copies"),
+                                    )
+                                    .on_hover_text(
+                                        "Move the older files of the mod itself to the trash, files that provide or bundle it are kept",
+                                    )
+                                    .clicked()
+                                {
+                                    if let Some(tx) = &front_tx {
+                                        for copy in older_copies {
+                                            tx.send(
+                                                ToBackend::DeleteMod {
+                                                    path: copy.path.clone(),
+                                                }
+                                                .into(),
+                                            )
+                                            .unwrap();
+                                        }
+                                    }
+                                }
+                            });
+                        });
+
+                        for (index, copy) in group.copies.iter().enumerate() {
+                            let file_name = copy
+                                .path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+
+                            let how = match copy.claim {
+                                IdClaim::Own => String::new(),
+                                IdClaim::Provided => format!(", provided by {}", copy.display_name),
+                                IdClaim::Bundled => format!(", bundled in {}", copy.display_name),
+                            };
+
+                            let newest = if index == 0 { " (newest)" } else { "" };
+
+                            ui.label(format!(
+                                "{} {}{} in {}{}",
+                                group.id, copy.version, newest, file_name, how
+                            ));
+                        }
+                    });
+                }
+            });
+    }
+
+    /// Show a closable frame for every message in `messages`
+    fn render_backend_messages(messages: &mut Vec<BackendError>, fill: Color32, ui: &mut Ui) {
+        if messages.is_empty() {
+            return;
+        }
+
+        ScrollArea::vertical().id_source(fill).show(ui, |ui| {
+            messages.retain(|message| {
+                let mut retain = true;
+
+                Frame {
+                    fill,
+                    inner_margin: Margin::same(6.0),
+                    rounding: THEME.rounding.big,
+                    ..Frame::default()
+                }
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(&message.message).wrap(true))
+                            .on_hover_text(message.error.to_string());
+                        ui.with_layout(Layout::right_to_left(), |ui| {
+                            if ui.button("Close").clicked() {
+                                retain = false;
+                            }
+                        });
+                    });
+                });
+
+                retain
+            });
+        });
+    }
 }
 
 impl MCubedAppUI {
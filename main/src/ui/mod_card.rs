@@ -1,24 +1,32 @@
 use std::collections::HashMap;
 
 use back::{
-    messages::ToBackend,
-    mod_file::{CurrentSource, FileState, ModEntry, ModFile, ModLoader},
+    messages::{BackendRequest, ToBackend},
+    mod_file::{CurrentSource, FileState, ModEntry, ModFile, ModLoader, Pin},
+    settings::SettingsBuilder,
+    versions::StoredVersion,
+    VersionType,
 };
 use crossbeam_channel::Sender;
 use eframe::{
     egui::{
-        collapsing_header, style::Margin, ComboBox, Context, Frame, ImageButton, Layout, Response,
-        Sense, Ui,
+        collapsing_header, style::Margin, ComboBox, Context, Frame, Image, ImageButton, Layout,
+        Response, Sense, Ui,
     },
     emath::Vec2,
     epaint::{ColorImage, TextureHandle},
 };
 
-use super::{misc, text_utils, ICON_RESIZE_QUALITY, IMAGES, THEME};
+use super::{
+    changelog_prompt::ChangelogPrompt, details_prompt::DetailsPrompt, misc, text_utils,
+    version_browser::VersionBrowser, ICON_RESIZE_QUALITY, IMAGES, THEME,
+};
 
 pub struct FileCard {
     mod_file: ModFile,
     mod_icons: HashMap<String, TextureHandle>,
+    /// The icon of the Modrinth project, used when the files have none or remote metadata is preferred
+    project_icon: Option<TextureHandle>,
 }
 
 impl FileCard {
@@ -42,9 +50,29 @@ impl FileCard {
             entry.icon = None;
         }
 
+        let project = mod_file
+            .data
+            .sources
+            .modrinth
+            .as_mut()
+            .and_then(|modrinth_data| modrinth_data.project.as_mut());
+
+        let project_icon = project.and_then(|project| {
+            let image_raw = project.icon.take()?;
+
+            Some(ctx.load_texture(
+                format!("{}project", mod_file.hashes.sha1),
+                ColorImage::from_rgba_unmultiplied(
+                    [ICON_RESIZE_QUALITY as usize, ICON_RESIZE_QUALITY as usize],
+                    image_raw.as_slice(),
+                ),
+            ))
+        });
+
         Self {
             mod_file,
             mod_icons,
+            project_icon,
         }
     }
 
@@ -52,14 +80,27 @@ impl FileCard {
         &self.mod_file
     }
 
+    /// Whether the file only holds mods that exist for other mods to use
+    pub fn is_library(&self) -> bool {
+        !self.mod_file.entries.is_empty() && self.mod_file.entries.iter().all(ModEntry::is_library)
+    }
+
     pub fn show(
         &mut self,
         current_search: &str,
+        version_history: &[StoredVersion],
+        settings: &SettingsBuilder,
         ui: &mut Ui,
-        front_tx: &Option<Sender<ToBackend>>,
+        front_tx: &Option<Sender<BackendRequest>>,
     ) {
         let mod_file = &mut self.mod_file;
 
+        let history_key = mod_file.history_key();
+        let previous_versions: Vec<&StoredVersion> = version_history
+            .iter()
+            .filter(|version| version.key == history_key)
+            .collect();
+
         for entry in mod_file.entries.clone() {
             // Skip the entries that are not within the filtered list
             if !entry
@@ -71,9 +112,24 @@ impl FileCard {
             }
 
             let key = format!("{}{}", mod_file.hashes.sha1, entry.id);
-            let mod_icon = self.mod_icons.get(&key);
+            let entry_icon = self.mod_icons.get(&key);
+            let project_icon = self.project_icon.as_ref();
 
-            ModCard::show(mod_file, &entry, ui, front_tx, mod_icon);
+            let mod_icon = if settings.prefer_remote_metadata {
+                project_icon.or(entry_icon)
+            } else {
+                entry_icon.or(project_icon)
+            };
+
+            ModCard::show(
+                mod_file,
+                &entry,
+                &previous_versions,
+                settings,
+                ui,
+                front_tx,
+                mod_icon,
+            );
         }
     }
 }
@@ -84,8 +140,10 @@ impl ModCard {
     pub fn show(
         mod_file: &mut ModFile,
         mod_entry: &ModEntry,
+        previous_versions: &[&StoredVersion],
+        settings: &SettingsBuilder,
         ui: &mut Ui,
-        front_tx: &Option<Sender<ToBackend>>,
+        front_tx: &Option<Sender<BackendRequest>>,
         mod_icon: Option<&TextureHandle>,
     ) {
         let mut state = collapsing_header::CollapsingState::load_with_default_open(
@@ -104,13 +162,27 @@ impl ModCard {
         state.show_body_indented(&header_res, ui, |ui| {
             ui.spacing_mut().item_spacing.y = THEME.spacing.small;
 
+            let project = mod_file
+                .data
+                .sources
+                .modrinth
+                .as_ref()
+                .and_then(|modrinth_data| modrinth_data.project.clone());
+
             mod_info_text("Version:", &mod_entry.version, ui);
 
-            mod_info_text(
-                "Description:",
-                mod_entry.description.as_deref().unwrap_or("None"),
-                ui,
-            );
+            let remote_summary = project
+                .as_ref()
+                .map(|project| project.summary.as_str())
+                .filter(|summary| !summary.is_empty());
+
+            let description = if settings.prefer_remote_metadata {
+                remote_summary.or_else(|| mod_entry.own_description())
+            } else {
+                mod_entry.own_description().or(remote_summary)
+            };
+
+            mod_info_text("Description:", description.unwrap_or("None"), ui);
 
             mod_info_text(
                 "Authors:",
@@ -118,15 +190,233 @@ impl ModCard {
                 ui,
             );
 
+            if !mod_entry.badges.is_empty() {
+                mod_info_text("Badges:", mod_entry.badges.join(", "), ui);
+            }
+
+            if let Some(project) = project
+                .as_ref()
+                .filter(|project| !project.categories.is_empty())
+            {
+                mod_info_text("Categories:", project.categories.join(", "), ui);
+            }
+
             mod_info_text("Mod path:", mod_file.path.display().to_string(), ui);
+
+            let mut links: Vec<(String, &str)> = mod_entry
+                .links
+                .iter()
+                .map(|(key, url)| (link_label(key), url.as_str()))
+                .collect();
+
+            // The project often links to the same places as the manifest
+            for (name, url) in project.iter().flat_map(|project| &project.links) {
+                if !links.iter().any(|(_, other)| other == url) {
+                    links.push((name.clone(), url));
+                }
+            }
+
+            if !links.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(
+                        text_utils::mod_card_data_header("Links:").color(THEME.colors.lighter_gray),
+                    );
+
+                    for (name, url) in links {
+                        ui.hyperlink_to(text_utils::mod_card_data_text(name), url)
+                            .on_hover_text(url);
+                    }
+                });
+            }
+
+            Self::render_pin(mod_file, ui, front_tx);
+
+            if mod_file.data.sources.modrinth.is_some() {
+                Self::render_release_channel(
+                    mod_file,
+                    settings.modrinth_version_type,
+                    ui,
+                    front_tx,
+                );
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(text_utils::update_button_text("Details"))
+                    .clicked()
+                {
+                    DetailsPrompt::open(ui.ctx(), mod_file, front_tx);
+                }
+
+                if mod_file.data.sources.modrinth.is_some()
+                    && ui
+                        .button(text_utils::update_button_text("Browse versions"))
+                        .clicked()
+                {
+                    VersionBrowser::open(ui.ctx(), mod_file, front_tx);
+                }
+            });
+
+            if !previous_versions.is_empty() {
+                ui.label(
+                    text_utils::mod_card_data_header("Previous versions:")
+                        .color(THEME.colors.lighter_gray),
+                );
+
+                for version in previous_versions {
+                    ui.horizontal(|ui| {
+                        ui.label(text_utils::mod_card_data_text(format!(
+                            "{} ({})",
+                            version.version, version.file_name
+                        )));
+
+                        if ui
+                            .button(text_utils::update_button_text("Roll back"))
+                            .clicked()
+                        {
+                            if let Some(tx) = &front_tx {
+                                tx.send(
+                                    ToBackend::RollbackMod {
+                                        mod_file: Box::new(mod_file.clone()),
+                                        sha1: version.hashes.sha1.clone(),
+                                    }
+                                    .into(),
+                                )
+                                .unwrap();
+                            }
+                        }
+                    });
+                }
+            }
         });
     }
 
+    fn render_pin(mod_file: &ModFile, ui: &mut Ui, front_tx: &Option<Sender<BackendRequest>>) {
+        let mut new_pin = None;
+
+        ui.horizontal(|ui| {
+            ui.label(text_utils::mod_card_data_header("Pin:").color(THEME.colors.lighter_gray));
+
+            match &mod_file.data.pin {
+                None => {
+                    ui.label(text_utils::mod_card_data_text("None"));
+
+                    if ui
+                        .button(text_utils::update_button_text("Pin to this file"))
+                        .clicked()
+                    {
+                        new_pin = Some(Some(Pin::CurrentFile));
+                    }
+
+                    let latest_version_id = mod_file
+                        .data
+                        .sources
+                        .modrinth
+                        .as_ref()
+                        .and_then(|modrinth| modrinth.latest_version_id.clone());
+
+                    if let Some(version_id) = latest_version_id {
+                        if mod_file.data.state == FileState::Outdated
+                            && ui
+                                .button(text_utils::update_button_text("Ignore this update"))
+                                .clicked()
+                        {
+                            new_pin = Some(Some(Pin::IgnoreVersion { version_id }));
+                        }
+                    }
+                }
+                Some(pin) => {
+                    let text = match pin {
+                        Pin::CurrentFile => "Pinned to this file".to_string(),
+                        Pin::IgnoreVersion { version_id } => {
+                            format!("Ignoring version {}", version_id)
+                        }
+                    };
+
+                    ui.label(text_utils::mod_card_data_text(text));
+
+                    if ui.button(text_utils::update_button_text("Unpin")).clicked() {
+                        new_pin = Some(None);
+                    }
+                }
+            }
+        });
+
+        if let Some(pin) = new_pin {
+            if let Some(tx) = &front_tx {
+                tx.send(
+                    ToBackend::SetPin {
+                        sha1: mod_file.hashes.sha1.clone(),
+                        pin,
+                    }
+                    .into(),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn render_release_channel(
+        mod_file: &ModFile,
+        default_channel: VersionType,
+        ui: &mut Ui,
+        front_tx: &Option<Sender<BackendRequest>>,
+    ) {
+        let mut new_channel = None;
+        let current = mod_file.data.release_channel;
+
+        ui.horizontal(|ui| {
+            ui.label(
+                text_utils::mod_card_data_header("Release type:").color(THEME.colors.lighter_gray),
+            )
+            .on_hover_text(
+                "The minimum level of stability updates to this mod should be marked with",
+            );
+
+            let default_text = format!("Default ({:?})", default_channel);
+
+            ComboBox::from_id_source(ui.id().with(&mod_file.hashes.sha1))
+                .icon(misc::combobox_icon_fn)
+                .selected_text(
+                    current.map_or(default_text.clone(), |channel| format!("{:?}", channel)),
+                )
+                .show_ui(ui, |ui| {
+                    let options = [
+                        (None, default_text),
+                        (Some(VersionType::Release), "Release".to_string()),
+                        (Some(VersionType::Beta), "Beta".to_string()),
+                        (Some(VersionType::Alpha), "Alpha".to_string()),
+                    ];
+
+                    for (channel, text) in options {
+                        if ui.selectable_label(current == channel, text).clicked()
+                            && current != channel
+                        {
+                            new_channel = Some(channel);
+                        }
+                    }
+                });
+        });
+
+        if let Some(channel) = new_channel {
+            if let Some(tx) = &front_tx {
+                tx.send(
+                    ToBackend::SetReleaseChannel {
+                        sha1: mod_file.hashes.sha1.clone(),
+                        channel,
+                    }
+                    .into(),
+                )
+                .unwrap();
+            }
+        }
+    }
+
     pub fn render_header(
         mod_entry: &ModEntry,
         mod_file: &mut ModFile,
         ui: &mut Ui,
-        front_tx: &Option<Sender<ToBackend>>,
+        front_tx: &Option<Sender<BackendRequest>>,
         mod_icon: Option<&TextureHandle>,
     ) -> Response {
         let images = IMAGES.lock();
@@ -142,7 +432,7 @@ impl ModCard {
 
                 ui.style_mut().spacing.item_spacing = Vec2::splat(0.0);
 
-                Frame {
+                let status_res = Frame {
                     inner_margin: Margin::symmetric(6.0, 0.0),
                     fill: THEME.colors.mod_card.mod_status_icon_background,
                     ..Frame::default()
@@ -166,9 +456,38 @@ impl ModCard {
                             images.mod_status_ok.as_ref().unwrap().id(),
                             THEME.image_size.mod_card_status,
                         ),
+                        FileState::Pinned => ui.add(
+                            Image::new(
+                                images.mod_status_ok.as_ref().unwrap().id(),
+                                THEME.image_size.mod_card_status,
+                            )
+                            .tint(THEME.colors.mod_card.pinned_status),
+                        ),
                     };
                 });
 
+                let stale = mod_file
+                    .data
+                    .sources
+                    .modrinth
+                    .as_ref()
+                    .map_or(false, |modrinth_data| modrinth_data.stale);
+
+                let hover_text = match (&mod_file.data.state_reason, stale) {
+                    (Some(reason), _) => Some(reason.as_str()),
+                    (None, true) => Some(
+                        "Checked using cached data that could not be refreshed, it may be out of date",
+                    ),
+                    (None, false) if mod_file.data.state == FileState::Local => Some(
+                        "Not found on Modrinth, the mod declares to work on the selected game version or does not say",
+                    ),
+                    (None, false) => None,
+                };
+
+                if let Some(hover_text) = hover_text {
+                    status_res.response.on_hover_text(hover_text);
+                }
+
                 ui.add_space(THEME.spacing.medium);
 
                 Frame {
@@ -327,9 +646,12 @@ impl ModCard {
 
                     if ui.add(button).clicked() {
                         if let Some(tx) = &front_tx {
-                            tx.send(ToBackend::DeleteMod {
-                                path: mod_file.path.clone(),
-                            })
+                            tx.send(
+                                ToBackend::DeleteMod {
+                                    path: mod_file.path.clone(),
+                                }
+                                .into(),
+                            )
                             .unwrap();
                         }
                     };
@@ -341,12 +663,7 @@ impl ModCard {
                             .button(text_utils::update_button_text("Update"))
                             .clicked()
                     {
-                        if let Some(tx) = &front_tx {
-                            tx.send(ToBackend::UpdateMod {
-                                mod_file: Box::new(mod_file.clone()),
-                            })
-                            .unwrap();
-                        }
+                        ChangelogPrompt::open(ui.ctx(), mod_file, front_tx);
                     }
                 });
             });
@@ -367,3 +684,13 @@ fn mod_info_text(header: impl Into<String>, body: impl Into<String>, ui: &mut Ui
         ui.label(text_utils::mod_card_data_text(body));
     });
 }
+
+/// A readable name for a Mod Menu link key (Ex. `modmenu.github_sponsors` becomes "Github sponsors")
+fn link_label(key: &str) -> String {
+    let name = key.rsplit('.').next().unwrap_or(key).replace('_', " ");
+
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
@@ -0,0 +1,320 @@
+use std::collections::{HashMap, HashSet};
+
+use back::{
+    messages::{BackendRequest, ModSide, RequestId, SearchHit, SearchPage, SearchQuery, ToBackend},
+    mod_file::ModLoader,
+};
+use crossbeam_channel::Sender;
+use eframe::{
+    egui::{
+        Button, Checkbox, CollapsingHeader, ComboBox, Context, Label, Layout, RichText, ScrollArea,
+        Spinner, TextEdit, Ui, Widget,
+    },
+    emath::{Align, Vec2},
+    epaint::{ColorImage, TextureHandle},
+};
+
+use super::{
+    misc, pending_request::PendingRequest, text_utils, widgets::screen_prompt::ScreenPrompt,
+    ICON_RESIZE_QUALITY, IMAGES, THEME,
+};
+
+/// Finds mods on Modrinth and adds them to the mods folder
+#[derive(Default)]
+pub struct ModSearch {
+    query_buf: String,
+    side: Option<ModSide>,
+    /// Every category that can be searched by, fetched the first time the prompt is opened
+    categories: Vec<String>,
+    selected_categories: Vec<String>,
+    results: Option<SearchPage>,
+    icons: HashMap<String, TextureHandle>,
+    /// The projects that were added since the prompt was opened
+    added: HashSet<String>,
+    searching: bool,
+}
+
+impl ModSearch {
+    pub const PROMPT_NAME: &'static str = "mod_search";
+
+    pub fn open(&mut self, ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            if self.categories.is_empty() {
+                tx.send(ToBackend::GetSearchCategories.into()).unwrap();
+            }
+
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, true);
+        }
+    }
+
+    pub fn set_results(&mut self, ctx: &Context, id: RequestId, page: SearchPage) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.icons = page
+                .hits
+                .iter()
+                .filter_map(|hit| {
+                    let icon = hit.icon.as_ref()?;
+                    let texture = ctx.load_texture(
+                        format!("search-{}", hit.project_id),
+                        ColorImage::from_rgba_unmultiplied(
+                            [ICON_RESIZE_QUALITY as usize, ICON_RESIZE_QUALITY as usize],
+                            icon.as_slice(),
+                        ),
+                    );
+
+                    Some((hit.project_id.clone(), texture))
+                })
+                .collect();
+
+            self.results = Some(page);
+            self.searching = false;
+        }
+    }
+
+    pub fn set_categories(&mut self, categories: Vec<String>) {
+        self.categories = categories;
+    }
+
+    /// Stop showing the search as running if it did not go through, keeping the previous results
+    pub fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.searching = false;
+        }
+    }
+
+    /// Show the prompt, searching for and adding mods for `game_version` and `modloader`
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        front_tx: &Option<Sender<BackendRequest>>,
+        game_version: Option<&str>,
+        modloader: ModLoader,
+    ) {
+        let mut close = false;
+        let mut search_page = None;
+
+        ScreenPrompt::new(Self::PROMPT_NAME).show(ctx, |ui, state| {
+            let size = ui.available_size() - Vec2::splat(50.0);
+            ui.set_max_size(size);
+
+            ui.heading("Search Modrinth");
+
+            ui.horizontal(|ui| {
+                let edit_res = ui.add_sized(
+                    Vec2::new(size.x * 0.5, ui.available_height()),
+                    TextEdit::singleline(&mut self.query_buf)
+                        .hint_text(RichText::new("Search mods").color(THEME.colors.gray)),
+                );
+
+                let submitted =
+                    edit_res.lost_focus() && ui.input().key_pressed(eframe::egui::Key::Enter);
+
+                if ui.button("Search").clicked() || submitted {
+                    search_page = Some(0);
+                }
+
+                if self.searching {
+                    Spinner::new().size(14.0).ui(ui);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(
+                    text_utils::mod_card_data_header(format!(
+                        "For {} on {:?}",
+                        game_version.unwrap_or("any version"),
+                        modloader
+                    ))
+                    .color(THEME.colors.lighter_gray),
+                );
+
+                ui.add_space(THEME.spacing.medium);
+
+                ui.label("Side");
+                ComboBox::from_id_source(ui.id().with("search-side"))
+                    .icon(misc::combobox_icon_fn)
+                    .selected_text(match self.side {
+                        None => "Any",
+                        Some(ModSide::Client) => "Client",
+                        Some(ModSide::Server) => "Server",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.side, None, "Any");
+                        ui.selectable_value(&mut self.side, Some(ModSide::Client), "Client");
+                        ui.selectable_value(&mut self.side, Some(ModSide::Server), "Server");
+                    });
+            });
+
+            if !self.categories.is_empty() {
+                CollapsingHeader::new(format!(
+                    "Categories ({} selected)",
+                    self.selected_categories.len()
+                ))
+                .id_source(ui.id().with("search-categories"))
+                .show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for category in &self.categories {
+                            let mut selected = self.selected_categories.contains(category);
+
+                            if Checkbox::new(&mut selected, category).ui(ui).changed() {
+                                if selected {
+                                    self.selected_categories.push(category.clone());
+                                } else {
+                                    self.selected_categories.retain(|other| other != category);
+                                }
+                            }
+                        }
+                    });
+                });
+            }
+
+            ui.separator();
+
+            if let Some(results) = &self.results {
+                ScrollArea::vertical()
+                    .max_height(size.y - 150.0)
+                    .show(ui, |ui| {
+                        ui.set_width(size.x);
+
+                        if results.hits.is_empty() {
+                            ui.label("No mods were found");
+                        }
+
+                        for hit in &results.hits {
+                            let added = self.added.contains(&hit.project_id);
+
+                            if Self::show_hit(hit, self.icons.get(&hit.project_id), added, ui) {
+                                if let Some(tx) = &front_tx {
+                                    if let Some(game_version) = game_version {
+                                        tx.send(
+                                            ToBackend::AddMod {
+                                                modrinth_id: hit.project_id.clone(),
+                                                game_version: game_version.to_string(),
+                                                modloader,
+                                            }
+                                            .into(),
+                                        )
+                                        .unwrap();
+
+                                        self.added.insert(hit.project_id.clone());
+                                    }
+                                }
+                            }
+
+                            ui.separator();
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(results.page > 0, Button::new("Previous"))
+                        .clicked()
+                    {
+                        search_page = Some(results.page - 1);
+                    }
+
+                    ui.label(format!(
+                        "Page {} of {}",
+                        results.page + 1,
+                        results.total_pages.max(1)
+                    ));
+
+                    if ui
+                        .add_enabled(results.page + 1 < results.total_pages, Button::new("Next"))
+                        .clicked()
+                    {
+                        search_page = Some(results.page + 1);
+                    }
+                });
+            }
+
+            ui.add_space(THEME.spacing.medium);
+
+            ui.with_layout(Layout::bottom_up(Align::Center), |ui| {
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+
+            if close {
+                state.shown(false);
+            }
+        });
+
+        if let Some(page) = search_page {
+            if let Some(tx) = &front_tx {
+                let query = SearchQuery {
+                    text: self.query_buf.clone(),
+                    game_version: game_version.map(ToString::to_string),
+                    modloader,
+                    categories: self.selected_categories.clone(),
+                    side: self.side,
+                    page,
+                };
+
+                // A new search replaces the one that is running
+                PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+                PendingRequest::send(ctx, Self::PROMPT_NAME, tx, ToBackend::SearchMods { query });
+                self.searching = true;
+            }
+        }
+
+        if close {
+            PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+
+            // The filters and the categories are kept for the next time
+            self.results = None;
+            self.icons.clear();
+            self.added.clear();
+            self.searching = false;
+        }
+    }
+
+    /// Returns whether the mod was chosen to be added
+    fn show_hit(hit: &SearchHit, icon: Option<&TextureHandle>, added: bool, ui: &mut Ui) -> bool {
+        let mut add = false;
+
+        ui.horizontal(|ui| {
+            match icon {
+                Some(icon) => ui.image(icon.id(), THEME.image_size.mod_card_icon),
+                None => ui.image(
+                    IMAGES.lock().modrinth.as_ref().unwrap().id(),
+                    THEME.image_size.mod_card_icon,
+                ),
+            };
+
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(&hit.title).strong());
+                    ui.label(RichText::new(format!("by {}", hit.author)).color(THEME.colors.gray));
+
+                    ui.with_layout(Layout::right_to_left(), |ui| {
+                        let text = if added { "Added" } else { "Add" };
+
+                        if ui
+                            .add_enabled(!added, Button::new(text_utils::update_button_text(text)))
+                            .clicked()
+                        {
+                            add = true;
+                        }
+                    });
+                });
+
+                ui.add(Label::new(text_utils::mod_card_data_text(&hit.description)).wrap(true));
+
+                let mut details = format!("{} downloads", hit.downloads);
+                if !hit.categories.is_empty() {
+                    details.push_str(&format!(" | {}", hit.categories.join(", ")));
+                }
+
+                ui.label(text_utils::mod_card_data_text(details).color(THEME.colors.lighter_gray));
+            });
+        });
+
+        add
+    }
+}
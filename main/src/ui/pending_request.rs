@@ -0,0 +1,54 @@
+use back::messages::{BackendRequest, RequestId, ToBackend};
+use crossbeam_channel::Sender;
+use eframe::egui::{Context, Id};
+
+/// Keeps track of the request a prompt is waiting on, so that responses to older requests can be ignored and the request cancelled
+pub struct PendingRequest;
+
+impl PendingRequest {
+    const BASE_ID: &'static str = "pending_request";
+
+    /// Send a request on behalf of a prompt, replacing the one it was waiting on
+    pub fn send(ctx: &Context, prompt_name: &str, tx: &Sender<BackendRequest>, message: ToBackend) {
+        let request = BackendRequest::new(message);
+
+        ctx.memory()
+            .data
+            .insert_temp(Self::id(prompt_name), request.id);
+
+        tx.send(request).unwrap();
+    }
+
+    /// Whether a response belongs to the request the prompt is waiting on
+    pub fn is_pending(ctx: &Context, prompt_name: &str, id: RequestId) -> bool {
+        ctx.memory()
+            .data
+            .get_temp::<RequestId>(Self::id(prompt_name))
+            == Some(id)
+    }
+
+    /// Stop waiting on the request once its response has arrived
+    pub fn clear(ctx: &Context, prompt_name: &str) {
+        ctx.memory().data.remove::<RequestId>(Self::id(prompt_name));
+    }
+
+    /// Stop waiting on the request, cancelling it if it is still running
+    pub fn cancel(ctx: &Context, prompt_name: &str, front_tx: &Option<Sender<BackendRequest>>) {
+        let id = ctx
+            .memory()
+            .data
+            .get_temp::<RequestId>(Self::id(prompt_name));
+
+        if let Some(id) = id {
+            ctx.memory().data.remove::<RequestId>(Self::id(prompt_name));
+
+            if let Some(tx) = &front_tx {
+                tx.send(ToBackend::Cancel { id }.into()).unwrap();
+            }
+        }
+    }
+
+    fn id(prompt_name: &str) -> Id {
+        Id::new(Self::BASE_ID).with(prompt_name)
+    }
+}
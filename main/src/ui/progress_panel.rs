@@ -0,0 +1,97 @@
+use back::messages::{BackendRequest, JobPhase, JobProgress, ToBackend};
+use crossbeam_channel::Sender;
+use eframe::egui::{ProgressBar, Spinner, Ui, Widget};
+
+use super::{text_utils, THEME};
+
+/// Shows the progress of every job currently running on the backend
+#[derive(Default)]
+pub struct ProgressPanel {
+    jobs: Vec<JobProgress>,
+}
+
+impl ProgressPanel {
+    /// Track the new state of a job, dropping it once it has finished
+    pub fn update(&mut self, progress: JobProgress) {
+        if progress.phase == JobPhase::Finished {
+            self.jobs.retain(|job| job.job_id != progress.job_id);
+        } else if let Some(job) = self
+            .jobs
+            .iter_mut()
+            .find(|job| job.job_id == progress.job_id)
+        {
+            *job = progress;
+        } else {
+            self.jobs.push(progress);
+        }
+    }
+
+    pub fn show(&self, ui: &mut Ui, front_tx: &Option<Sender<BackendRequest>>) {
+        if self.jobs.is_empty() {
+            return;
+        }
+
+        ui.vertical(|ui| {
+            ui.spacing_mut().item_spacing.y = THEME.spacing.small;
+
+            for job in &self.jobs {
+                Self::show_job(job, ui, front_tx);
+            }
+        });
+    }
+
+    fn show_job(job: &JobProgress, ui: &mut Ui, front_tx: &Option<Sender<BackendRequest>>) {
+        ui.horizontal(|ui| {
+            let mut text = job.phase.to_string();
+
+            if job.items_total > 1 {
+                text.push_str(&format!(" ({}/{})", job.items_done, job.items_total));
+            }
+
+            if job.bytes_total > 0 {
+                text.push_str(&format!(
+                    " - {} of {}",
+                    format_size(job.bytes_done),
+                    format_size(job.bytes_total)
+                ));
+            }
+
+            match fraction(job) {
+                Some(fraction) => {
+                    ProgressBar::new(fraction)
+                        .desired_width(200.0)
+                        .show_percentage()
+                        .ui(ui);
+                }
+                None => {
+                    Spinner::new().size(14.0).ui(ui);
+                }
+            }
+
+            ui.label(text_utils::mod_card_data_text(text));
+
+            // Jobs run in the backend's main loop can not be stopped halfway
+            if job.cancellable && ui.small_button("Cancel").clicked() {
+                if let Some(tx) = &front_tx {
+                    tx.send(ToBackend::Cancel { id: job.job_id }.into())
+                        .unwrap();
+                }
+            }
+        });
+    }
+}
+
+/// How much of the current phase is done, if it can be known
+fn fraction(job: &JobProgress) -> Option<f32> {
+    if job.bytes_total > 0 && job.phase == JobPhase::Downloading {
+        Some(job.bytes_done as f32 / job.bytes_total as f32)
+    } else if job.items_total > 0 {
+        Some(job.items_done as f32 / job.items_total as f32)
+    } else {
+        None
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.2} MB", bytes as f64 / 1_000_000.0)
+}
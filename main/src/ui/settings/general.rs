@@ -1,7 +1,8 @@
-use back::settings::{SettingsBuilder, CONF};
-use eframe::egui::Ui;
+use back::{messages::BackendRequest, settings::SettingsBuilder};
+use crossbeam_channel::Sender;
+use eframe::egui::{Checkbox, ComboBox, DragValue, Ui};
 
-use crate::ui::IMAGES;
+use crate::ui::{instance_picker::InstancePicker, misc, IMAGES};
 
 use super::SettingsSection;
 
@@ -10,28 +11,176 @@ pub(super) struct GeneralSettings;
 impl SettingsSection for GeneralSettings {
     const ID: &'static str = "general";
 
-    fn show(ui: &mut Ui) {
+    fn show(
+        ui: &mut Ui,
+        settings: &mut SettingsBuilder,
+        front_tx: &Option<Sender<BackendRequest>>,
+    ) {
         Self::settings_section(ui, &IMAGES.lock().settings, "General", |ui| {
+            if !settings.profiles.is_empty() {
+                ui.label("Profile")
+                    .on_hover_text("Switch between the mods folders adopted from your launchers");
+
+                let selected_text = settings.active_profile().map_or_else(
+                    || "Custom folder".to_string(),
+                    |profile| profile.name.clone(),
+                );
+
+                let mut selected_folder = None;
+
+                ComboBox::from_id_source(ui.id().with("profile-combo"))
+                    .icon(misc::combobox_icon_fn)
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for profile in &settings.profiles {
+                            let selected = profile.mod_folder_path == settings.mod_folder_path;
+
+                            if ui.selectable_label(selected, &profile.name).clicked() && !selected {
+                                selected_folder = Some(profile.mod_folder_path.clone());
+                            }
+                        }
+                    });
+
+                if let Some(folder) = selected_folder {
+                    settings.mod_folder_path = folder;
+                    Self::submit(settings, front_tx);
+                }
+            }
+
             ui.label("Mods folder path").on_hover_text(
                 "The path to the current mods folder of your Minecraft installation",
             );
 
             if ui
-                .button(CONF.lock().mod_folder_path.display().to_string())
+                .button(settings.mod_folder_path.display().to_string())
                 .clicked()
             {
                 // This intentionally causes the UI to hang while the dialog is open, so that the user must do something before operations resume
                 let folder = rfd::FileDialog::new()
                     .set_title("Choose the mods path")
-                    .set_directory(&CONF.lock().mod_folder_path)
+                    .set_directory(&settings.mod_folder_path)
                     .pick_folder();
 
                 if let Some(folder) = folder {
-                    SettingsBuilder::from_current()
-                        .mod_folder_path(folder)
-                        .apply();
+                    settings.mod_folder_path = folder;
+                    Self::submit(settings, front_tx);
                 }
             }
+
+            if ui
+                .button("Detect installations")
+                .on_hover_text("Look for the instances of the Minecraft Launcher, Prism Launcher, MultiMC, ATLauncher and GDLauncher")
+                .clicked()
+            {
+                InstancePicker::open(ui.ctx(), front_tx);
+            }
+
+            ui.label("Stored versions per mod").on_hover_text(
+                "How many replaced versions of each mod are kept around to allow rolling back",
+            );
+
+            if ui
+                .add(DragValue::new(&mut settings.max_stored_versions).clamp_range(0..=20))
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            ui.label("Concurrent downloads").on_hover_text(
+                "How many mods are downloaded at the same time when updating all of them",
+            );
+
+            if ui
+                .add(DragValue::new(&mut settings.max_concurrent_downloads).clamp_range(1..=16))
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            ui.label("Concurrent requests").on_hover_text(
+                "How many requests can be sent to Modrinth and Mojang at the same time",
+            );
+
+            if ui
+                .add(DragValue::new(&mut settings.max_concurrent_requests).clamp_range(1..=32))
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            ui.label("Request retries").on_hover_text(
+                "How many times a request that failed because of a network or server error is tried again",
+            );
+
+            if ui
+                .add(DragValue::new(&mut settings.max_request_retries).clamp_range(0..=10))
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            ui.label("Retry delay").on_hover_text(
+                "How long to wait before retrying a request, doubled on every further attempt",
+            );
+
+            if ui
+                .add(
+                    DragValue::new(&mut settings.retry_base_delay_ms)
+                        .clamp_range(100..=10_000)
+                        .suffix(" ms"),
+                )
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            ui.label("Request timeout").on_hover_text(
+                "How long a request can take before it is given up on, downloads included",
+            );
+
+            if ui
+                .add(
+                    DragValue::new(&mut settings.request_timeout_secs)
+                        .clamp_range(5..=3_600)
+                        .suffix(" s"),
+                )
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            if ui
+                .add(Checkbox::new(
+                    &mut settings.prefer_remote_metadata,
+                    "Prefer Modrinth metadata",
+                ))
+                .on_hover_text(
+                    "Show the icon and summary of the Modrinth project of a mod over the ones in its file. Either is used when the other is missing",
+                )
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            if ui
+                .add(Checkbox::new(&mut settings.respect_rate_limits, "Wait out rate limits"))
+                .on_hover_text(
+                    "Pause requests until the rate limit resets instead of failing when a service reports it was exceeded",
+                )
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
+
+            if ui
+                .add(Checkbox::new(&mut settings.offline_mode, "Offline mode"))
+                .on_hover_text(
+                    "Work only from cached data without connecting to Modrinth or Mojang. Anything shown may be out of date",
+                )
+                .changed()
+            {
+                Self::submit(settings, front_tx);
+            }
         })
     }
 }
@@ -1,3 +1,8 @@
+use back::{
+    messages::{BackendRequest, ToBackend},
+    settings::SettingsBuilder,
+};
+use crossbeam_channel::Sender;
 use eframe::{
     egui::{collapsing_header, style::Margin, Frame, Id, Layout, Sense, Ui},
     epaint::TextureHandle,
@@ -13,10 +18,15 @@ mod modrinth;
 pub struct SettingsUi;
 
 impl SettingsUi {
-    pub fn show(ui: &mut Ui) {
+    /// Show the settings in `settings`, sending any change to the backend
+    pub fn show(
+        ui: &mut Ui,
+        settings: &mut SettingsBuilder,
+        front_tx: &Option<Sender<BackendRequest>>,
+    ) {
         ui.spacing_mut().item_spacing = THEME.spacing.widget_spacing;
-        GeneralSettings::show(ui);
-        ModrinthSettings::show(ui);
+        GeneralSettings::show(ui, settings, front_tx);
+        ModrinthSettings::show(ui, settings, front_tx);
     }
 }
 
@@ -73,5 +83,18 @@ pub(super) trait SettingsSection {
         state.show_body_indented(&header_res.response, ui, |ui| body(ui));
     }
 
-    fn show(ui: &mut Ui);
+    /// Send the changed settings to the backend, which validates and saves them before they take effect
+    fn submit(settings: &SettingsBuilder, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            tx.send(
+                ToBackend::UpdateSettings {
+                    settings: Box::new(settings.clone()),
+                }
+                .into(),
+            )
+            .unwrap();
+        }
+    }
+
+    fn show(ui: &mut Ui, settings: &mut SettingsBuilder, front_tx: &Option<Sender<BackendRequest>>);
 }
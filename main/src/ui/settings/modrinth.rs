@@ -1,7 +1,5 @@
-use back::{
-    settings::{SettingsBuilder, CONF},
-    VersionType,
-};
+use back::{messages::BackendRequest, settings::SettingsBuilder, VersionType};
+use crossbeam_channel::Sender;
 use eframe::egui::{ComboBox, Ui};
 
 use crate::ui::{misc, IMAGES};
@@ -13,29 +11,40 @@ pub(super) struct ModrinthSettings;
 impl SettingsSection for ModrinthSettings {
     const ID: &'static str = "modrinth";
 
-    fn show(ui: &mut Ui) {
+    fn show(
+        ui: &mut Ui,
+        settings: &mut SettingsBuilder,
+        front_tx: &Option<Sender<BackendRequest>>,
+    ) {
         Self::settings_section(ui, &IMAGES.lock().modrinth, "Modrinth", |ui| {
             ui.label("Base release type").on_hover_text("This indicates the minimum level of stability a version should be marked with to appear when update-checking");
 
-            let current = CONF.lock().modrinth_version_type;
+            let current = settings.modrinth_version_type;
 
             ComboBox::from_id_source(ui.id().with("version-type"))
                 .icon(misc::combobox_icon_fn)
                 .selected_text(format!("{:?}", current))
                 .show_ui(ui, |ui| {
-                    let release_res =
-                        ui.selectable_label(current == VersionType::Release, "Release");
-                    let beta_res = ui.selectable_label(current == VersionType::Beta, "Beta");
-                    let alpha_res = ui.selectable_label(current == VersionType::Alpha, "Alpha");
-                    let builder = SettingsBuilder::from_current();
-                    if release_res.clicked() {
-                        builder.modrinth_version_type(VersionType::Release).apply()
-                    } else if beta_res.clicked() {
-                        builder.modrinth_version_type(VersionType::Beta).apply()
-                    } else if alpha_res.clicked() {
-                        builder.modrinth_version_type(VersionType::Alpha).apply()
-                    }
+                    ui.selectable_value(
+                        &mut settings.modrinth_version_type,
+                        VersionType::Release,
+                        "Release",
+                    );
+                    ui.selectable_value(
+                        &mut settings.modrinth_version_type,
+                        VersionType::Beta,
+                        "Beta",
+                    );
+                    ui.selectable_value(
+                        &mut settings.modrinth_version_type,
+                        VersionType::Alpha,
+                        "Alpha",
+                    );
                 });
+
+            if settings.modrinth_version_type != current {
+                Self::submit(settings, front_tx);
+            }
         })
     }
 }
@@ -0,0 +1,237 @@
+use back::messages::{BackendRequest, PlannedUpdate, RequestId, ToBackend, UpdateSummary};
+use crossbeam_channel::Sender;
+use eframe::{
+    egui::{Button, Context, Grid, Label, ScrollArea, Spinner, Ui, Widget},
+    emath::Vec2,
+};
+
+use super::{
+    pending_request::PendingRequest, text_utils, widgets::screen_prompt::ScreenPrompt, THEME,
+};
+
+enum Stage {
+    Planning,
+    Reviewing(Vec<(bool, PlannedUpdate)>),
+    Updating,
+    Done(UpdateSummary),
+}
+
+impl Default for Stage {
+    fn default() -> Self {
+        Self::Planning
+    }
+}
+
+/// Lets the user review every pending update before applying them all at once
+#[derive(Default)]
+pub struct UpdateAllPrompt {
+    stage: Stage,
+}
+
+impl UpdateAllPrompt {
+    pub const PROMPT_NAME: &'static str = "update_all_prompt";
+
+    /// Open the prompt and request the update plan from the backend
+    pub fn open(&mut self, ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            PendingRequest::send(ctx, Self::PROMPT_NAME, tx, ToBackend::PlanUpdateAll);
+
+            self.stage = Stage::Planning;
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, true);
+        }
+    }
+
+    pub fn set_plan(&mut self, ctx: &Context, id: RequestId, plan: Vec<PlannedUpdate>) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.stage = Stage::Reviewing(plan.into_iter().map(|update| (true, update)).collect());
+        }
+    }
+
+    pub fn set_summary(&mut self, ctx: &Context, id: RequestId, summary: UpdateSummary) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.stage = Stage::Done(summary);
+        }
+    }
+
+    /// Close the prompt if the request it is waiting on did not go through
+    pub fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, false);
+
+            *self = Self::default();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        let mut close = false;
+
+        ScreenPrompt::new(Self::PROMPT_NAME).show(ctx, |ui, state| {
+            let size = ui.available_size() - Vec2::splat(50.0);
+            ui.set_max_size(size);
+
+            match &mut self.stage {
+                Stage::Planning => {
+                    loading_text("Gathering updates", ui);
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                }
+                Stage::Reviewing(plan) => {
+                    if plan.is_empty() {
+                        ui.label("Everything is up to date");
+
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    } else {
+                        let start = Self::show_plan(plan, size, ui);
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Cancel").clicked() {
+                                close = true;
+                            }
+                        });
+
+                        if start {
+                            let plan = plan
+                                .drain(..)
+                                .filter(|(selected, _)| *selected)
+                                .map(|(_, update)| update)
+                                .collect();
+
+                            if let Some(tx) = &front_tx {
+                                PendingRequest::send(
+                                    ctx,
+                                    Self::PROMPT_NAME,
+                                    tx,
+                                    ToBackend::UpdateAll { plan },
+                                );
+                            }
+
+                            self.stage = Stage::Updating;
+                        }
+                    }
+                }
+                Stage::Updating => {
+                    loading_text("Updating mods", ui);
+
+                    // Nothing is installed until every download is done, so stopping here leaves the folder untouched
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                }
+                Stage::Done(summary) => {
+                    Self::show_summary(summary, size, ui);
+
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                }
+            };
+
+            if close {
+                state.shown(false);
+            }
+        });
+
+        if close {
+            PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+
+            *self = Self::default();
+        }
+    }
+
+    /// Returns whether the selected updates should be applied
+    fn show_plan(plan: &mut [(bool, PlannedUpdate)], size: Vec2, ui: &mut Ui) -> bool {
+        ui.heading("Pending updates");
+
+        ScrollArea::vertical()
+            .max_height(size.y - 80.0)
+            .show(ui, |ui| {
+                ui.set_width(size.x);
+
+                Grid::new("update_plan").striped(true).show(ui, |ui| {
+                    for (selected, update) in plan.iter_mut() {
+                        ui.checkbox(selected, "");
+                        ui.label(text_utils::mod_card_data_header(&update.display_name));
+                        ui.label(text_utils::mod_card_data_text(format!(
+                            "{} → {}",
+                            update.old_version, update.new_version
+                        )));
+                        ui.label(text_utils::mod_card_data_text(format_size(update.size)));
+                        ui.hyperlink_to(
+                            text_utils::mod_card_data_text("Changelog"),
+                            &update.changelog_url,
+                        );
+                        ui.end_row();
+                    }
+                });
+            });
+
+        let selected: Vec<&PlannedUpdate> = plan
+            .iter()
+            .filter(|(selected, _)| *selected)
+            .map(|(_, update)| update)
+            .collect();
+        let total_size: usize = selected.iter().map(|update| update.size).sum();
+
+        ui.add_space(THEME.spacing.medium);
+
+        ui.label(format!(
+            "{} of {} updates selected ({})",
+            selected.len(),
+            plan.len(),
+            format_size(total_size)
+        ));
+
+        ui.add_enabled(!selected.is_empty(), Button::new("Update selected"))
+            .clicked()
+    }
+
+    fn show_summary(summary: &UpdateSummary, size: Vec2, ui: &mut Ui) {
+        ui.heading(format!(
+            "Updated {} mods, {} failed",
+            summary.updated.len(),
+            summary.failed.len()
+        ));
+
+        ScrollArea::vertical()
+            .max_height(size.y - 80.0)
+            .show(ui, |ui| {
+                ui.set_width(size.x);
+                ui.spacing_mut().item_spacing.y = THEME.spacing.small;
+
+                for name in &summary.updated {
+                    ui.label(text_utils::mod_card_data_text(name));
+                }
+
+                for error in &summary.failed {
+                    ui.add(
+                        Label::new(
+                            text_utils::mod_card_data_text(&error.message)
+                                .color(THEME.colors.error_message),
+                        )
+                        .wrap(true),
+                    )
+                    .on_hover_text(error.error.to_string());
+                }
+            });
+    }
+}
+
+fn loading_text(text: &str, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        Spinner::new().size(14.0).ui(ui);
+        ui.label(text);
+    });
+}
+
+fn format_size(bytes: usize) -> String {
+    format!("{:.2} MB", bytes as f64 / 1_000_000.0)
+}
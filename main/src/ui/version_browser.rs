@@ -0,0 +1,211 @@
+use back::{
+    messages::{BackendRequest, RequestId, ToBackend},
+    mod_file::ModFile,
+    ModrinthVersion,
+};
+use crossbeam_channel::Sender;
+use eframe::{
+    egui::{CollapsingHeader, Context, Label, Layout, ScrollArea, Spinner, Ui, Widget},
+    emath::{Align, Vec2},
+};
+
+use super::{
+    pending_request::PendingRequest, text_utils, widgets::screen_prompt::ScreenPrompt, THEME,
+};
+
+#[derive(Default)]
+pub struct VersionBrowser {
+    mod_file: Option<Box<ModFile>>,
+    versions: Vec<ModrinthVersion>,
+}
+
+impl VersionBrowser {
+    pub const PROMPT_NAME: &'static str = "version_browser";
+
+    /// Open the browser and request the versions of a mod from the backend
+    pub fn open(ctx: &Context, mod_file: &ModFile, front_tx: &Option<Sender<BackendRequest>>) {
+        if let Some(tx) = &front_tx {
+            PendingRequest::send(
+                ctx,
+                Self::PROMPT_NAME,
+                tx,
+                ToBackend::ListVersions {
+                    mod_file: Box::new(mod_file.clone()),
+                },
+            );
+
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, true);
+        }
+    }
+
+    pub fn set_versions(
+        &mut self,
+        ctx: &Context,
+        id: RequestId,
+        mod_file: Box<ModFile>,
+        versions: Vec<ModrinthVersion>,
+    ) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+
+            self.mod_file = Some(mod_file);
+            self.versions = versions;
+        }
+    }
+
+    /// Close the browser if the request it is waiting on did not go through
+    pub fn request_failed(&mut self, ctx: &Context, id: RequestId) {
+        if PendingRequest::is_pending(ctx, Self::PROMPT_NAME, id) {
+            PendingRequest::clear(ctx, Self::PROMPT_NAME);
+            ScreenPrompt::set_shown(ctx, Self::PROMPT_NAME, false);
+
+            *self = Self::default();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, front_tx: &Option<Sender<BackendRequest>>) {
+        let mut close = false;
+
+        ScreenPrompt::new(Self::PROMPT_NAME).show(ctx, |ui, state| {
+            let size = ui.available_size() - Vec2::splat(50.0);
+            ui.set_max_size(size);
+
+            match &self.mod_file {
+                Some(mod_file) => {
+                    let name = mod_file
+                        .entries
+                        .first()
+                        .map_or("Unknown", |entry| entry.display_name.as_str());
+                    ui.heading(format!("Versions of {}", name));
+
+                    ScrollArea::vertical()
+                        .max_height(size.y - 50.0)
+                        .show(ui, |ui| {
+                            ui.set_width(size.x);
+
+                            for version in &self.versions {
+                                if Self::show_version(version, mod_file, ui, front_tx) {
+                                    close = true;
+                                }
+                            }
+                        });
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        Spinner::new().size(14.0).ui(ui);
+                        ui.label("Fetching versions");
+                    });
+                }
+            }
+
+            ui.add_space(THEME.spacing.medium);
+
+            ui.with_layout(Layout::bottom_up(Align::Center), |ui| {
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+
+            if close {
+                state.shown(false);
+            }
+        });
+
+        if close {
+            PendingRequest::cancel(ctx, Self::PROMPT_NAME, front_tx);
+
+            *self = Self::default();
+        }
+    }
+
+    /// Returns whether a file was chosen to be installed
+    fn show_version(
+        version: &ModrinthVersion,
+        mod_file: &ModFile,
+        ui: &mut Ui,
+        front_tx: &Option<Sender<BackendRequest>>,
+    ) -> bool {
+        let mut installed = false;
+
+        let is_current = version
+            .files
+            .iter()
+            .any(|file| file.hashes.sha1.as_deref() == Some(mod_file.hashes.sha1.as_str()));
+
+        let heading = format!(
+            "{} ({:?}){}",
+            version.version_number,
+            version.version_type,
+            if is_current { " - Installed" } else { "" }
+        );
+
+        CollapsingHeader::new(heading)
+            .id_source(&version.id)
+            .show(ui, |ui| {
+                ui.spacing_mut().item_spacing.y = THEME.spacing.small;
+
+                version_info_text("Name:", &version.name, ui);
+                version_info_text("Published:", version.date_published.to_string(), ui);
+                version_info_text("Game versions:", version.game_versions.join(", "), ui);
+                version_info_text("Loaders:", version.loaders.join(", "), ui);
+
+                ui.label(
+                    text_utils::mod_card_data_header("Changelog:").color(THEME.colors.lighter_gray),
+                );
+                ui.add(
+                    Label::new(text_utils::mod_card_data_text(
+                        version.changelog.as_deref().unwrap_or("None"),
+                    ))
+                    .wrap(true),
+                );
+
+                ui.label(
+                    text_utils::mod_card_data_header("Files:").color(THEME.colors.lighter_gray),
+                );
+
+                for file in &version.files {
+                    ui.horizontal(|ui| {
+                        ui.label(text_utils::mod_card_data_text(format!(
+                            "{}{} ({:.2} MB)",
+                            file.filename,
+                            if file.primary { " [Primary]" } else { "" },
+                            file.size as f64 / 1_000_000.0
+                        )));
+
+                        let is_installed =
+                            file.hashes.sha1.as_deref() == Some(mod_file.hashes.sha1.as_str());
+
+                        if !is_installed
+                            && ui
+                                .button(text_utils::update_button_text("Install"))
+                                .clicked()
+                        {
+                            if let Some(tx) = &front_tx {
+                                tx.send(
+                                    ToBackend::InstallVersion {
+                                        mod_file: Box::new(mod_file.clone()),
+                                        version_id: version.id.clone(),
+                                        version_file: file.clone(),
+                                    }
+                                    .into(),
+                                )
+                                .unwrap();
+                            }
+
+                            installed = true;
+                        }
+                    });
+                }
+            });
+
+        installed
+    }
+}
+
+fn version_info_text(header: impl Into<String>, body: impl Into<String>, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        ui.label(text_utils::mod_card_data_header(header).color(THEME.colors.lighter_gray));
+
+        ui.label(text_utils::mod_card_data_text(body));
+    });
+}
@@ -15,6 +15,7 @@ use crate::{
 };
 
 pub const FABRIC_META_PATH: &str = "fabric.mod.json";
+const MOD_MENU_KEY: &str = "modmenu";
 
 #[derive(Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
@@ -35,6 +36,7 @@ pub struct FabricManifest {
     pub access_widener: Option<String>,
 
     //Dependency resolution
+    pub provides: Option<Vec<String>>,
     pub depends: Option<HashMap<String, DependencyVersion>>,
     pub recommends: Option<HashMap<String, DependencyVersion>>,
     pub suggests: Option<HashMap<String, DependencyVersion>>,
@@ -86,6 +88,13 @@ impl FabricManifest {
             Err(Error::IncorrectModloader)
         }
     }
+
+    /// The metadata Mod Menu reads from `custom`, [None] if there is none or it cannot be understood
+    pub fn mod_menu(&self) -> Option<ModMenu> {
+        let value = self.custom.as_ref()?.get(MOD_MENU_KEY)?;
+
+        serde_json::from_value(value.clone()).ok()
+    }
 }
 
 //* Mod loading
@@ -187,3 +196,45 @@ pub enum License {
     Single(String),
     Multiple(Vec<String>),
 }
+
+//* Mod Menu
+// https://github.com/TerraformersMC/ModMenu/wiki/API#fabricmodjson-metadata
+#[derive(Deserialize, Clone, Default)]
+pub struct ModMenu {
+    pub parent: Option<ModMenuParent>,
+    #[serde(default)]
+    pub badges: Vec<String>,
+    //The keys are translation keys, like "modmenu.discord"
+    #[serde(default)]
+    pub links: HashMap<String, String>,
+
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ModMenuParent {
+    Id(String),
+    //Describes a parent that is not an actual mod, to group mods under it
+    Object(ModMenuParentObject),
+}
+
+impl ModMenuParent {
+    pub fn id(&self) -> &str {
+        match self {
+            ModMenuParent::Id(id) => id,
+            ModMenuParent::Object(object) => &object.id,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ModMenuParentObject {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub badges: Vec<String>,
+}